@@ -0,0 +1,45 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// build
+
+// ----------------------------------------------------------------
+
+use std::env;
+use std::process::Command;
+
+// ----------------------------------------------------------------
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-check-cfg=cfg(synext_diagnostics)");
+
+    // `proc_macro::Diagnostic` is nightly-only, stable builds keep `compile_error!`
+    if env::var_os("CARGO_FEATURE_DIAGNOSTICS").is_some() && is_nightly() {
+        println!("cargo:rustc-cfg=synext_diagnostics");
+    }
+}
+
+fn is_nightly() -> bool {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.contains("nightly") || version.contains("-dev"))
+        .unwrap_or(false)
+}
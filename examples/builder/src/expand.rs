@@ -0,0 +1,172 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/builder/expand
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, LitStr, Type};
+use synext::diag::ErrorAccumulator;
+use synext::ext::IdentExt;
+use synext::model::{FieldModel, StructModel};
+use synext::{
+    deny_unknown_field_model_keys, try_extract_field_model_path_attribute,
+    try_parse_named_fields_result, try_predicate_is_phantom_data, try_unwrap_option,
+    try_unwrap_vec,
+};
+
+// ----------------------------------------------------------------
+
+const ATTRIBUTE: &str = "builder";
+const KEYS: &[&str] = &["each", "default"];
+
+enum Setter<'a> {
+    Skip,
+    Optional(&'a Type),
+    Required { default: bool },
+    Each(Ident, &'a Type),
+}
+
+struct BuilderField<'a> {
+    ident: &'a Ident,
+    ty: &'a Type,
+    setter: Setter<'a>,
+}
+
+pub fn generate(input: &DeriveInput) -> syn::Result<TokenStream> {
+    try_parse_named_fields_result(input)?;
+    let model = StructModel::parse(input, &[ATTRIBUTE])?;
+    let mut errors = ErrorAccumulator::new();
+    let fields: Vec<BuilderField> = model
+        .fields
+        .iter()
+        .filter_map(|field| errors.handle(parse_field(field)))
+        .collect();
+    errors.finish()?;
+
+    let ident = &input.ident;
+    let builder = ident.suffixed("Builder")?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let vis = &input.vis;
+
+    let slots = fields.iter().map(|field| {
+        let (ident, ty) = (field.ident, field.ty);
+        match field.setter {
+            Setter::Skip | Setter::Optional(_) | Setter::Each(..) => quote!(#ident: #ty),
+            Setter::Required { .. } => quote!(#ident: ::core::option::Option<#ty>),
+        }
+    });
+    let inits = fields.iter().map(|field| {
+        let ident = field.ident;
+        match field.setter {
+            Setter::Skip => quote!(#ident: ::core::marker::PhantomData),
+            Setter::Optional(_) | Setter::Required { .. } => {
+                quote!(#ident: ::core::option::Option::None)
+            }
+            Setter::Each(..) => quote!(#ident: ::std::vec::Vec::new()),
+        }
+    });
+    let setters = fields.iter().map(|field| {
+        let (ident, ty) = (field.ident, field.ty);
+        match field.setter {
+            Setter::Skip => quote!(),
+            Setter::Optional(inner) => quote! {
+                pub fn #ident(mut self, #ident: #inner) -> Self {
+                    self.#ident = ::core::option::Option::Some(#ident);
+                    self
+                }
+            },
+            Setter::Required { .. } => quote! {
+                pub fn #ident(mut self, #ident: #ty) -> Self {
+                    self.#ident = ::core::option::Option::Some(#ident);
+                    self
+                }
+            },
+            Setter::Each(ref each, elem) => quote! {
+                pub fn #each(mut self, #each: #elem) -> Self {
+                    self.#ident.push(#each);
+                    self
+                }
+            },
+        }
+    });
+    let builds = fields.iter().map(|field| {
+        let ident = field.ident;
+        match field.setter {
+            Setter::Skip => quote!(#ident: ::core::marker::PhantomData),
+            Setter::Optional(_) | Setter::Each(..) => quote!(#ident: self.#ident),
+            Setter::Required { default: true } => {
+                quote!(#ident: self.#ident.unwrap_or_default())
+            }
+            Setter::Required { default: false } => {
+                let message = LitStr::new(&format!("field `{}` is not set", ident), ident.span());
+                quote! {
+                    #ident: self.#ident.ok_or_else(|| ::std::string::String::from(#message))?
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #vis struct #builder #impl_generics #where_clause {
+            #(#slots,)*
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn builder() -> #builder #ty_generics {
+                #builder {
+                    #(#inits,)*
+                }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            pub fn build(self) -> ::core::result::Result<#ident #ty_generics, ::std::string::String> {
+                ::core::result::Result::Ok(#ident {
+                    #(#builds,)*
+                })
+            }
+        }
+    })
+}
+
+fn parse_field<'a>(field: &FieldModel<'a>) -> syn::Result<BuilderField<'a>> {
+    deny_unknown_field_model_keys(ATTRIBUTE, KEYS, field)?;
+
+    let ident = field.ident().expect("named field");
+    let ty = field.ty();
+    let each = try_extract_field_model_path_attribute(ATTRIBUTE, "each", field)?;
+    let default = field.helpers.flag(ATTRIBUTE, "default");
+
+    let setter = match each {
+        Some(each) if !field.is_vec => {
+            return Err(syn::Error::new(
+                each.span(),
+                "`each` is only supported on `Vec<T>` fields",
+            ))
+        }
+        Some(each) => Setter::Each(each.into_inner(), try_unwrap_vec(ty)),
+        None if try_predicate_is_phantom_data(ty) => Setter::Skip,
+        None if field.is_option => Setter::Optional(try_unwrap_option(ty)),
+        None => Setter::Required { default },
+    };
+
+    Ok(BuilderField { ident, ty, setter })
+}
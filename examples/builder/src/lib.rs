@@ -0,0 +1,38 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/builder
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+mod expand;
+
+// ----------------------------------------------------------------
+
+/// Derive a `FooBuilder` for `struct Foo`, with a setter per field and a fallible `build`.
+///
+/// - `Option<T>` fields are optional, their setter takes a `T`.
+/// - `#[builder(each = "arg")]` on a `Vec<T>` field adds an `arg(T)` setter appending one element.
+/// - `#[builder(default)]` fields fall back to `Default::default()`.
+/// - `PhantomData` fields are skipped.
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn builder(input: TokenStream) -> TokenStream {
+    synext::expand::expand_derive("Builder", input, expand::generate)
+}
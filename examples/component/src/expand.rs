@@ -0,0 +1,127 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/component/expand
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{parse_quote, ItemStruct, LitStr, Meta, NestedMeta, Type};
+use synext::ext::PathExt;
+use synext::{
+    did_you_mean, doc_attrs, peek_item, try_extract_attribute_args,
+    try_extract_attribute_first_args, try_to_snake_case, try_unwrap_arc, AttrForwarder,
+};
+
+// ----------------------------------------------------------------
+
+const KEYS: &[&str] = &["value", "lazy"];
+
+pub fn generate(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let header = peek_item(&item)?;
+    if header.keyword != "struct" {
+        return Err(syn::Error::new_spanned(
+            &header.keyword,
+            "`#[component]` is only supported on structs",
+        ));
+    }
+
+    let args: Vec<NestedMeta> = Punctuated::<NestedMeta, Comma>::parse_terminated
+        .parse2(args)?
+        .into_iter()
+        .collect();
+    let lazy = parse_keys(&args)?;
+    let name = try_extract_attribute_args("value", args.clone())
+        .or_else(|| try_extract_attribute_first_args(args))
+        .unwrap_or_else(|| try_to_snake_case(&header.ident.to_string()));
+
+    let item: ItemStruct = syn::parse2(item)?;
+    let ident = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let dependencies = item
+        .fields
+        .iter()
+        .filter_map(|field| try_unwrap_arc(&field.ty))
+        .map(dependency_name);
+
+    let derive = AttrForwarder::new(parse_quote!(derive)).push(parse_quote!(Clone));
+    let docs = doc_attrs(&format!("\nComponent `{}`.", name), ident.span());
+    let name = LitStr::new(&name, ident.span());
+
+    Ok(quote! {
+        #derive
+        #item
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #docs
+            pub const COMPONENT_NAME: &'static str = #name;
+            pub const LAZY: bool = #lazy;
+
+            /// The names of the `Arc<T>` dependencies, in field order.
+            pub fn dependencies() -> &'static [&'static str] {
+                &[#(#dependencies),*]
+            }
+        }
+    })
+}
+
+/// Reject unknown keys, `lazy` is a bare marker.
+fn parse_keys(args: &[NestedMeta]) -> syn::Result<bool> {
+    let mut lazy = false;
+    for (index, arg) in args.iter().enumerate() {
+        let path = match arg {
+            NestedMeta::Lit(_) if index == 0 => continue,
+            NestedMeta::Lit(lit) => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "only the first `#[component]` argument can be a name literal",
+                ))
+            }
+            NestedMeta::Meta(meta) => meta.path(),
+        };
+
+        let key = path.to_path_string();
+        if !KEYS.contains(&key.as_str()) {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!(
+                    "unknown `component` key `{}`{}",
+                    key,
+                    did_you_mean(KEYS, &key)
+                ),
+            ));
+        }
+        if let NestedMeta::Meta(Meta::Path(_)) = arg {
+            lazy |= key == "lazy";
+        }
+    }
+
+    Ok(lazy)
+}
+
+fn dependency_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(tp) => match tp.path.last_ident() {
+            Some(ident) => ident.to_string(),
+            None => ty.to_token_stream().to_string(),
+        },
+        _ => ty.to_token_stream().to_string(),
+    }
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/component
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+mod expand;
+
+// ----------------------------------------------------------------
+
+/// Register a struct as a component named by `value` (or its snake-cased name),
+/// deriving `Clone` and listing its `Arc<T>` dependencies.
+///
+/// ```ignore
+/// #[component(value = "helloController", lazy)]
+/// pub struct HelloController {
+///     hello_service: Arc<HelloService>,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
+    synext::expand::expand_attribute("component", args, item, expand::generate)
+}
@@ -0,0 +1,29 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures
+
+// ----------------------------------------------------------------
+
+//! Integration fixtures of the example macros built on synext.
+//!
+//! - `tests/builder.rs`, `tests/component.rs`, `tests/projection.rs`: the macros expand into working code.
+//! - `tests/ui.rs`: the errors of every `tests/ui/<macro>/*.rs` input, rendered rustc-like,
+//!   match the `.stderr` snapshot next to it. Run with `SYNEXT_BLESS=1` to update the snapshots.
+
+pub use synext_example_builder::Builder;
+pub use synext_example_component::component;
+pub use synext_example_projection::Project;
@@ -0,0 +1,48 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/arbitrary
+
+// ----------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use quote::ToTokens;
+use synext::testing::arbitrary::Arbitrary;
+
+// ----------------------------------------------------------------
+
+fn attributes(seed: u64) -> Vec<String> {
+    let mut arbitrary = Arbitrary::new(seed);
+    (0..16)
+        .map(|_| arbitrary.attribute("builder").to_token_stream().to_string())
+        .collect()
+}
+
+#[test]
+fn test_arbitrary_is_deterministic_per_seed() {
+    assert_eq!(attributes(42), attributes(42));
+    assert_ne!(attributes(42), attributes(43));
+}
+
+#[test]
+fn test_arbitrary_varies_for_every_seed() {
+    // the seeds that used to map to the stuck all-zero xorshift state
+    for seed in [0, 0x9E37_79B9_7F4A_7C15, u64::MAX] {
+        let distinct = attributes(seed).into_iter().collect::<HashSet<_>>();
+        assert!(distinct.len() > 1, "seed {} generated one attribute", seed);
+    }
+}
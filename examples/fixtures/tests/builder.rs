@@ -0,0 +1,90 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/builder
+
+// ----------------------------------------------------------------
+
+use std::marker::PhantomData;
+
+use synext_example_fixtures::Builder;
+
+// ----------------------------------------------------------------
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    retries: u8,
+}
+
+#[derive(Builder)]
+pub struct Tagged<T> {
+    value: T,
+    marker: PhantomData<fn() -> T>,
+}
+
+#[test]
+fn test_builder_sets_every_field() {
+    let command = Command::builder()
+        .executable("cargo".to_string())
+        .arg("build".to_string())
+        .arg("--release".to_string())
+        .current_dir("..".to_string())
+        .retries(3)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        command,
+        Command {
+            executable: "cargo".to_string(),
+            args: vec!["build".to_string(), "--release".to_string()],
+            current_dir: Some("..".to_string()),
+            retries: 3,
+        }
+    );
+}
+
+#[test]
+fn test_builder_optional_and_default_fields() {
+    let command = Command::builder()
+        .executable("ls".to_string())
+        .build()
+        .unwrap();
+
+    assert!(command.args.is_empty());
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.retries, 0);
+}
+
+#[test]
+fn test_builder_missing_required_field() {
+    let error = Command::builder().build().unwrap_err();
+
+    assert_eq!(error, "field `executable` is not set");
+}
+
+#[test]
+fn test_builder_generics_and_phantom_data() {
+    let tagged = Tagged::<u32>::builder().value(7).build().unwrap();
+
+    assert_eq!(tagged.value, 7);
+    assert_eq!(tagged.marker, PhantomData);
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/compare
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, Type};
+use synext::{type_to_canonical_string, types_equal};
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_types_equal_normalizes_paths() {
+    let cases: [(Type, Type); 7] = [
+        (parse_quote!(::my::Id), parse_quote!(my::Id)),
+        (
+            parse_quote!(std::option::Option<::std::vec::Vec<u8>>),
+            parse_quote!(Option<Vec<u8>>),
+        ),
+        (
+            parse_quote!(Box<dyn core::iter::Iterator<Item = u8>>),
+            parse_quote!(Box<dyn Iterator<Item = u8>>),
+        ),
+        (parse_quote!((u8)), parse_quote!(u8)),
+        (parse_quote!(Option::<u8>), parse_quote!(Option<u8>)),
+        (
+            parse_quote!(std::collections::HashMap::<K, Vec::<V>>),
+            parse_quote!(std::collections::HashMap<K, Vec<V>>),
+        ),
+        (
+            parse_quote!(<T as std::iter::Iterator>::Item),
+            parse_quote!(<T as Iterator>::Item),
+        ),
+    ];
+
+    for (a, b) in cases {
+        assert!(types_equal(&a, &b), "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_types_equal_keeps_distinct_paths_apart() {
+    let cases: [(Type, Type); 4] = [
+        (parse_quote!(std::io::Result<u8>), parse_quote!(Result<u8>)),
+        (parse_quote!(std::io::Error), parse_quote!(std::fmt::Error)),
+        (parse_quote!(crate::Id), parse_quote!(Id)),
+        (parse_quote!(&'a u8), parse_quote!(&'b u8)),
+    ];
+
+    for (a, b) in cases {
+        assert!(!types_equal(&a, &b), "{:?} == {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_type_to_canonical_string_renders_rustfmt_spacing() {
+    let cases: [(Type, &str); 12] = [
+        (
+            parse_quote!(std::option::Option<Vec<u8>>),
+            "Option<Vec<u8>>",
+        ),
+        (parse_quote!(Option::<u8>), "Option<u8>"),
+        (parse_quote!(::my::Id), "my::Id"),
+        (parse_quote!(std::io::Result<u8>), "std::io::Result<u8>"),
+        (parse_quote!(&'a mut HashMap<K, V>), "&'a mut HashMap<K, V>"),
+        (parse_quote!(&u8), "&u8"),
+        (parse_quote!(*const u8), "*const u8"),
+        (
+            parse_quote!(Box<dyn Fn(u8) -> u8 + Send>),
+            "Box<dyn Fn(u8) -> u8 + Send>",
+        ),
+        (parse_quote!([u8; 4]), "[u8; 4]"),
+        (parse_quote!((u8, String)), "(u8, String)"),
+        (
+            parse_quote!(<T as std::iter::Iterator>::Item),
+            "<T as Iterator>::Item",
+        ),
+        (parse_quote!(Vec<(u8)>), "Vec<u8>"),
+    ];
+
+    for (ty, expected) in cases {
+        assert_eq!(type_to_canonical_string(&ty), expected);
+    }
+}
+
+#[test]
+fn test_type_to_canonical_string_agrees_with_types_equal() {
+    let a: Type = parse_quote!(::std::vec::Vec::<std::option::Option<u8>>);
+    let b: Type = parse_quote!(Vec<Option<u8>>);
+
+    assert!(types_equal(&a, &b));
+    assert_eq!(type_to_canonical_string(&a), type_to_canonical_string(&b));
+}
@@ -0,0 +1,83 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/component
+
+// ----------------------------------------------------------------
+
+use std::sync::Arc;
+
+use synext_example_fixtures::component;
+
+// ----------------------------------------------------------------
+
+pub struct HelloService;
+
+pub trait Repository {}
+
+#[component(value = "helloController", lazy)]
+pub struct HelloController {
+    hello_service: Arc<HelloService>,
+    repository: Arc<dyn Repository + Send + Sync>,
+    greeting: String,
+}
+
+#[component]
+pub struct JobScheduler;
+
+#[component("named")]
+pub struct Named<T: Clone> {
+    value: T,
+}
+
+#[test]
+fn test_component_name_and_flags() {
+    assert_eq!(HelloController::COMPONENT_NAME, "helloController");
+    assert_eq!(JobScheduler::COMPONENT_NAME, "job_scheduler");
+    assert_eq!(Named::<u8>::COMPONENT_NAME, "named");
+
+    assert_eq!([HelloController::LAZY, JobScheduler::LAZY], [true, false]);
+}
+
+#[test]
+fn test_component_dependencies() {
+    assert_eq!(
+        HelloController::dependencies(),
+        &["HelloService", "dyn Repository + Send + Sync"]
+    );
+    assert!(JobScheduler::dependencies().is_empty());
+}
+
+#[test]
+fn test_component_derives_clone() {
+    struct Repo;
+    impl Repository for Repo {}
+
+    let controller = HelloController {
+        hello_service: Arc::new(HelloService),
+        repository: Arc::new(Repo),
+        greeting: "hello".to_string(),
+    };
+    let cloned = controller.clone();
+
+    assert!(Arc::ptr_eq(
+        &controller.hello_service,
+        &cloned.hello_service
+    ));
+    assert_eq!(Arc::strong_count(&cloned.repository), 2);
+    assert_eq!(cloned.greeting, "hello");
+    assert_eq!(Named { value: 1u8 }.clone().value, 1);
+}
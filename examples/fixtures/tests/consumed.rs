@@ -0,0 +1,91 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/consumed
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, Data, DeriveInput};
+use synext::ext::{DeriveInputExt, FieldExt};
+use synext::{ensure_no_unconsumed_helper_attrs, mark_attr_consumed, ConsumedAttrs};
+
+// ----------------------------------------------------------------
+
+fn input() -> DeriveInput {
+    parse_quote! {
+        #[builder(skip)]
+        pub struct Config {
+            #[builder(skip)]
+            cache: bool,
+        }
+    }
+}
+
+fn mark_field_attrs(input: &DeriveInput) {
+    if let Data::Struct(data) = &input.data {
+        for field in &data.fields {
+            field.attrs.iter().for_each(mark_attr_consumed);
+        }
+    }
+}
+
+#[test]
+fn test_identical_unread_attribute_is_reported() {
+    let input = input();
+    let (_, consumed) = ConsumedAttrs::track(|| mark_field_attrs(&input));
+
+    let error = ensure_no_unconsumed_helper_attrs(&input, "builder", &consumed).unwrap_err();
+    assert_eq!(error.into_iter().count(), 1);
+}
+
+#[test]
+fn test_every_read_attribute_is_accepted() {
+    let input = input();
+    let (_, consumed) = ConsumedAttrs::track(|| {
+        input.attrs.iter().for_each(mark_attr_consumed);
+        mark_field_attrs(&input);
+    });
+
+    assert!(ensure_no_unconsumed_helper_attrs(&input, "builder", &consumed).is_ok());
+}
+
+#[test]
+fn test_looking_up_an_attribute_does_not_consume_it() {
+    let input = input();
+    let (_, consumed) = ConsumedAttrs::track(|| {
+        assert!(input.find_attr("builder").is_some());
+        for field in input.all_fields() {
+            assert!(field.has_attr("builder"));
+            assert!(field.find_attr("builder").is_some());
+        }
+    });
+
+    let error = ensure_no_unconsumed_helper_attrs(&input, "builder", &consumed).unwrap_err();
+    assert_eq!(error.into_iter().count(), 2);
+}
+
+#[test]
+fn test_taking_an_attribute_consumes_it() {
+    let input = input();
+    let (_, consumed) = ConsumedAttrs::track(|| {
+        assert!(input.take_attr("builder").is_some());
+        for field in input.all_fields() {
+            assert!(field.consume_attr("builder"));
+        }
+    });
+
+    assert!(ensure_no_unconsumed_helper_attrs(&input, "builder", &consumed).is_ok());
+}
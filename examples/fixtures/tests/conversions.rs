@@ -0,0 +1,119 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/conversions
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, DeriveInput};
+use synext::{try_collect_from_conversions, try_parse_conversion_plan};
+
+// ----------------------------------------------------------------
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn test_conversion_plan_accepts_distinct_targets() {
+    let input: DeriveInput = parse_quote! {
+        #[convert(into = "UserDto", from = "UserDto", into = "crate::UserDto")]
+        struct User {
+            name: String,
+        }
+    };
+
+    let plan = try_parse_conversion_plan(&input, "convert").unwrap();
+
+    assert_eq!(plan.targets.len(), 3);
+}
+
+#[test]
+fn test_conversion_plan_rejects_duplicate_targets() {
+    let input: DeriveInput = parse_quote! {
+        #[convert(into = "Option<UserDto>")]
+        #[convert(into = "std::option::Option::<UserDto>")]
+        struct User {
+            name: String,
+        }
+    };
+
+    let error = try_parse_conversion_plan(&input, "convert").unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "duplicate `into` conversion for `Option<UserDto>`",
+            "first declared here",
+        ]
+    );
+}
+
+#[test]
+fn test_conversion_plan_rejects_fallible_and_infallible_targets() {
+    let input: DeriveInput = parse_quote! {
+        #[convert(from = "::row::Row", try_from = "row::Row", error = "ConvertError")]
+        struct User {
+            name: String,
+        }
+    };
+
+    let error = try_parse_conversion_plan(&input, "convert").unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "`from` and `try_from` conflict for `row::Row`: the infallible conversion already implements `TryFrom` through std's blanket impl",
+            "first declared here",
+        ]
+    );
+}
+
+#[test]
+fn test_from_conversions_reject_the_same_source_written_twice() {
+    let input: DeriveInput = parse_quote! {
+        enum Error {
+            Io(#[from] std::io::Error),
+            Fmt(#[from] std::fmt::Error),
+            Again(#[from] ::std::io::Error),
+        }
+    };
+
+    let error = try_collect_from_conversions(&input).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "conflicting `#[from]` conversion from `std::io::Error`",
+            "`std::io::Error` is first converted here",
+        ]
+    );
+}
+
+#[test]
+fn test_from_conversions_accept_distinct_sources() {
+    let input: DeriveInput = parse_quote! {
+        enum Error {
+            Io(#[from] std::io::Error),
+            Fmt(#[from] std::fmt::Error),
+            Parse(#[from] ParseError),
+        }
+    };
+
+    let conversions = try_collect_from_conversions(&input).unwrap();
+
+    assert_eq!(conversions.len(), 3);
+}
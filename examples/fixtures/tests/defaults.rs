@@ -0,0 +1,99 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/defaults
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, Data, DeriveInput};
+use synext::{try_collect_field_defaults, try_order_field_defaults, FieldDefault};
+
+// ----------------------------------------------------------------
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn test_order_field_defaults_follows_dependencies() {
+    let input: DeriveInput = parse_quote! {
+        pub struct Rect {
+            #[builder(default = "self.width * self.height")]
+            area: u32,
+            #[builder(default = "self.width")]
+            height: u32,
+            width: u32,
+        }
+    };
+
+    let defaults = try_collect_field_defaults(&input, "builder", "default").unwrap();
+    let ordered = try_order_field_defaults(&defaults).unwrap();
+    let idents: Vec<String> = ordered.iter().map(|d| d.ident().to_string()).collect();
+
+    assert_eq!(idents, ["height", "area"]);
+}
+
+#[test]
+fn test_order_field_defaults_reports_only_cycle_members() {
+    let input: DeriveInput = parse_quote! {
+        pub struct Cycle {
+            #[builder(default = "self.b")]
+            a: u32,
+            #[builder(default = "self.a")]
+            b: u32,
+            #[builder(default = "self.a + 1")]
+            c: u32,
+        }
+    };
+
+    let defaults = try_collect_field_defaults(&input, "builder", "default").unwrap();
+    let error = try_order_field_defaults(&defaults).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "cyclic default: `a` -> `b` -> `a`",
+            "cyclic default: `b` -> `a` -> `b`",
+        ]
+    );
+}
+
+#[test]
+fn test_self_dependency_is_reported_once() {
+    let input: DeriveInput = parse_quote! {
+        pub struct Counter {
+            #[builder(default = "self.count + 1")]
+            count: u32,
+        }
+    };
+
+    let error = try_collect_field_defaults(&input, "builder", "default").unwrap_err();
+    assert_eq!(
+        messages(error),
+        ["the `default` of `count` cannot read the field itself"]
+    );
+
+    let field = match &input.data {
+        Data::Struct(data) => data.fields.iter().next().unwrap(),
+        _ => unreachable!(),
+    };
+    let defaults = [FieldDefault {
+        field,
+        expr: parse_quote!(self.count + 1),
+        dependencies: vec![parse_quote!(count)],
+    }];
+    assert_eq!(try_order_field_defaults(&defaults).unwrap().len(), 1);
+}
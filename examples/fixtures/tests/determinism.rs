@@ -0,0 +1,120 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/determinism
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{DeriveInput, Generics, ItemStruct};
+use synext::{
+    generate_helper_constants, normalize_where_clause, AttributeKey, AttributeSchema, ValueKind,
+};
+
+#[path = "../../builder/src/expand.rs"]
+mod builder;
+#[path = "../../component/src/expand.rs"]
+mod component;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_builder_expansion_is_deterministic() {
+    let expand = |tokens: TokenStream| {
+        let input: DeriveInput = syn::parse2(tokens).unwrap();
+        builder::generate(&input).unwrap().to_string()
+    };
+    let input = quote! {
+        pub struct Command {
+            executable: String,
+            #[builder(each = "arg")]
+            #[builder(default)]
+            args: Vec<String>,
+            current_dir: Option<String>,
+        }
+    };
+
+    let expanded = expand(input.clone());
+    assert_eq!(expanded, expand(input));
+
+    // The helper keys are looked up by name, whatever the attribute order.
+    let permuted = quote! {
+        pub struct Command {
+            executable: String,
+            #[builder(default)]
+            #[builder(each = "arg")]
+            args: Vec<String>,
+            current_dir: Option<String>,
+        }
+    };
+    assert_eq!(expanded, expand(permuted));
+}
+
+#[test]
+fn test_component_expansion_is_deterministic() {
+    let expand = || {
+        let item = quote! {
+            pub struct HelloController {
+                hello_service: Arc<HelloService>,
+                repository: Arc<dyn Repository + Send + Sync>,
+            }
+        };
+        component::generate(quote!(value = "helloController", lazy), item)
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(expand(), expand());
+}
+
+#[test]
+fn test_normalized_where_clause_is_independent_of_predicate_order() {
+    let normalize = |tokens: TokenStream| {
+        let item: ItemStruct = syn::parse2(tokens).unwrap();
+        let mut generics: Generics = item.generics;
+        normalize_where_clause(&mut generics);
+        generics.where_clause.to_token_stream().to_string()
+    };
+
+    let declared = normalize(quote! {
+        struct Foo<'a, T, U> where T: Clone, U: Default, 'a: 'static, T: Send + Clone;
+    });
+    let permuted = normalize(quote! {
+        struct Foo<'a, T, U> where U: Default, T: Clone + Send, 'a: 'static, T: Clone;
+    });
+
+    assert_eq!(declared, permuted);
+    assert_eq!(
+        declared,
+        quote!(where 'a: 'static, T: Clone + Send, U: Default).to_string()
+    );
+}
+
+#[test]
+fn test_helper_constants_are_deterministic() {
+    let generate = || {
+        let schema = AttributeSchema::new("builder")
+            .key(AttributeKey::new("each", ValueKind::Str))
+            .key(AttributeKey::new("default", ValueKind::Flag))
+            .key(AttributeKey::new("max_len", ValueKind::Int));
+        generate_helper_constants("attrs", &[schema])
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(generate(), generate());
+}
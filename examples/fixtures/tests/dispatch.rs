@@ -0,0 +1,82 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/dispatch
+
+// ----------------------------------------------------------------
+
+use quote::ToTokens;
+use syn::{parse_quote, DeriveInput};
+use synext::try_parse_dispatch_table;
+
+// ----------------------------------------------------------------
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn test_try_parse_dispatch_table_resolves_handlers() {
+    let input: DeriveInput = parse_quote! {
+        enum Command {
+            #[dispatch(handler = "handlers::start")]
+            Start,
+            Stop { force: bool },
+        }
+    };
+
+    let table =
+        try_parse_dispatch_table(&input, "dispatch", Some(&parse_quote!(handlers::other))).unwrap();
+
+    let handlers = table
+        .entries
+        .iter()
+        .map(|entry| (entry.handler.to_token_stream().to_string(), entry.fallback))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        handlers,
+        [
+            ("handlers :: start".to_string(), false),
+            ("handlers :: other".to_string(), true),
+        ]
+    );
+}
+
+#[test]
+fn test_try_parse_dispatch_table_reports_every_bad_entry() {
+    let input: DeriveInput = parse_quote! {
+        enum Command {
+            #[dispatch(handler = "handlers::start")]
+            Start,
+            #[dispatch(handler = "handlers::stop()")]
+            Stop,
+            #[dispatch(handler = 1)]
+            Pause,
+            Resume,
+        }
+    };
+
+    let error = try_parse_dispatch_table(&input, "dispatch", None).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "unexpected token",
+            r#"expected `dispatch(handler = "...")`"#,
+            r#"missing dispatch handler, expected `#[dispatch(handler = "...")]`"#,
+        ]
+    );
+}
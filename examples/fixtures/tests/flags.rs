@@ -0,0 +1,80 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/flags
+
+// ----------------------------------------------------------------
+
+use quote::{format_ident, ToTokens};
+use syn::{parse_quote, DeriveInput, File, Item};
+use synext::{try_generate_flag_set, try_parse_flag_set};
+
+// ----------------------------------------------------------------
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+fn stored_type(input: &DeriveInput) -> String {
+    let tokens = try_generate_flag_set(input, &format_ident!("Flags")).unwrap();
+    let file: File = syn::parse2(tokens).unwrap();
+    match &file.items[0] {
+        Item::Struct(item) => {
+            let field = item.fields.iter().next().unwrap();
+            field.ty.to_token_stream().to_string()
+        }
+        other => panic!("expected the set struct, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_flag_set_stores_usize_flags_in_u64() {
+    let input: DeriveInput = parse_quote! {
+        #[repr(usize)]
+        enum Flag {
+            Read = 1,
+            Wide = 1 << 40,
+        }
+    };
+    assert_eq!(stored_type(&input), "u64");
+
+    let input: DeriveInput = parse_quote! {
+        #[repr(u8)]
+        enum Flag {
+            Read = 1,
+            Write = 1 << 1,
+        }
+    };
+    assert_eq!(stored_type(&input), "u8");
+}
+
+#[test]
+fn test_flag_set_checks_usize_flags_against_64_bits() {
+    let input: DeriveInput = parse_quote! {
+        #[repr(usize)]
+        enum Flag {
+            Top = 1 << 63,
+            Over = 1 << 64,
+        }
+    };
+
+    let error = try_parse_flag_set(&input).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        ["flag discriminant `0x10000000000000000` overflows `usize`"]
+    );
+}
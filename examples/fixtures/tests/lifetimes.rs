@@ -0,0 +1,48 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/lifetimes
+
+// ----------------------------------------------------------------
+
+use quote::ToTokens;
+use syn::{parse_quote, Type};
+use synext::try_elide_lifetimes;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_elide_lifetimes_keeps_static_and_higher_ranked_lifetimes() {
+    let cases: [(Type, Type); 4] = [
+        (parse_quote!(&'a str), parse_quote!(&'_ str)),
+        (parse_quote!(&'static str), parse_quote!(&'static str)),
+        (
+            parse_quote!(Box<dyn for<'b> Fn(&'b str) -> &'a str>),
+            parse_quote!(Box<dyn for<'b> Fn(&'b str) -> &'_ str>),
+        ),
+        (
+            parse_quote!(for<'b> fn(&'b u8, &'a u8) -> &'b u8),
+            parse_quote!(for<'b> fn(&'b u8, &'_ u8) -> &'b u8),
+        ),
+    ];
+
+    for (ty, expected) in cases {
+        assert_eq!(
+            try_elide_lifetimes(&ty).to_token_stream().to_string(),
+            expected.to_token_stream().to_string()
+        );
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/literal
+
+// ----------------------------------------------------------------
+
+use proc_macro2::Span;
+use syn::{parse_quote, Lit, LitInt};
+use synext::{
+    ensure_byte_len, lit_float_as, lit_int_as, lit_int_with_unit, try_lit_to_bytes, ByteLen,
+};
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_lit_int_as_converts_in_range_values() {
+    let lit: Lit = parse_quote!(8080);
+    assert_eq!(lit_int_as::<u16>(&lit).unwrap(), 8080);
+
+    let lit: Lit = parse_quote!(255u8);
+    assert_eq!(lit_int_as::<u8>(&lit).unwrap(), 255);
+
+    let lit = Lit::Int(LitInt::new("-5", Span::call_site()));
+    assert_eq!(lit_int_as::<i32>(&lit).unwrap(), -5);
+
+    let lit: Lit = parse_quote!(340282366920938463463374607431768211455);
+    assert_eq!(lit_int_as::<u128>(&lit).unwrap(), u128::MAX);
+}
+
+#[test]
+fn test_lit_int_as_rejects_overflow() {
+    let lit: Lit = parse_quote!(300);
+    let error = lit_int_as::<u8>(&lit).unwrap_err();
+    assert_eq!(error.to_string(), "`300` does not fit in `u8`");
+
+    let lit = Lit::Int(LitInt::new("-1", Span::call_site()));
+    let error = lit_int_as::<u32>(&lit).unwrap_err();
+    assert_eq!(error.to_string(), "`-1` does not fit in `u32`");
+}
+
+#[test]
+fn test_lit_int_as_rejects_a_foreign_suffix() {
+    let lit: Lit = parse_quote!(300u16);
+    let error = lit_int_as::<u8>(&lit).unwrap_err();
+    assert_eq!(error.to_string(), "expected a `u8` literal, got `300u16`");
+
+    let lit: Lit = parse_quote!(1i64);
+    assert!(lit_int_as::<u64>(&lit).is_err());
+}
+
+#[test]
+fn test_lit_int_as_rejects_other_literal_kinds() {
+    let cases: [Lit; 3] = [parse_quote!("8080"), parse_quote!(1.5), parse_quote!(true)];
+
+    for lit in cases {
+        let error = lit_int_as::<u16>(&lit).unwrap_err();
+        assert_eq!(error.to_string(), "expected an integer literal");
+    }
+}
+
+#[test]
+fn test_lit_float_as_converts_and_checks_the_range() {
+    let lit: Lit = parse_quote!(0.5);
+    assert_eq!(lit_float_as::<f32>(&lit).unwrap(), 0.5);
+
+    let lit: Lit = parse_quote!(2);
+    assert_eq!(lit_float_as::<f64>(&lit).unwrap(), 2.0);
+
+    let lit: Lit = parse_quote!(0.25f64);
+    assert_eq!(lit_float_as::<f64>(&lit).unwrap(), 0.25);
+
+    let lit: Lit = parse_quote!(1e39);
+    let error = lit_float_as::<f32>(&lit).unwrap_err();
+    assert_eq!(error.to_string(), "`1e39` does not fit in `f32`");
+    assert_eq!(lit_float_as::<f64>(&lit).unwrap(), 1e39);
+}
+
+#[test]
+fn test_lit_float_as_rejects_a_foreign_suffix_and_other_kinds() {
+    let lit: Lit = parse_quote!(0.5f64);
+    let error = lit_float_as::<f32>(&lit).unwrap_err();
+    assert_eq!(error.to_string(), "expected a `f32` literal, got `0.5f64`");
+
+    let lit: Lit = parse_quote!("0.5");
+    let error = lit_float_as::<f32>(&lit).unwrap_err();
+    assert_eq!(error.to_string(), "expected a float literal");
+}
+
+#[test]
+fn test_lit_int_with_unit_strips_known_units() {
+    let units = ["ms", "s"];
+
+    let lit: Lit = parse_quote!(500ms);
+    let (value, unit) = lit_int_with_unit::<u64>(&lit, &units).unwrap();
+    assert_eq!(value, 500);
+    assert_eq!(unit.value(), "ms");
+
+    let lit: Lit = parse_quote!(30);
+    let (value, unit) = lit_int_with_unit::<u64>(&lit, &units).unwrap();
+    assert_eq!(value, 30);
+    assert_eq!(unit.value(), "");
+
+    let lit: Lit = parse_quote!(5min);
+    let error = lit_int_with_unit::<u64>(&lit, &units).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unknown unit `min`, expected one of: ms, s"
+    );
+
+    let lit: Lit = parse_quote!(300ms);
+    let error = lit_int_with_unit::<u8>(&lit, &units).unwrap_err();
+    assert_eq!(error.to_string(), "`300` does not fit in `u8`");
+}
+
+#[test]
+fn test_try_lit_to_bytes_checks_the_kind_and_length() {
+    let lit: Lit = parse_quote!(b"\x7fELF");
+    let bytes = try_lit_to_bytes(&lit).unwrap();
+    assert_eq!(bytes.value(), b"\x7fELF");
+    assert!(ensure_byte_len(&bytes, ByteLen::Exact(4)).is_ok());
+
+    let error = ensure_byte_len(&bytes, ByteLen::AtMost(2)).unwrap_err();
+    assert_eq!(error.to_string(), "expected at most 2 bytes, got 4");
+
+    let lit: Lit = parse_quote!(b'\x7f');
+    assert_eq!(try_lit_to_bytes(&lit).unwrap().value(), &vec![0x7f]);
+
+    let lit: Lit = parse_quote!("ELF");
+    assert!(try_lit_to_bytes(&lit).is_err());
+}
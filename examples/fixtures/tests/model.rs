@@ -0,0 +1,117 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/model
+
+// ----------------------------------------------------------------
+
+use darling::{FromDeriveInput, FromField, FromMeta};
+use syn::{parse_quote, DeriveInput, Ident};
+use synext::darling::{try_collect_field_options, FromStructModel};
+use synext::model::StructModel;
+
+// ----------------------------------------------------------------
+
+#[derive(Debug, FromMeta)]
+struct Setter {
+    into: Option<bool>,
+    prefix: Option<String>,
+}
+
+#[derive(Debug, FromField)]
+#[darling(attributes(builder))]
+struct BuilderField {
+    ident: Option<Ident>,
+    each: Option<String>,
+    setter: Option<Setter>,
+}
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(builder), supports(struct_named))]
+struct BuilderOpts {
+    ident: Ident,
+    name: Option<String>,
+    data: darling::ast::Data<(), BuilderField>,
+}
+
+fn input() -> DeriveInput {
+    parse_quote! {
+        #[builder(name = "ConfigBuilder")]
+        #[derive(Debug)]
+        pub struct Config {
+            #[builder(each = "arg")]
+            #[builder(setter(into, prefix = "with"))]
+            args: Vec<String>,
+            /// The port.
+            port: u16,
+        }
+    }
+}
+
+#[test]
+fn test_helper_attributes_keep_nested_lists() {
+    let input = input();
+    let model = StructModel::parse(&input, &["builder"]).unwrap();
+
+    let helpers = &model.fields[0].helpers;
+    assert_eq!(helpers.str("builder", "each").as_deref(), Some("arg"));
+    let setter = helpers.nested("builder", "setter").unwrap();
+    assert!(setter.flag("into"));
+    assert_eq!(setter.str("prefix").as_deref(), Some("with"));
+}
+
+#[test]
+fn test_helper_attributes_reject_literals() {
+    let input: DeriveInput = parse_quote! {
+        struct Config {
+            #[builder("arg")]
+            args: Vec<String>,
+        }
+    };
+
+    let error = StructModel::parse(&input, &["builder"]).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "expected `key = value`, `key` or `key(...)`"
+    );
+}
+
+#[test]
+fn test_darling_options_are_built_from_the_model() {
+    let input = input();
+    let model = StructModel::parse(&input, &["builder"]).unwrap();
+
+    let fields = try_collect_field_options::<BuilderField>(&model).unwrap();
+    let (_, args) = &fields[0];
+    assert_eq!(args.ident.as_ref().unwrap(), "args");
+    assert_eq!(args.each.as_deref(), Some("arg"));
+    let setter = args.setter.as_ref().unwrap();
+    assert_eq!(setter.into, Some(true));
+    assert_eq!(setter.prefix.as_deref(), Some("with"));
+    let (_, port) = &fields[1];
+    assert_eq!(port.each, None);
+    assert!(port.setter.is_none());
+
+    let options = BuilderOpts::from_struct_model(&model).unwrap();
+    assert_eq!(options.ident, "Config");
+    assert_eq!(options.name.as_deref(), Some("ConfigBuilder"));
+    let fields = options.data.take_struct().unwrap().fields;
+    assert_eq!(
+        fields[0].setter.as_ref().unwrap().prefix.as_deref(),
+        Some("with")
+    );
+}
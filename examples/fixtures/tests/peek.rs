@@ -0,0 +1,97 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/peek
+
+// ----------------------------------------------------------------
+
+use quote::quote;
+use synext::peek_item;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_peek_item_reads_the_keyword_and_name() {
+    let cases = [
+        (
+            quote!(
+                #[x]
+                pub(crate) const unsafe fn run() {}
+            ),
+            "fn",
+            "run",
+        ),
+        (
+            quote!(
+                unsafe extern "C" fn callback() {}
+            ),
+            "fn",
+            "callback",
+        ),
+        (
+            quote!(
+                static mut COUNTER: usize = 0;
+            ),
+            "static",
+            "COUNTER",
+        ),
+        (
+            quote!(
+                pub struct Buffer<const N: usize>([u8; N]);
+            ),
+            "struct",
+            "Buffer",
+        ),
+        (
+            quote!(
+                const LIMIT: usize = 8;
+            ),
+            "const",
+            "LIMIT",
+        ),
+    ];
+
+    for (tokens, keyword, ident) in cases {
+        let header = peek_item(&tokens).unwrap();
+        assert_eq!(
+            (header.keyword.to_string(), header.ident.to_string()),
+            (keyword.to_string(), ident.to_string()),
+            "{}",
+            tokens
+        );
+    }
+}
+
+#[test]
+fn test_peek_item_does_not_look_into_generics() {
+    let cases = [
+        quote!(
+            impl<const N: usize> Foo for [u8; N] {}
+        ),
+        quote!(
+            impl<T> Foo<T> {
+                fn run() {}
+            }
+        ),
+        quote!(
+            const _: () = ();
+        ),
+    ];
+
+    for tokens in cases {
+        assert!(peek_item(&tokens).is_err(), "{}", tokens);
+    }
+}
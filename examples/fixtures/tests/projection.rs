@@ -0,0 +1,91 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/projection
+
+// ----------------------------------------------------------------
+
+use std::marker::PhantomPinned;
+use std::pin::{pin, Pin};
+
+use synext_example_fixtures::Project;
+
+// ----------------------------------------------------------------
+
+pub trait Bump {
+    fn bump(self: Pin<&mut Self>) -> u8;
+}
+
+/// A `!Unpin` counter, only reachable through `Pin<&mut Counter>`.
+pub struct Counter {
+    count: u8,
+    _pinned: PhantomPinned,
+}
+
+impl Bump for Counter {
+    fn bump(self: Pin<&mut Self>) -> u8 {
+        // SAFETY: `count` is not structurally pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        this.count += 1;
+        this.count
+    }
+}
+
+#[derive(Project)]
+pub struct Buffered<F: Bump, const N: usize> {
+    #[pin]
+    inner: F,
+    data: [u8; N],
+}
+
+#[derive(Project)]
+pub struct Pair<A, B>(#[pin] A, B)
+where
+    B: Clone + Default;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_projection_keeps_inline_bounds_and_const_generics() {
+    let mut buffered = pin!(Buffered {
+        inner: Counter {
+            count: 0,
+            _pinned: PhantomPinned,
+        },
+        data: [0u8; 4],
+    });
+
+    let projection: BufferedProjection<'_, Counter, 4> = buffered.as_mut().project();
+    projection.data[0] = 1;
+    assert_eq!(projection.inner.bump(), 1);
+    assert_eq!(buffered.as_mut().project().inner.bump(), 2);
+
+    let projection_ref = buffered.as_ref().project_ref();
+    assert_eq!(projection_ref.data, &[1, 0, 0, 0]);
+    assert_eq!(projection_ref.inner.count, 2);
+}
+
+#[test]
+fn test_projection_of_tuple_struct_with_where_clause() {
+    let mut pair = Pair(1u8, String::from("b"));
+    let pair = Pin::new(&mut pair);
+
+    let projection: PairProjection<'_, u8, String> = pair.project();
+    let first: Pin<&mut u8> = projection.0;
+    assert_eq!(*first, 1);
+    projection.1.push('c');
+    assert_eq!(projection.1, "bc");
+}
@@ -0,0 +1,129 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/schema
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, Attribute};
+use synext::{AttrPosition, AttributeKey, AttributeSchema, KeyRule, ValueKind};
+
+// ----------------------------------------------------------------
+
+fn range_schema() -> AttributeSchema {
+    AttributeSchema::new("range")
+        .key(AttributeKey::new("min", ValueKind::Int).required())
+        .key(AttributeKey::new("max", ValueKind::Int).required())
+        .key(AttributeKey::new("name", ValueKind::Str))
+        .key(AttributeKey::new("inclusive", ValueKind::Flag))
+        .rule(KeyRule::LessOrEqual("min".into(), "max".into()))
+}
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn test_schema_parses_declared_keys() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[range(min = 1, max = 10)]),
+        parse_quote!(#[range(name = "port", inclusive)]),
+    ];
+
+    let values = range_schema().parse(&attrs).unwrap();
+
+    assert_eq!(values.len(), 4);
+    assert_eq!(values.int::<u8>("min").unwrap(), Some(1));
+    assert_eq!(values.int::<u8>("max").unwrap(), Some(10));
+    assert_eq!(values.str("name").as_deref(), Some("port"));
+    assert!(values.flag("inclusive"));
+}
+
+#[test]
+fn test_schema_rejects_unknown_keys() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[range(min = 1, max = 10, mxa = 3)])];
+
+    let error = range_schema().parse(&attrs).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        ["unknown `range` key `mxa`, did you mean `max`?"]
+    );
+}
+
+#[test]
+fn test_schema_rejects_duplicate_keys() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[range(min = 1, max = 10)]),
+        parse_quote!(#[range(min = 2)]),
+    ];
+
+    let error = range_schema().parse(&attrs).unwrap_err();
+
+    assert_eq!(messages(error), ["duplicate `range` key `min`"]);
+}
+
+#[test]
+fn test_schema_rejects_missing_required_keys() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[range(min = 1)])];
+
+    let error = range_schema().parse(&attrs).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        ["missing required `max = <integer>` in `#[range(...)]`"]
+    );
+}
+
+#[test]
+fn test_schema_rejects_mistyped_values_and_broken_rules() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[range(min = 10, max = 1, name = 3)])];
+
+    let error = range_schema().parse(&attrs).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            r#"expected `name = "..."`"#,
+            "`max` must be greater than or equal to `min`",
+            "`min` is declared here",
+        ]
+    );
+}
+
+#[test]
+fn test_schema_does_not_require_keys_of_other_positions() {
+    let schema = AttributeSchema::new("builder")
+        .key(
+            AttributeKey::new("each", ValueKind::Str)
+                .required()
+                .only(&[AttrPosition::Field]),
+        )
+        .key(AttributeKey::new("name", ValueKind::Str).only(&[AttrPosition::Container]));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[builder(name = "Builder")])];
+    assert!(schema.parse_at(&attrs, AttrPosition::Container).is_ok());
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[builder(each = "arg", name = "Builder")])];
+    let error = schema.parse_at(&attrs, AttrPosition::Field).unwrap_err();
+    assert_eq!(messages(error), ["`name` is only valid on containers"]);
+}
+
+#[test]
+#[should_panic(expected = "synext: `range` key `max-len` is not an identifier")]
+fn test_schema_rejects_a_key_that_is_not_an_identifier() {
+    let _ = AttributeSchema::new("range").key(AttributeKey::new("max-len", ValueKind::Int));
+}
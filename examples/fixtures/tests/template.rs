@@ -0,0 +1,102 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/template
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, Attribute, LitStr};
+use synext::{render_template, AttributeKey, AttributeSchema, TemplateVars, ValueKind};
+
+// ----------------------------------------------------------------
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn test_render_template_substitutes_variables() {
+    let vars = TemplateVars::new()
+        .with("prefix", "api")
+        .with("version", "1")
+        .with("version", "2");
+
+    let cases: [(LitStr, &str); 5] = [
+        (parse_quote!("{prefix}/users"), "api/users"),
+        (parse_quote!("/{prefix}/v{version}/{prefix}"), "/api/v2/api"),
+        (parse_quote!("{ prefix }"), "api"),
+        (parse_quote!("{{prefix}} = {prefix}"), "{prefix} = api"),
+        (parse_quote!("users"), "users"),
+    ];
+
+    for (template, expected) in cases {
+        assert_eq!(render_template(&template, &vars).unwrap().value(), expected);
+    }
+}
+
+#[test]
+fn test_render_template_reads_container_values() {
+    let schema = AttributeSchema::new("route")
+        .key(AttributeKey::new("prefix", ValueKind::Str))
+        .key(AttributeKey::new("version", ValueKind::Int))
+        .key(AttributeKey::new("internal", ValueKind::Flag));
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[route(prefix = "api", version = 2, internal)])];
+    let vars = TemplateVars::from_values(&schema.parse(&attrs).unwrap());
+
+    assert_eq!(vars.names().collect::<Vec<_>>(), ["prefix", "version"]);
+
+    let template: LitStr = parse_quote!("{prefix}/v{version}");
+    assert_eq!(render_template(&template, &vars).unwrap().value(), "api/v2");
+}
+
+#[test]
+fn test_render_template_rejects_unknown_placeholders() {
+    let template: LitStr = parse_quote!("{prefix}/{resource}");
+
+    let error = render_template(&template, &TemplateVars::new()).unwrap_err();
+    assert_eq!(
+        messages(error),
+        [
+            "undefined template variable `prefix`",
+            "undefined template variable `resource`",
+        ]
+    );
+
+    let vars = TemplateVars::new()
+        .with("prefix", "api")
+        .with("version", "2");
+    let error = render_template(&template, &vars).unwrap_err();
+    assert_eq!(
+        messages(error),
+        ["undefined template variable `resource`, expected one of `prefix`, `version`"]
+    );
+}
+
+#[test]
+fn test_render_template_rejects_unbalanced_braces() {
+    let vars = TemplateVars::new().with("prefix", "api");
+    let template: LitStr = parse_quote!("{prefix}} / {prefix");
+
+    let error = render_template(&template, &vars).unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "unmatched `}` in template, use `}}` for a literal brace",
+            "unclosed `{prefix` in template, use `{{` for a literal brace",
+        ]
+    );
+}
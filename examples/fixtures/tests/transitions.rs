@@ -0,0 +1,150 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/transitions
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, DeriveInput};
+use synext::try_parse_transition_table;
+
+// ----------------------------------------------------------------
+
+fn messages(error: syn::Error) -> Vec<String> {
+    error.into_iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn test_try_parse_transition_table_accepts_declared_transitions() {
+    let input: DeriveInput = parse_quote! {
+        enum Job {
+            #[transition(to = "Running", on = "start")]
+            Idle,
+            #[transition(to = "Idle", on = "stop")]
+            #[transition(from = "Running", to = "Done", on = "finish")]
+            Running,
+            Done,
+        }
+    };
+
+    let table = try_parse_transition_table(&input, "transition").unwrap();
+
+    assert_eq!(table.initial.ident, "Idle");
+    assert_eq!(table.states.len(), 3);
+    assert_eq!(table.transitions.len(), 3);
+    assert_eq!(
+        table
+            .events()
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>(),
+        ["start", "stop", "finish"]
+    );
+    assert_eq!(table.transitions_from(&parse_quote!(Running)).len(), 2);
+
+    let target = |state: syn::Ident, event: syn::Ident| {
+        table.target(&state, &event).map(|to| to.ident.to_string())
+    };
+    assert_eq!(
+        target(parse_quote!(Idle), parse_quote!(start)).as_deref(),
+        Some("Running")
+    );
+    assert_eq!(
+        target(parse_quote!(Running), parse_quote!(finish)).as_deref(),
+        Some("Done")
+    );
+    assert_eq!(target(parse_quote!(Idle), parse_quote!(finish)), None);
+    assert_eq!(target(parse_quote!(Done), parse_quote!(start)), None);
+}
+
+#[test]
+fn test_try_parse_transition_table_honours_the_initial_marker() {
+    let input: DeriveInput = parse_quote! {
+        enum Door {
+            #[transition(to = "Closed", on = "close")]
+            Open,
+            #[transition(initial, to = "Open", on = "open")]
+            Closed,
+        }
+    };
+
+    let table = try_parse_transition_table(&input, "transition").unwrap();
+
+    assert_eq!(table.initial.ident, "Closed");
+}
+
+#[test]
+fn test_try_parse_transition_table_rejects_unknown_and_duplicate_transitions() {
+    let input: DeriveInput = parse_quote! {
+        enum Job {
+            #[transition(to = "Running", on = "start")]
+            #[transition(to = "Done", on = "start")]
+            Idle,
+            #[transition(to = "Done", on = "finish")]
+            #[transition(to = "Missing", on = "stop")]
+            #[transition(to = "Done")]
+            #[transition(to = "Idle", on = "stop", when = "now")]
+            Running,
+            Done,
+        }
+    };
+
+    let error = try_parse_transition_table(&input, "transition").unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        [
+            "duplicate transition on `start` from state `Idle`",
+            "unknown state `Missing`",
+            "a transition requires both `to` and `on`",
+            r#"expected `from = "..."`, `to = "..."` or `on = "..."`"#,
+        ]
+    );
+}
+
+#[test]
+fn test_try_parse_transition_table_rejects_unreachable_states() {
+    let input: DeriveInput = parse_quote! {
+        enum Job {
+            #[transition(to = "Running", on = "start")]
+            Idle,
+            Running,
+            #[transition(to = "Idle", on = "reset")]
+            Orphan,
+        }
+    };
+
+    let error = try_parse_transition_table(&input, "transition").unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        ["state `Orphan` is unreachable from the initial state `Idle`"]
+    );
+}
+
+#[test]
+fn test_try_parse_transition_table_rejects_non_enums() {
+    let input: DeriveInput = parse_quote! {
+        struct Job;
+    };
+
+    let error = try_parse_transition_table(&input, "transition").unwrap_err();
+
+    assert_eq!(
+        messages(error),
+        ["synext: Only non-empty enums are supported! target:`Job`"]
+    );
+}
@@ -0,0 +1,156 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/ui
+
+// ----------------------------------------------------------------
+
+use std::path::Path;
+use std::{env, fs};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{DeriveInput, Item};
+use synext::testing::render::{
+    render_diagnostic, render_diagnostics, Diagnostic, DiagnosticLocation,
+};
+
+#[path = "../../builder/src/expand.rs"]
+mod builder;
+#[path = "../../component/src/expand.rs"]
+mod component;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_ui_builder() {
+    run_ui("builder", |source| {
+        let input: DeriveInput = syn::parse_str(source)?;
+        builder::generate(&input).map(|_| ())
+    });
+}
+
+#[test]
+fn test_ui_component() {
+    run_ui("component", |source| {
+        let mut item: Item = syn::parse_str(source)?;
+        let attrs = match item {
+            Item::Struct(ref mut item) => &mut item.attrs,
+            Item::Enum(ref mut item) => &mut item.attrs,
+            Item::Fn(ref mut item) => &mut item.attrs,
+            _ => panic!("unsupported ui item"),
+        };
+        let index = attrs
+            .iter()
+            .position(|attr| attr.path.is_ident("component"))
+            .expect("`#[component]` attribute");
+        let attr = attrs.remove(index);
+        let args = match attr.tokens.into_iter().next() {
+            Some(TokenTree::Group(group)) => group.stream(),
+            _ => TokenStream::new(),
+        };
+
+        component::generate(args, item.into_token_stream()).map(|_| ())
+    });
+}
+
+// ----------------------------------------------------------------
+
+/// Expand every `tests/ui/<dir>/*.rs` input, which must fail, and compare the rendered errors
+/// with the `.stderr` snapshot next to it, rewritten instead when `SYNEXT_BLESS` is set.
+#[test]
+fn test_render_diagnostic_without_a_source_line() {
+    let diagnostic = |line| Diagnostic {
+        message: "synext: oops".to_string(),
+        location: Some(DiagnosticLocation {
+            line,
+            column: 0,
+            end_line: line,
+            end_column: 3,
+        }),
+    };
+
+    assert_eq!(
+        render_diagnostic(&diagnostic(0), "lib.rs", "struct A;"),
+        "error: synext: oops\n --> lib.rs:0:1\n"
+    );
+    assert_eq!(
+        render_diagnostic(&diagnostic(2), "lib.rs", "struct A;"),
+        "error: synext: oops\n --> lib.rs:2:1\n"
+    );
+    assert_eq!(
+        render_diagnostic(&diagnostic(1), "lib.rs", "struct A;"),
+        "error: synext: oops\n --> lib.rs:1:1\n  |\n1 | struct A;\n  | ^^^\n"
+    );
+}
+
+fn run_ui<F>(dir: &str, expand: F)
+where
+    F: Fn(&str) -> syn::Result<()>,
+{
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let bless = env::var_os("SYNEXT_BLESS").is_some();
+
+    let mut cases: Vec<_> = fs::read_dir(root.join("tests/ui").join(dir))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "no ui cases under tests/ui/{}", dir);
+
+    let mut failures = Vec::new();
+    for case in cases {
+        let file = case
+            .strip_prefix(root)
+            .unwrap()
+            .to_string_lossy()
+            .replace('\\', "/");
+        let source = fs::read_to_string(&case).unwrap();
+        let actual = match expand(&source) {
+            Ok(()) => {
+                failures.push(format!(
+                    "{}: expected errors, the expansion succeeded",
+                    file
+                ));
+                continue;
+            }
+            Err(error) => format!(
+                "{}\n",
+                render_diagnostics(&error, &file, &source).trim_end()
+            ),
+        };
+
+        let snapshot = case.with_extension("stderr");
+        if bless {
+            fs::write(&snapshot, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&snapshot) {
+            Ok(expected) if expected.trim_end() == actual.trim_end() => {}
+            Ok(expected) => failures.push(format!(
+                "{}: mismatch\n--- expected\n{}\n--- actual\n{}",
+                file, expected, actual
+            )),
+            Err(_) => failures.push(format!(
+                "{}: missing snapshot, run with SYNEXT_BLESS=1\n--- actual\n{}",
+                file, actual
+            )),
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n\n"));
+}
@@ -0,0 +1,5 @@
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: String,
+}
@@ -0,0 +1,2 @@
+#[derive(Builder)]
+pub struct Point(i32, i32);
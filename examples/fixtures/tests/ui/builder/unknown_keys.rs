@@ -0,0 +1,7 @@
+#[derive(Builder)]
+pub struct Command {
+    #[builder(eahc = "arg")]
+    args: Vec<String>,
+    #[builder(defualt)]
+    retries: u8,
+}
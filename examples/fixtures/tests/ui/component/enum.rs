@@ -0,0 +1,4 @@
+#[component]
+pub enum Shape {
+    Circle,
+}
@@ -0,0 +1,2 @@
+#[component(lazy, "helloController")]
+pub struct HelloController;
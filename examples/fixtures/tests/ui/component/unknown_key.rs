@@ -0,0 +1,4 @@
+#[component(valeu = "helloController")]
+pub struct HelloController {
+    hello_service: Arc<HelloService>,
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/verbatim
+
+// ----------------------------------------------------------------
+
+use quote::{quote, ToTokens};
+use syn::{parse_quote, Type};
+use synext::{
+    try_predicate_is_option_with, try_predicate_is_vec_with, try_unwrap_option_with, VerbatimPolicy,
+};
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_unwrap_option_with_policy() {
+    let option: Type = parse_quote!(Option<u8>);
+    let plain: Type = parse_quote!(u8);
+    let verbatim = Type::Verbatim(quote!(unsafe<'a> &'a u8));
+
+    let inner = try_unwrap_option_with(&option, VerbatimPolicy::Error).unwrap();
+    assert_eq!(inner.unwrap().to_token_stream().to_string(), "u8");
+    assert!(try_unwrap_option_with(&plain, VerbatimPolicy::Error)
+        .unwrap()
+        .is_none());
+    assert!(try_unwrap_option_with(&verbatim, VerbatimPolicy::Opaque)
+        .unwrap()
+        .is_none());
+    assert!(try_unwrap_option_with(&verbatim, VerbatimPolicy::Error).is_err());
+}
+
+#[test]
+fn test_predicates_with_policy() {
+    let nested: Type = parse_quote!(Vec<u8>);
+    let verbatim = Type::Verbatim(quote!(unsafe<'a> &'a u8));
+
+    assert!(try_predicate_is_vec_with(&nested, VerbatimPolicy::Error).unwrap());
+    assert!(!try_predicate_is_option_with(&nested, VerbatimPolicy::Error).unwrap());
+    assert!(!try_predicate_is_vec_with(&verbatim, VerbatimPolicy::Opaque).unwrap());
+    assert!(try_predicate_is_vec_with(&verbatim, VerbatimPolicy::Error).is_err());
+}
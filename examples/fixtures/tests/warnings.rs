@@ -0,0 +1,79 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/fixtures/tests/warnings
+
+// ----------------------------------------------------------------
+
+use syn::{parse_quote, DeriveInput, Item, Lit, Meta};
+use synext::diag::{try_map_fields, FieldErrorMode};
+use synext::try_match_fields;
+
+// ----------------------------------------------------------------
+
+#[test]
+fn test_skipped_fields_are_reported_as_deprecation_warnings() {
+    let input: DeriveInput = parse_quote! {
+        pub struct Config {
+            name: String,
+            #[builder(each = 1)]
+            tags: Vec<String>,
+        }
+    };
+
+    let results = try_map_fields(
+        try_match_fields(&input),
+        FieldErrorMode::Resilient,
+        |field| match field.attrs.is_empty() {
+            true => Ok(()),
+            false => Err(syn::Error::new_spanned(field, "`each` expects a string")),
+        },
+    )
+    .unwrap();
+
+    let file: syn::File = syn::parse2(results.warnings()).unwrap();
+    let notes: Vec<String> = file
+        .items
+        .iter()
+        .flat_map(|item| match item {
+            Item::Const(item) => match &*item.expr {
+                syn::Expr::Block(block) => block.block.stmts.clone(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        })
+        .filter_map(|stmt| match stmt {
+            syn::Stmt::Item(Item::Struct(item)) => item
+                .attrs
+                .iter()
+                .find(|attr| attr.path.is_ident("deprecated"))
+                .and_then(|attr| attr.parse_args::<Meta>().ok()),
+            _ => None,
+        })
+        .filter_map(|meta| match meta {
+            Meta::NameValue(nv) if nv.path.is_ident("note") => match nv.lit {
+                Lit::Str(note) => Some(note.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        notes,
+        ["synext: field `tags` skipped: `each` expects a string"]
+    );
+}
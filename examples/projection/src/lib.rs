@@ -0,0 +1,39 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// examples/projection
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::format_ident;
+
+// ----------------------------------------------------------------
+
+/// Derive `project` / `project_ref` for `struct Foo`, returning `FooProjection` / `FooProjectionRef`
+/// where `#[pin]` fields are pinned.
+#[proc_macro_derive(Project, attributes(pin))]
+pub fn project(input: TokenStream) -> TokenStream {
+    synext::expand::expand_derive("Project", input, |input| {
+        let projection = synext::try_parse_pin_projection(input, "pin")?;
+        Ok(projection.to_projection_tokens(
+            &format_ident!("{}Projection", input.ident),
+            &format_ident!("{}ProjectionRef", input.ident),
+        ))
+    })
+}
@@ -0,0 +1,180 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// compat
+
+// ----------------------------------------------------------------
+
+//! Converters between the `syn 1` nodes used by synext and their `syn 2` counterparts,
+//! for macro crates that depend on both majors.
+//!
+//! Nodes are converted through their tokens, so spans are preserved.
+//! Converting `syn 2` nodes fails when they use syntax `syn 1` cannot represent
+//! (e.g. `#[attr(key = some::path)]`, where `syn 1` expects a literal value).
+
+use quote::ToTokens;
+use syn::parse::Parser;
+use syn2::parse::Parser as Syn2Parser;
+
+use crate::diag::ErrorAccumulator;
+
+// ----------------------------------------------------------------
+
+/// Convert a [`syn::DeriveInput`] into a `syn 2` one.
+///
+/// # Examples
+///
+///```ignore
+/// let input: syn::DeriveInput = synext::try_derive_input(tokens);
+/// let other: syn2::DeriveInput = to_syn2_derive_input(&input)?;
+/// ```
+/// @since 0.4.0
+pub fn to_syn2_derive_input(input: &syn::DeriveInput) -> syn::Result<syn2::DeriveInput> {
+    syn2::parse2(input.to_token_stream()).map_err(from_syn2_error)
+}
+
+/// Convert a `syn 2` `DeriveInput` into a [`syn::DeriveInput`].
+///
+/// @since 0.4.0
+pub fn from_syn2_derive_input(input: &syn2::DeriveInput) -> syn::Result<syn::DeriveInput> {
+    syn::parse2(input.to_token_stream())
+}
+
+/// Convert a [`syn::Field`] into a `syn 2` one.
+///
+/// @since 0.4.0
+pub fn to_syn2_field(field: &syn::Field) -> syn::Result<syn2::Field> {
+    let tokens = field.to_token_stream();
+    let parsed = match field.ident {
+        Some(_) => syn2::Field::parse_named.parse2(tokens),
+        None => syn2::Field::parse_unnamed.parse2(tokens),
+    };
+
+    parsed.map_err(from_syn2_error)
+}
+
+/// Convert a `syn 2` `Field` into a [`syn::Field`].
+///
+/// @since 0.4.0
+pub fn from_syn2_field(field: &syn2::Field) -> syn::Result<syn::Field> {
+    let tokens = field.to_token_stream();
+    match field.ident {
+        Some(_) => syn::Field::parse_named.parse2(tokens),
+        None => syn::Field::parse_unnamed.parse2(tokens),
+    }
+}
+
+/// Convert a [`syn::Attribute`] (outer or inner) into a `syn 2` one.
+///
+/// @since 0.4.0
+pub fn to_syn2_attribute(attr: &syn::Attribute) -> syn::Result<syn2::Attribute> {
+    let tokens = attr.to_token_stream();
+    let parsed = match attr.style {
+        syn::AttrStyle::Outer => syn2::Attribute::parse_outer.parse2(tokens),
+        syn::AttrStyle::Inner(_) => syn2::Attribute::parse_inner.parse2(tokens),
+    };
+
+    parsed.map_err(from_syn2_error).and_then(single_attribute)
+}
+
+/// Convert a `syn 2` `Attribute` (outer or inner) into a [`syn::Attribute`].
+///
+/// @since 0.4.0
+pub fn from_syn2_attribute(attr: &syn2::Attribute) -> syn::Result<syn::Attribute> {
+    let tokens = attr.to_token_stream();
+    let parsed = match attr.style {
+        syn2::AttrStyle::Outer => syn::Attribute::parse_outer.parse2(tokens),
+        syn2::AttrStyle::Inner(_) => syn::Attribute::parse_inner.parse2(tokens),
+    };
+
+    parsed.and_then(single_attribute)
+}
+
+/// Convert [`syn::Attribute`]s into `syn 2` ones, combining the errors of every attribute.
+///
+/// @since 0.4.0
+pub fn to_syn2_attributes(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn2::Attribute>> {
+    collect_converted(attrs.iter().map(to_syn2_attribute))
+}
+
+/// Convert `syn 2` `Attribute`s into [`syn::Attribute`]s, combining the errors of every attribute.
+///
+/// @since 0.4.0
+pub fn from_syn2_attributes(attrs: &[syn2::Attribute]) -> syn::Result<Vec<syn::Attribute>> {
+    collect_converted(attrs.iter().map(from_syn2_attribute))
+}
+
+/// Convert a [`syn::Meta`] into a `syn 2` one.
+///
+/// @since 0.4.0
+pub fn to_syn2_meta(meta: &syn::Meta) -> syn::Result<syn2::Meta> {
+    syn2::parse2(meta.to_token_stream()).map_err(from_syn2_error)
+}
+
+/// Convert a `syn 2` `Meta` into a [`syn::Meta`].
+///
+/// Fails on `key = value` metas whose value is not a literal.
+///
+/// @since 0.4.0
+pub fn from_syn2_meta(meta: &syn2::Meta) -> syn::Result<syn::Meta> {
+    syn::parse2(meta.to_token_stream())
+}
+
+/// Convert a `syn 2` error into a [`syn::Error`], keeping its message and span.
+///
+/// @since 0.4.0
+pub fn from_syn2_error(error: syn2::Error) -> syn::Error {
+    error
+        .into_iter()
+        .map(|error| syn::Error::new(error.span(), error))
+        .collect::<ErrorAccumulator>()
+        .combine()
+        .expect("synext: a syn 2 error holds at least one message")
+}
+
+/// Convert a [`syn::Error`] into a `syn 2` error, keeping its message and span.
+///
+/// @since 0.4.0
+pub fn to_syn2_error(error: syn::Error) -> syn2::Error {
+    error
+        .into_iter()
+        .map(|error| syn2::Error::new(error.span(), error))
+        .reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        })
+        .expect("synext: a syn error holds at least one message")
+}
+
+fn single_attribute<T>(mut attrs: Vec<T>) -> syn::Result<T> {
+    match attrs.len() {
+        1 => Ok(attrs.remove(0)),
+        _ => Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "synext: expected exactly one attribute",
+        )),
+    }
+}
+
+fn collect_converted<T, I>(results: I) -> syn::Result<Vec<T>>
+where
+    I: Iterator<Item = syn::Result<T>>,
+{
+    let mut errors = ErrorAccumulator::new();
+    let converted = results.filter_map(|result| errors.handle(result)).collect();
+
+    errors.finish_with(converted)
+}
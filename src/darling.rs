@@ -0,0 +1,181 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+// darling
+
+// ----------------------------------------------------------------
+
+//! Adapters for crates migrating from darling: keep the existing darling-derived option structs
+//! next to a [`crate::model::StructModel`].
+//!
+//! The option structs are built from the values already parsed into the model: [`FromFieldModel`]
+//! and [`FromStructModel`] hand darling the `syn` nodes with each helper attribute rebuilt from
+//! its parsed values, [`FromHelperAttributes`] hands the values of one helper to darling directly.
+
+use proc_macro2::Span;
+use syn::{Attribute, Data, Field, Ident, Meta, MetaList, MetaNameValue, NestedMeta};
+
+use crate::diag::ErrorAccumulator;
+use crate::model::{FieldModel, HelperAttributes, StructModel};
+use crate::syntax::attr::schema::AttributeValues;
+
+// ----------------------------------------------------------------
+
+/// Build a darling [`::darling::FromField`] option struct from a [`FieldModel`]: darling reads the
+/// field with its helper attributes rebuilt from the model, one `#[helper(...)]` per helper.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(FromField)]
+/// #[darling(attributes(builder))]
+/// struct BuilderField {
+///     ident: Option<Ident>,
+///     each: Option<String>,
+/// }
+///
+/// let model = StructModel::parse(&input, &["builder"])?;
+/// for field in &model.fields {
+///     let options = BuilderField::from_field_model(field)?;
+/// }
+/// ```
+/// @since 0.4.0
+pub trait FromFieldModel: Sized {
+    fn from_field_model(model: &FieldModel<'_>) -> syn::Result<Self>;
+}
+
+impl<T: ::darling::FromField> FromFieldModel for T {
+    fn from_field_model(model: &FieldModel<'_>) -> syn::Result<Self> {
+        let field = Field {
+            attrs: model_attrs(&model.field.attrs, &model.helpers),
+            ..model.field.clone()
+        };
+
+        T::from_field(&field).map_err(syn::Error::from)
+    }
+}
+
+/// Build a darling [`::darling::FromDeriveInput`] option struct from a [`StructModel`]: darling reads
+/// the input with the container and field helper attributes rebuilt from the model.
+///
+/// @since 0.4.0
+pub trait FromStructModel: Sized {
+    fn from_struct_model(model: &StructModel<'_>) -> syn::Result<Self>;
+}
+
+impl<T: ::darling::FromDeriveInput> FromStructModel for T {
+    fn from_struct_model(model: &StructModel<'_>) -> syn::Result<Self> {
+        let mut input = model.input.clone();
+        input.attrs = model_attrs(&model.input.attrs, &model.helpers);
+        if let Data::Struct(ref mut data) = input.data {
+            for (field, field_model) in data.fields.iter_mut().zip(&model.fields) {
+                field.attrs = model_attrs(&field_model.field.attrs, &field_model.helpers);
+            }
+        }
+
+        T::from_derive_input(&input).map_err(syn::Error::from)
+    }
+}
+
+/// Build a darling [`::darling::FromMeta`] option struct from the already parsed values of one
+/// helper attribute, without parsing the attribute again. An absent helper is an empty list.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(FromMeta)]
+/// struct Setter {
+///     each: Option<String>,
+///     #[darling(default)]
+///     skip: bool,
+/// }
+///
+/// let setter = Setter::from_helper(&field.helpers, "builder")?;
+/// ```
+/// @since 0.4.0
+pub trait FromHelperAttributes: Sized {
+    fn from_helper(helpers: &HelperAttributes, helper: &str) -> syn::Result<Self>;
+}
+
+impl<T: ::darling::FromMeta> FromHelperAttributes for T {
+    fn from_helper(helpers: &HelperAttributes, helper: &str) -> syn::Result<Self> {
+        let nested = helpers
+            .get(helper)
+            .map(values_to_nested_metas)
+            .unwrap_or_default();
+
+        T::from_list(&nested).map_err(syn::Error::from)
+    }
+}
+
+/// Build the darling option struct of every field, reporting all fields' errors at once.
+///
+/// @since 0.4.0
+pub fn try_collect_field_options<'m, 'a, T: ::darling::FromField>(
+    model: &'m StructModel<'a>,
+) -> syn::Result<Vec<(&'m FieldModel<'a>, T)>> {
+    let mut errors = ErrorAccumulator::new();
+    let options = model
+        .fields
+        .iter()
+        .filter_map(|field| {
+            errors
+                .handle(T::from_field_model(field))
+                .map(|options| (field, options))
+        })
+        .collect();
+
+    errors.finish_with(options)
+}
+
+/// The non-helper attributes of `attrs`, followed by every helper rebuilt from its parsed values.
+fn model_attrs(attrs: &[Attribute], helpers: &HelperAttributes) -> Vec<Attribute> {
+    let mut rebuilt: Vec<Attribute> = attrs
+        .iter()
+        .filter(|attr| !helpers.iter().any(|(name, _)| attr.path.is_ident(name)))
+        .cloned()
+        .collect();
+    rebuilt.extend(helpers.iter().map(|(name, values)| {
+        let name = Ident::new(name, Span::call_site());
+        let nested = values_to_nested_metas(values);
+        match nested.is_empty() {
+            true => syn::parse_quote!(#[#name]),
+            false => syn::parse_quote!(#[#name(#(#nested),*)]),
+        }
+    }));
+
+    rebuilt
+}
+
+fn values_to_nested_metas(values: &AttributeValues) -> Vec<NestedMeta> {
+    values
+        .iter()
+        .map(|value| match (&value.value, &value.nested) {
+            (Some(lit), _) => NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path: value.key.clone(),
+                eq_token: Default::default(),
+                lit: lit.clone(),
+            })),
+            (None, Some(nested)) => NestedMeta::Meta(Meta::List(MetaList {
+                path: value.key.clone(),
+                paren_token: Default::default(),
+                nested: values_to_nested_metas(nested).into_iter().collect(),
+            })),
+            (None, None) => NestedMeta::Meta(Meta::Path(value.key.clone())),
+        })
+        .collect()
+}
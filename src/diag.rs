@@ -0,0 +1,463 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// diag
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use std::fmt::Display;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Field, Variant};
+
+// ----------------------------------------------------------------
+
+/// Collects errors so every invalid field is reported in one pass instead of only the first.
+///
+/// # Examples
+///
+///```ignore
+/// let mut errors = ErrorAccumulator::new();
+/// let getters = fields
+///     .iter()
+///     .filter_map(|field| errors.handle(generate_getter(field)))
+///     .collect::<Vec<_>>();
+///
+/// errors.finish()?;
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone, Default)]
+pub struct ErrorAccumulator {
+    errors: Vec<syn::Error>,
+}
+
+impl ErrorAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push an error, any [`crate::error::SynextError`] converts as well.
+    pub fn push(&mut self, error: impl Into<syn::Error>) {
+        self.errors.push(error.into());
+    }
+
+    /// Keep the value of an `Ok`, push the error of an `Err`.
+    pub fn handle<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Combine all collected errors into one [`syn::Error`], `None` if there are none.
+    pub fn combine(self) -> Option<syn::Error> {
+        self.errors.into_iter().reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        })
+    }
+
+    /// `Ok(())` if nothing was collected, the combined errors otherwise.
+    pub fn finish(self) -> syn::Result<()> {
+        self.finish_with(())
+    }
+
+    pub fn finish_with<T>(self, value: T) -> syn::Result<T> {
+        match self.combine() {
+            Some(errors) => Err(errors),
+            None => Ok(value),
+        }
+    }
+
+    /// One `compile_error!` per collected error, empty if there are none.
+    pub fn into_compile_error(self) -> TokenStream {
+        self.combine()
+            .map(|errors| errors.to_compile_error())
+            .unwrap_or_default()
+    }
+}
+
+impl Extend<syn::Error> for ErrorAccumulator {
+    fn extend<I: IntoIterator<Item = syn::Error>>(&mut self, iter: I) {
+        self.errors.extend(iter);
+    }
+}
+
+impl FromIterator<syn::Error> for ErrorAccumulator {
+    fn from_iter<I: IntoIterator<Item = syn::Error>>(iter: I) -> Self {
+        Self {
+            errors: iter.into_iter().collect(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------- error.context
+
+/// Builds [`syn::Error`]s prefixed with where they happened, e.g.
+/// ``in struct `Foo`, field `bar`: ...``, spanned at the innermost item.
+///
+/// Unnamed fields are reported by position when they belong to `input`.
+///
+/// # Examples
+///
+///```ignore
+/// let context = ErrorCtx::new(&input);
+/// for field in try_match_fields(&input) {
+///     if try_predicate_is_not_option(&field.ty) {
+///         return Err(context.field(field).error("expected `Option<_>`"));
+///         // -> in struct `Foo`, field `bar`: expected `Option<_>`
+///     }
+/// }
+/// ```
+/// @since 0.4.0
+#[derive(Clone)]
+pub struct ErrorCtx<'a> {
+    input: &'a DeriveInput,
+    variant: Option<&'a Variant>,
+    field: Option<(String, &'a Field)>,
+    span: Option<Span>,
+}
+
+impl<'a> ErrorCtx<'a> {
+    pub fn new(input: &'a DeriveInput) -> Self {
+        Self {
+            input,
+            variant: None,
+            field: None,
+            span: None,
+        }
+    }
+
+    pub fn variant(mut self, variant: &'a Variant) -> Self {
+        self.variant = Some(variant);
+        self
+    }
+
+    pub fn field(mut self, field: &'a Field) -> Self {
+        let name = match field.ident {
+            Some(ref ident) => ident.to_string(),
+            None => self
+                .fields()
+                .position(|candidate| std::ptr::eq(candidate, field))
+                .map_or_else(|| "_".to_string(), |index| index.to_string()),
+        };
+        self.field = Some((name, field));
+        self
+    }
+
+    /// Report on `tokens` instead of the innermost item, e.g. on the offending attribute.
+    pub fn at<T: ToTokens>(mut self, tokens: &T) -> Self {
+        self.span = Some(tokens.to_token_stream().span());
+        self
+    }
+
+    /// The prefix of every message, e.g. ``in struct `Foo`, field `bar` ``.
+    pub fn context(&self) -> String {
+        let kind = match self.input.data {
+            Data::Struct(_) => "struct",
+            Data::Enum(_) => "enum",
+            Data::Union(_) => "union",
+        };
+        let mut context = format!("in {} `{}`", kind, self.input.ident);
+        if let Some(variant) = self.variant {
+            context.push_str(&format!(", variant `{}`", variant.ident));
+        }
+        if let Some((ref name, _)) = self.field {
+            context.push_str(&format!(", field `{}`", name));
+        }
+
+        context
+    }
+
+    pub fn span(&self) -> Span {
+        match (self.span, &self.field, self.variant) {
+            (Some(span), _, _) => span,
+            (None, Some((_, field)), _) => field.span(),
+            (None, None, Some(variant)) => variant.ident.span(),
+            (None, None, None) => self.input.ident.span(),
+        }
+    }
+
+    pub fn error<T: Display>(&self, message: T) -> syn::Error {
+        syn::Error::new(self.span(), format!("{}: {}", self.context(), message))
+    }
+
+    fn fields(&self) -> Box<dyn Iterator<Item = &'a Field> + 'a> {
+        match (self.variant, &self.input.data) {
+            (Some(variant), _) => Box::new(variant.fields.iter()),
+            (None, Data::Struct(data)) => Box::new(data.fields.iter()),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------- diagnostic.backend
+
+/// The severity of an emitted diagnostic, see [`emit_diagnostic`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+/// Whether diagnostics are emitted through `proc_macro::Diagnostic`: the `diagnostics` feature
+/// is enabled, the crate was compiled on nightly and a proc macro is running.
+///
+/// @since 0.4.0
+pub fn is_diagnostic_backend_enabled() -> bool {
+    cfg!(synext_diagnostics) && proc_macro::is_available()
+}
+
+/// Emit a diagnostic with a proper severity on `span`.
+///
+/// With the `diagnostics` feature on nightly, it goes through `proc_macro::Diagnostic` and the
+/// returned stream is empty. Otherwise errors fall back to `compile_error!`, while warnings,
+/// notes and helps can't be reported on stable and are dropped.
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_derive(Builder, attributes(builder))]
+/// pub fn builder(input: TokenStream) -> TokenStream {
+///     let mut output = emit_diagnostic(field.span(), Severity::Warning, "`each` is ignored on `Option` fields");
+///     output.extend(generate(&input));
+///     output
+/// }
+/// ```
+/// @since 0.4.0
+pub fn emit_diagnostic<T: Display>(
+    span: Span,
+    severity: Severity,
+    message: T,
+) -> proc_macro::TokenStream {
+    #[cfg(synext_diagnostics)]
+    if proc_macro::is_available() {
+        emit_nightly_diagnostic(span.unwrap(), severity, message);
+        return proc_macro::TokenStream::new();
+    }
+
+    match severity {
+        Severity::Error => syn::Error::new(span, message).to_compile_error().into(),
+        _ => proc_macro::TokenStream::new(),
+    }
+}
+
+/// Like [`emit_diagnostic`], spanned from the first to the last token of `tokens`.
+///
+/// @since 0.4.0
+pub fn emit_spanned_diagnostic<T: ToTokens, U: Display>(
+    tokens: T,
+    severity: Severity,
+    message: U,
+) -> proc_macro::TokenStream {
+    #[cfg(synext_diagnostics)]
+    if proc_macro::is_available() {
+        let mut spans = tokens
+            .into_token_stream()
+            .into_iter()
+            .map(|token| token.span().unwrap());
+        let start = spans.next().unwrap_or_else(proc_macro::Span::call_site);
+        let span = spans
+            .last()
+            .and_then(|end| start.join(end))
+            .unwrap_or(start);
+        emit_nightly_diagnostic(span, severity, message);
+        return proc_macro::TokenStream::new();
+    }
+
+    match severity {
+        Severity::Error => syn::Error::new_spanned(tokens, message)
+            .to_compile_error()
+            .into(),
+        _ => proc_macro::TokenStream::new(),
+    }
+}
+
+#[cfg(synext_diagnostics)]
+fn emit_nightly_diagnostic<T: Display>(span: proc_macro::Span, severity: Severity, message: T) {
+    let level = match severity {
+        Severity::Error => proc_macro::Level::Error,
+        Severity::Warning => proc_macro::Level::Warning,
+        Severity::Note => proc_macro::Level::Note,
+        Severity::Help => proc_macro::Level::Help,
+    };
+
+    proc_macro::Diagnostic::spanned(span, level, message.to_string()).emit();
+}
+
+// ---------------------------------------------------------------- resilient.fields
+
+/// How per-field errors are handled by [`try_map_fields`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldErrorMode {
+    /// Fail with every field error combined.
+    #[default]
+    Strict,
+    /// Skip the invalid fields, record a warning for each and keep the valid ones,
+    /// so editor builds still see partial output.
+    Resilient,
+}
+
+/// The per-field outcomes of [`try_map_fields`], in field order.
+///
+/// @since 0.4.0
+#[derive(Debug)]
+pub struct FieldResults<'a, T> {
+    results: Vec<(&'a Field, syn::Result<T>)>,
+}
+
+impl<'a, T> FieldResults<'a, T> {
+    /// The valid fields with their values.
+    pub fn valid(&self) -> impl Iterator<Item = (&'a Field, &T)> + '_ {
+        self.results
+            .iter()
+            .filter_map(|(field, result)| result.as_ref().ok().map(|value| (*field, value)))
+    }
+
+    /// The skipped fields with their errors.
+    pub fn skipped(&self) -> impl Iterator<Item = (&'a Field, &syn::Error)> + '_ {
+        self.results
+            .iter()
+            .filter_map(|(field, result)| result.as_ref().err().map(|error| (*field, error)))
+    }
+
+    pub fn has_skipped(&self) -> bool {
+        self.skipped().next().is_some()
+    }
+
+    /// The values of the valid fields, skipped fields are dropped.
+    pub fn into_values(self) -> Vec<T> {
+        self.results
+            .into_iter()
+            .filter_map(|(_, result)| result.ok())
+            .collect()
+    }
+
+    /// The values of every field, with `placeholder` generated in place of each skipped field,
+    /// e.g. a `::core::default::Default::default()` initializer so the struct literal stays complete.
+    pub fn into_values_or_else<F>(self, mut placeholder: F) -> Vec<T>
+    where
+        F: FnMut(&'a Field, &syn::Error) -> T,
+    {
+        self.results
+            .into_iter()
+            .map(|(field, result)| result.unwrap_or_else(|error| placeholder(field, &error)))
+            .collect()
+    }
+
+    /// One warning per skipped field, to be placed among the generated items.
+    ///
+    /// With the `diagnostics` feature on nightly, they go through [`emit_diagnostic`]. On stable
+    /// each one is a `const _` block using a `#[deprecated]` unit struct, so rustc reports a
+    /// `deprecated` warning carrying the message on the field.
+    pub fn warnings(&self) -> TokenStream {
+        self.skipped()
+            .map(|(field, error)| {
+                let message = match field.ident {
+                    Some(ref ident) => format!("synext: field `{}` skipped: {}", ident, error),
+                    None => format!("synext: field skipped: {}", error),
+                };
+                match is_diagnostic_backend_enabled() {
+                    true => emit_spanned_diagnostic(field, Severity::Warning, message).into(),
+                    false => stable_warning(field.span(), &message),
+                }
+            })
+            .collect()
+    }
+}
+
+fn stable_warning(span: Span, message: &str) -> TokenStream {
+    let warning = quote_spanned!(span=> synext_warning);
+
+    quote! {
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_camel_case_types)]
+            struct synext_warning;
+            #[allow(dead_code)]
+            fn synext_warning_use() {
+                let _ = #warning;
+            }
+        };
+    }
+}
+
+/// Try to map every field with `f`, handling the errors according to `mode`.
+///
+/// In [`FieldErrorMode::Strict`] the errors of all fields are combined into one [`syn::Error`].
+/// In [`FieldErrorMode::Resilient`] this never fails, the invalid fields are recorded as skipped.
+///
+/// # Examples
+///
+///```ignore
+/// let mode = match cfg!(feature = "ide") {
+///     true => FieldErrorMode::Resilient,
+///     false => FieldErrorMode::Strict,
+/// };
+/// let results = try_map_fields(try_match_fields(&input), mode, |field| generate_getter(field))?;
+///
+/// let mut output = results.warnings();
+/// let getters = results.into_values();
+/// output.extend(quote! {
+///     impl #ident { #(#getters)* }
+/// });
+/// ```
+/// @since 0.4.0
+pub fn try_map_fields<'a, I, T, F>(
+    fields: I,
+    mode: FieldErrorMode,
+    mut f: F,
+) -> syn::Result<FieldResults<'a, T>>
+where
+    I: IntoIterator<Item = &'a Field>,
+    F: FnMut(&'a Field) -> syn::Result<T>,
+{
+    let results: Vec<(&'a Field, syn::Result<T>)> =
+        fields.into_iter().map(|field| (field, f(field))).collect();
+
+    if mode == FieldErrorMode::Strict {
+        let errors: ErrorAccumulator = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().err().cloned())
+            .collect();
+        errors.finish()?;
+    }
+
+    Ok(FieldResults { results })
+}
@@ -0,0 +1,298 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// error
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use std::fmt;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields};
+
+// ----------------------------------------------------------------
+
+/// The fields a derive expected but did not find, see [`SynextError::MissingFields`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldsKind {
+    Named,
+    Unnamed,
+    Any,
+}
+
+impl FieldsKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FieldsKind::Named => "named",
+            FieldsKind::Unnamed => "unnamed",
+            FieldsKind::Any => "any",
+        }
+    }
+}
+
+/// The data kind a derive does not support, see [`SynextError::UnsupportedData`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    Enum,
+    Union,
+}
+
+impl DataKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataKind::Enum => "enum",
+            DataKind::Union => "union",
+        }
+    }
+}
+
+/// The first and last token spans of a node, like [`syn::Error::new_spanned`] reports on.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy)]
+pub struct SpanRange {
+    pub start: Span,
+    pub end: Span,
+}
+
+impl SpanRange {
+    pub fn single(span: Span) -> Self {
+        SpanRange {
+            start: span,
+            end: span,
+        }
+    }
+
+    pub fn of<T: ToTokens>(tokens: &T) -> Self {
+        let mut spans = tokens
+            .to_token_stream()
+            .into_iter()
+            .map(|token| token.span());
+        match spans.next() {
+            Some(start) => SpanRange {
+                start,
+                end: spans.last().unwrap_or(start),
+            },
+            None => SpanRange::single(Span::call_site()),
+        }
+    }
+
+    /// The joined span where the compiler supports it, the start span otherwise.
+    pub fn join(&self) -> Span {
+        self.start.join(self.end).unwrap_or(self.start)
+    }
+
+    /// A [`syn::Error`] underlining the whole range.
+    pub fn error<T: fmt::Display>(&self, message: T) -> syn::Error {
+        let start = Ident::new("start", self.start);
+        let end = Ident::new("end", self.end);
+        syn::Error::new_spanned(quote::quote!(#start #end), message)
+    }
+}
+
+impl From<Span> for SpanRange {
+    fn from(span: Span) -> Self {
+        SpanRange::single(span)
+    }
+}
+
+/// A structured synext failure, carrying the span range it should be reported on.
+///
+/// Match on the kind to customize the message, or convert it into a [`syn::Error`] or a
+/// [`proc_macro::TokenStream`] as is.
+///
+/// # Examples
+///
+///```ignore
+/// if let Some(error) = SynextError::unsupported_data(&input) {
+///     return match error {
+///         SynextError::UnsupportedData { data: DataKind::Enum, .. } => error
+///             .to_syn_error_with("`Builder` can't be derived for enums")
+///             .to_compile_error()
+///             .into(),
+///         error => error.into(),
+///     };
+/// }
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum SynextError {
+    /// The input is an enum or a union where only structs are supported.
+    UnsupportedData {
+        span: SpanRange,
+        target: String,
+        data: DataKind,
+    },
+    /// The struct does not contain the expected kind of fields.
+    MissingFields {
+        span: SpanRange,
+        target: String,
+        expected: FieldsKind,
+    },
+    /// A type does not match the expected one.
+    UnexpectedType {
+        span: SpanRange,
+        expected: String,
+        found: String,
+    },
+    /// An attribute is malformed or not valid at its position.
+    BadAttribute {
+        span: SpanRange,
+        attribute: String,
+        message: String,
+    },
+    Custom {
+        span: SpanRange,
+        message: String,
+    },
+}
+
+impl SynextError {
+    /// [`SynextError::UnsupportedData`] on the `enum`/`union` keyword, `None` for structs.
+    pub fn unsupported_data(input: &DeriveInput) -> Option<Self> {
+        let (span, data) = match &input.data {
+            Data::Struct(_) => return None,
+            Data::Enum(data) => (data.enum_token.span(), DataKind::Enum),
+            Data::Union(data) => (data.union_token.span(), DataKind::Union),
+        };
+
+        Some(SynextError::UnsupportedData {
+            span: span.into(),
+            target: input.ident.to_string(),
+            data,
+        })
+    }
+
+    /// [`SynextError::MissingFields`] on the fields of `input`, or its ident for unit structs.
+    pub fn missing_fields(input: &DeriveInput, expected: FieldsKind) -> Self {
+        let span = match &input.data {
+            Data::Struct(data) if !matches!(data.fields, Fields::Unit) => {
+                SpanRange::of(&data.fields)
+            }
+            _ => input.ident.span().into(),
+        };
+
+        SynextError::MissingFields {
+            span,
+            target: input.ident.to_string(),
+            expected,
+        }
+    }
+
+    pub fn unexpected_type<T: ToTokens>(found: &T, expected: impl Into<String>) -> Self {
+        SynextError::UnexpectedType {
+            span: SpanRange::of(found),
+            expected: expected.into(),
+            found: found.to_token_stream().to_string(),
+        }
+    }
+
+    pub fn bad_attribute(attr: &syn::Attribute, message: impl Into<String>) -> Self {
+        SynextError::BadAttribute {
+            span: SpanRange::of(attr),
+            attribute: attr.path.to_token_stream().to_string().replace(' ', ""),
+            message: message.into(),
+        }
+    }
+
+    pub fn custom(span: impl Into<SpanRange>, message: impl Into<String>) -> Self {
+        SynextError::Custom {
+            span: span.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.span_range().join()
+    }
+
+    pub fn span_range(&self) -> SpanRange {
+        match self {
+            SynextError::UnsupportedData { span, .. }
+            | SynextError::MissingFields { span, .. }
+            | SynextError::UnexpectedType { span, .. }
+            | SynextError::BadAttribute { span, .. }
+            | SynextError::Custom { span, .. } => *span,
+        }
+    }
+
+    /// Convert into a [`syn::Error`] on the same span range with a replaced message.
+    pub fn to_syn_error_with(&self, message: impl fmt::Display) -> syn::Error {
+        self.span_range().error(message)
+    }
+
+    pub fn to_syn_error(&self) -> syn::Error {
+        self.span_range().error(self)
+    }
+
+    pub fn to_compile_error(&self) -> TokenStream {
+        self.to_syn_error().to_compile_error()
+    }
+}
+
+impl fmt::Display for SynextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SynextError::UnsupportedData { target, .. } => {
+                write!(f, "synext: Only structs are supported! target:`{}`", target)
+            }
+            SynextError::MissingFields {
+                target, expected, ..
+            } => write!(
+                f,
+                "synext: Does not contain {} fields! target:`{}`",
+                expected.label(),
+                target
+            ),
+            SynextError::UnexpectedType {
+                expected, found, ..
+            } => write!(
+                f,
+                "synext: Unexpected type! expected:`{}` found:`{}`",
+                expected, found
+            ),
+            SynextError::BadAttribute {
+                attribute, message, ..
+            } => write!(f, "synext: Bad attribute `#[{}]`! {}", attribute, message),
+            SynextError::Custom { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for SynextError {}
+
+impl From<SynextError> for syn::Error {
+    fn from(error: SynextError) -> Self {
+        error.to_syn_error()
+    }
+}
+
+impl From<SynextError> for proc_macro::TokenStream {
+    fn from(error: SynextError) -> Self {
+        error.to_compile_error().into()
+    }
+}
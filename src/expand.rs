@@ -0,0 +1,369 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// expand
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{DeriveInput, Expr, Ident, Item, Stmt};
+
+use crate::diag::ErrorAccumulator;
+use crate::syntax::peek::parser::peek_ident;
+
+// ----------------------------------------------------------------
+
+/// Hooks called by the expansion entry points ([`expand_derive`], [`expand_attribute`]),
+/// for logging or metrics of macro expansions without patching every macro.
+///
+/// Every hook has an empty default implementation.
+///
+/// # Examples
+///
+///```ignore
+/// struct Metrics;
+///
+/// impl ExpansionObserver for Metrics {
+///     fn on_error(&self, macro_name: &str, error: &syn::Error) {
+///         eprintln!("[{}] {}", macro_name, error);
+///     }
+/// }
+///
+/// #[proc_macro_derive(Builder, attributes(builder))]
+/// pub fn builder(input: TokenStream) -> TokenStream {
+///     let _ = set_expansion_observer(Metrics);
+///     expand_derive("Builder", input, |input| generate(input))
+/// }
+/// ```
+/// @since 0.4.0
+pub trait ExpansionObserver: Send + Sync {
+    /// The input of the macro was parsed, `ident` is the annotated item.
+    fn on_parse(&self, macro_name: &str, ident: &Ident) {
+        let _ = (macro_name, ident);
+    }
+
+    /// The macro failed, the error is emitted as `compile_error!` tokens.
+    fn on_error(&self, macro_name: &str, error: &syn::Error) {
+        let _ = (macro_name, error);
+    }
+
+    /// The macro succeeded, `elapsed` covers parsing and generation.
+    fn on_emit(&self, macro_name: &str, output: &TokenStream, elapsed: Duration) {
+        let _ = (macro_name, output, elapsed);
+    }
+}
+
+static EXPANSION_OBSERVER: OnceLock<Box<dyn ExpansionObserver>> = OnceLock::new();
+
+/// Install the observer of this macro crate's expansions.
+///
+/// The observer can be installed once, later calls give the rejected observer back.
+///
+/// @since 0.4.0
+pub fn set_expansion_observer<O>(observer: O) -> Result<(), O>
+where
+    O: ExpansionObserver + 'static,
+{
+    let mut observer = Some(observer);
+    EXPANSION_OBSERVER.get_or_init(|| Box::new(observer.take().unwrap()));
+
+    match observer {
+        Some(observer) => Err(observer),
+        None => Ok(()),
+    }
+}
+
+/// The installed observer, if any.
+///
+/// @since 0.4.0
+pub fn expansion_observer() -> Option<&'static dyn ExpansionObserver> {
+    EXPANSION_OBSERVER.get().map(|observer| observer.as_ref())
+}
+
+// ----------------------------------------------------------------
+
+/// The entry point of a derive macro: parse the input, run `expand` and turn errors
+/// into `compile_error!` tokens, notifying the installed [`ExpansionObserver`].
+///
+/// @since 0.4.0
+pub fn expand_derive<F>(
+    macro_name: &str,
+    input: proc_macro::TokenStream,
+    expand: F,
+) -> proc_macro::TokenStream
+where
+    F: FnOnce(&DeriveInput) -> syn::Result<TokenStream>,
+{
+    expand_derive2(macro_name, input.into(), expand).into()
+}
+
+/// Like [`expand_derive`], over [`proc_macro2::TokenStream`]s, usable outside of a macro (tests).
+///
+/// @since 0.4.0
+pub fn expand_derive2<F>(macro_name: &str, input: TokenStream, expand: F) -> TokenStream
+where
+    F: FnOnce(&DeriveInput) -> syn::Result<TokenStream>,
+{
+    let started = Instant::now();
+    let result = syn::parse2::<DeriveInput>(input).and_then(|input| {
+        if let Some(observer) = expansion_observer() {
+            observer.on_parse(macro_name, &input.ident);
+        }
+        expand(&input)
+    });
+
+    finish_expansion(macro_name, started, result)
+}
+
+/// The entry point of an attribute macro: run `expand` over the arguments and the item and
+/// turn errors into `compile_error!` tokens, notifying the installed [`ExpansionObserver`].
+///
+/// @since 0.4.0
+pub fn expand_attribute<F>(
+    macro_name: &str,
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+    expand: F,
+) -> proc_macro::TokenStream
+where
+    F: FnOnce(TokenStream, TokenStream) -> syn::Result<TokenStream>,
+{
+    expand_attribute2(macro_name, args.into(), item.into(), expand).into()
+}
+
+/// Like [`expand_attribute`], over [`proc_macro2::TokenStream`]s, usable outside of a macro (tests).
+///
+/// @since 0.4.0
+pub fn expand_attribute2<F>(
+    macro_name: &str,
+    args: TokenStream,
+    item: TokenStream,
+    expand: F,
+) -> TokenStream
+where
+    F: FnOnce(TokenStream, TokenStream) -> syn::Result<TokenStream>,
+{
+    let started = Instant::now();
+    if let (Some(observer), Ok(ident)) = (expansion_observer(), peek_ident(&item)) {
+        observer.on_parse(macro_name, &ident);
+    }
+
+    finish_expansion(macro_name, started, expand(args, item))
+}
+
+fn finish_expansion(
+    macro_name: &str,
+    started: Instant,
+    result: syn::Result<TokenStream>,
+) -> TokenStream {
+    match result {
+        Ok(output) => {
+            if let Some(observer) = expansion_observer() {
+                observer.on_emit(macro_name, &output, started.elapsed());
+            }
+            output
+        }
+        Err(error) => {
+            if let Some(observer) = expansion_observer() {
+                observer.on_error(macro_name, &error);
+            }
+            error.to_compile_error()
+        }
+    }
+}
+
+// ---------------------------------------------------------------- expansion.context
+
+/// State shared by the expansions of one macro invocation, e.g. every struct of a module
+/// processed by [`expand_batch`]: collected errors and the names of generated items.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ExpansionContext {
+    macro_name: String,
+    errors: ErrorAccumulator,
+    generated: Vec<Ident>,
+}
+
+impl ExpansionContext {
+    pub fn new(macro_name: impl Into<String>) -> Self {
+        Self {
+            macro_name: macro_name.into(),
+            errors: ErrorAccumulator::new(),
+            generated: Vec::new(),
+        }
+    }
+
+    pub fn macro_name(&self) -> &str {
+        &self.macro_name
+    }
+
+    /// Record an error and keep going, errors are combined by [`ExpansionContext::finish`].
+    pub fn error(&mut self, error: syn::Error) {
+        self.errors.push(error);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Claim the name of a generated item, reporting a clash with an item
+    /// generated earlier in the same invocation.
+    pub fn claim_ident(&mut self, ident: &Ident) -> syn::Result<()> {
+        if let Some(existing) = self.generated.iter().find(|existing| *existing == ident) {
+            let mut error = syn::Error::new_spanned(
+                ident,
+                format!("`{}` generates `{}` more than once", self.macro_name, ident),
+            );
+            error.combine(syn::Error::new_spanned(existing, "first generated here"));
+            return Err(error);
+        }
+        self.generated.push(ident.clone());
+
+        Ok(())
+    }
+
+    /// The names claimed so far, in order.
+    pub fn generated(&self) -> &[Ident] {
+        &self.generated
+    }
+
+    /// `output` if no error was recorded, the combined errors otherwise.
+    pub fn finish(self, output: TokenStream) -> syn::Result<TokenStream> {
+        self.errors.finish_with(output)
+    }
+
+    /// Like [`ExpansionContext::finish`], but `output` is kept when errors were recorded and
+    /// followed by the combined errors as `compile_error!` invocations: the items of `output`
+    /// stay defined, so rustc reports the errors without follow-up "cannot find type" errors.
+    pub fn finish_recovering(self, output: TokenStream) -> TokenStream {
+        let mut tokens = output;
+        tokens.extend(self.errors.into_compile_error());
+
+        tokens
+    }
+}
+
+/// Run `expand` for every struct, enum and union of a module (`mod models { ... }`) or an
+/// anonymous const block (`const _: () = { ... };`), and reassemble the item with the tokens
+/// generated for each definition placed right after it.
+///
+/// Errors of every definition are collected in `context` and reported together, after the
+/// reassembled item (see [`ExpansionContext::finish_recovering`]): a failing definition is kept
+/// as written, the others with their generated tokens. `Err` is reserved for an item that is
+/// neither a module nor a const block.
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_attribute]
+/// pub fn builders(args: TokenStream, item: TokenStream) -> TokenStream {
+///     expand_attribute("builders", args, item, |_, item| {
+///         let context = ExpansionContext::new("builders");
+///         expand_batch(context, item, |context, input| {
+///             let builder = format_ident!("{}Builder", input.ident);
+///             context.claim_ident(&builder)?;
+///             generate_builder(input, &builder)
+///         })
+///     })
+/// }
+/// ```
+/// @since 0.4.0
+pub fn expand_batch<F>(
+    mut context: ExpansionContext,
+    item: TokenStream,
+    mut expand: F,
+) -> syn::Result<TokenStream>
+where
+    F: FnMut(&mut ExpansionContext, &DeriveInput) -> syn::Result<TokenStream>,
+{
+    let mut expand_items = |items: Vec<Item>, context: &mut ExpansionContext| {
+        let mut expanded = Vec::with_capacity(items.len());
+        for item in items {
+            let input = match item {
+                Item::Struct(ref item) => Some(DeriveInput::from(item.clone())),
+                Item::Enum(ref item) => Some(DeriveInput::from(item.clone())),
+                Item::Union(ref item) => Some(DeriveInput::from(item.clone())),
+                _ => None,
+            };
+            expanded.push(item);
+            if let Some(input) = input {
+                match expand(context, &input) {
+                    Ok(generated) => expanded.push(Item::Verbatim(generated)),
+                    Err(error) => context.error(error),
+                }
+            }
+        }
+
+        expanded
+    };
+
+    let output = match syn::parse2::<Item>(item)? {
+        Item::Mod(mut item_mod) => {
+            let items = match item_mod.content.take() {
+                Some((brace, items)) => (brace, items),
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        &item_mod,
+                        "expected an inline module: `mod name { ... }`",
+                    ))
+                }
+            };
+            item_mod.content = Some((items.0, expand_items(items.1, &mut context)));
+            item_mod.into_token_stream()
+        }
+        Item::Const(mut item_const) => {
+            let block = match *item_const.expr {
+                Expr::Block(ref mut block) => &mut block.block,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &item_const.expr,
+                        "expected a const block: `const _: () = { ... };`",
+                    ))
+                }
+            };
+            let mut stmts = Vec::with_capacity(block.stmts.len());
+            for stmt in std::mem::take(&mut block.stmts) {
+                match stmt {
+                    Stmt::Item(item) => stmts.extend(
+                        expand_items(vec![item], &mut context)
+                            .into_iter()
+                            .map(Stmt::Item),
+                    ),
+                    other => stmts.push(other),
+                }
+            }
+            block.stmts = stmts;
+            item_const.into_token_stream()
+        }
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "expected a module or a const block containing definitions",
+            ))
+        }
+    };
+
+    Ok(context.finish_recovering(output))
+}
@@ -0,0 +1,341 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+// ext
+
+// ----------------------------------------------------------------
+
+//! Extension traits exposing the crate's free functions as methods on syn nodes.
+//!
+//! ```ignore
+//! use synext::ext::*;
+//!
+//! for field in input.named_fields()? {
+//!     if field.is_option() {
+//!         let each = field.attr_value("builder", "each")?;
+//!     }
+//! }
+//! ```
+
+use proc_macro2::Span;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Attribute, Data, DeriveInput, Field, Ident, LitStr, Path, Type};
+
+use crate::syntax::analysis::parser::try_collect_all_fields;
+use crate::syntax::attr::parser::{
+    mark_attr_consumed, try_extract_attribute_path_value, try_find_marker_attribute,
+};
+use crate::syntax::codegen::generator::try_to_snake_case;
+use crate::syntax::derive::parser::{
+    try_extract_inner_types, try_extract_interior_mutable, try_match_fields_result,
+    try_parse_named_fields_result, try_parse_unnamed_fields_result, try_predicate_contains_macro,
+    try_predicate_contains_verbatim, try_predicate_is_option, try_predicate_is_type,
+    try_predicate_is_vec, try_unwrap_single_type, InteriorMutable, BUILTIN_TYPE_OPTION,
+    BUILTIN_TYPE_VEC,
+};
+
+// ----------------------------------------------------------------
+
+/// Methods on [`syn::Type`], see the `try_predicate_*` and `try_unwrap_*` functions.
+///
+/// @since 0.4.0
+pub trait TypeExt {
+    /// `Option<T>`, see [`try_predicate_is_option`].
+    fn is_option(&self) -> bool;
+    /// `Vec<T>`, see [`try_predicate_is_vec`].
+    fn is_vec(&self) -> bool;
+    /// `ident<..>` with `target_types` generic arguments, see [`try_predicate_is_type`].
+    fn is_type(&self, ident: &str, target_types: usize) -> bool;
+    /// `T` of `Option<T>`, `None` instead of panicking on other types.
+    fn option_inner(&self) -> Option<&Type>;
+    /// `T` of `Vec<T>`, `None` instead of panicking on other types.
+    fn vec_inner(&self) -> Option<&Type>;
+    /// The generic type arguments of the last path segment, see [`try_extract_inner_types`].
+    fn inner_types(&self) -> Option<Vec<&Type>>;
+    /// See [`try_extract_interior_mutable`].
+    fn interior_mutable(&self) -> Option<InteriorMutable<'_>>;
+    /// See [`try_predicate_contains_macro`].
+    fn contains_macro(&self) -> bool;
+    /// See [`try_predicate_contains_verbatim`].
+    fn contains_verbatim(&self) -> bool;
+}
+
+impl TypeExt for Type {
+    fn is_option(&self) -> bool {
+        try_predicate_is_option(self)
+    }
+
+    fn is_vec(&self) -> bool {
+        try_predicate_is_vec(self)
+    }
+
+    fn is_type(&self, ident: &str, target_types: usize) -> bool {
+        try_predicate_is_type(ident, target_types, self)
+    }
+
+    fn option_inner(&self) -> Option<&Type> {
+        try_unwrap_single_type(BUILTIN_TYPE_OPTION, self)
+    }
+
+    fn vec_inner(&self) -> Option<&Type> {
+        try_unwrap_single_type(BUILTIN_TYPE_VEC, self)
+    }
+
+    fn inner_types(&self) -> Option<Vec<&Type>> {
+        try_extract_inner_types(self)
+    }
+
+    fn interior_mutable(&self) -> Option<InteriorMutable<'_>> {
+        try_extract_interior_mutable(self)
+    }
+
+    fn contains_macro(&self) -> bool {
+        try_predicate_contains_macro(self)
+    }
+
+    fn contains_verbatim(&self) -> bool {
+        try_predicate_contains_verbatim(self)
+    }
+}
+
+/// Methods on [`syn::Field`]: the [`TypeExt`] predicates of its type and its helper attributes.
+///
+/// @since 0.4.0
+pub trait FieldExt {
+    fn is_option(&self) -> bool;
+    fn is_vec(&self) -> bool;
+    /// The string value of `key` in `#[attribute(key = "...")]`,
+    /// see [`try_extract_attribute_path_value`].
+    fn attr_value(&self, attribute: &str, key: &str) -> syn::Result<Option<LitStr>>;
+    /// The first `#[name]`/`#[name(...)]` attribute, without marking it consumed.
+    fn find_attr(&self, name: &str) -> Option<&Attribute>;
+    /// Without marking the attribute consumed.
+    fn has_attr(&self, name: &str) -> bool;
+    /// Like [`FieldExt::find_attr`], marking the attribute consumed, see [`mark_attr_consumed`].
+    fn take_attr(&self, name: &str) -> Option<&Attribute>;
+    /// Like [`FieldExt::has_attr`], marking every `#[name]` consumed, see [`mark_attr_consumed`].
+    fn consume_attr(&self, name: &str) -> bool;
+    /// A bare `#[name]`, see [`try_find_marker_attribute`].
+    fn has_marker(&self, name: &str) -> bool;
+}
+
+impl FieldExt for Field {
+    fn is_option(&self) -> bool {
+        self.ty.is_option()
+    }
+
+    fn is_vec(&self) -> bool {
+        self.ty.is_vec()
+    }
+
+    fn attr_value(&self, attribute: &str, key: &str) -> syn::Result<Option<LitStr>> {
+        try_extract_attribute_path_value(&self.attrs, attribute, key)
+    }
+
+    fn find_attr(&self, name: &str) -> Option<&Attribute> {
+        find_attr(&self.attrs, name)
+    }
+
+    fn has_attr(&self, name: &str) -> bool {
+        self.find_attr(name).is_some()
+    }
+
+    fn take_attr(&self, name: &str) -> Option<&Attribute> {
+        take_attr(&self.attrs, name)
+    }
+
+    fn consume_attr(&self, name: &str) -> bool {
+        consume_attr(&self.attrs, name)
+    }
+
+    fn has_marker(&self, name: &str) -> bool {
+        try_find_marker_attribute(&self.attrs, name).is_some()
+    }
+}
+
+/// Methods on [`syn::DeriveInput`], returning spanned errors instead of panicking.
+///
+/// @since 0.4.0
+pub trait DeriveInputExt {
+    /// See [`try_parse_named_fields_result`].
+    fn named_fields(&self) -> syn::Result<&Punctuated<Field, Comma>>;
+    /// See [`try_parse_unnamed_fields_result`].
+    fn unnamed_fields(&self) -> syn::Result<&Punctuated<Field, Comma>>;
+    /// See [`try_match_fields_result`].
+    fn fields(&self) -> syn::Result<&Punctuated<Field, Comma>>;
+    /// The fields of a struct, or of every variant of an enum, see [`try_collect_all_fields`].
+    fn all_fields(&self) -> Vec<&Field>;
+    fn is_struct(&self) -> bool;
+    fn is_enum(&self) -> bool;
+    /// The string value of `key` in the container's `#[attribute(key = "...")]`.
+    fn attr_value(&self, attribute: &str, key: &str) -> syn::Result<Option<LitStr>>;
+    /// The first container `#[name]`/`#[name(...)]` attribute, without marking it consumed.
+    fn find_attr(&self, name: &str) -> Option<&Attribute>;
+    /// Like [`DeriveInputExt::find_attr`], marking the attribute consumed, see [`mark_attr_consumed`].
+    fn take_attr(&self, name: &str) -> Option<&Attribute>;
+}
+
+impl DeriveInputExt for DeriveInput {
+    fn named_fields(&self) -> syn::Result<&Punctuated<Field, Comma>> {
+        try_parse_named_fields_result(self)
+    }
+
+    fn unnamed_fields(&self) -> syn::Result<&Punctuated<Field, Comma>> {
+        try_parse_unnamed_fields_result(self)
+    }
+
+    fn fields(&self) -> syn::Result<&Punctuated<Field, Comma>> {
+        try_match_fields_result(self)
+    }
+
+    fn all_fields(&self) -> Vec<&Field> {
+        try_collect_all_fields(self)
+    }
+
+    fn is_struct(&self) -> bool {
+        matches!(self.data, Data::Struct(_))
+    }
+
+    fn is_enum(&self) -> bool {
+        matches!(self.data, Data::Enum(_))
+    }
+
+    fn attr_value(&self, attribute: &str, key: &str) -> syn::Result<Option<LitStr>> {
+        try_extract_attribute_path_value(&self.attrs, attribute, key)
+    }
+
+    fn find_attr(&self, name: &str) -> Option<&Attribute> {
+        find_attr(&self.attrs, name)
+    }
+
+    fn take_attr(&self, name: &str) -> Option<&Attribute> {
+        take_attr(&self.attrs, name)
+    }
+}
+
+fn find_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs.iter().find(|attr| attr.path.is_ident(name))
+}
+
+fn take_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    find_attr(attrs, name).inspect(|attr| mark_attr_consumed(attr))
+}
+
+fn consume_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(name))
+        .inspect(|attr| mark_attr_consumed(attr))
+        .count()
+        > 0
+}
+
+/// Methods on [`syn::Path`] matching paths regardless of generic arguments and leading `::`.
+///
+/// @since 0.4.0
+pub trait PathExt {
+    /// The last segment is `name`, whatever the qualification: `Vec`, `std::vec::Vec<T>`.
+    fn is_ident_loose(&self, name: &str) -> bool;
+    fn last_ident(&self) -> Option<&Ident>;
+    /// The segments joined with `::`, without generic arguments: `::std::vec::Vec<T>` -> `std::vec::Vec`.
+    fn to_path_string(&self) -> String;
+    /// The path is one of `candidates`, e.g. `&["std::vec::Vec", "Vec"]`.
+    fn matches_any(&self, candidates: &[&str]) -> bool;
+}
+
+impl PathExt for Path {
+    fn is_ident_loose(&self, name: &str) -> bool {
+        self.last_ident().is_some_and(|ident| ident == name)
+    }
+
+    fn last_ident(&self) -> Option<&Ident> {
+        self.segments.last().map(|segment| &segment.ident)
+    }
+
+    fn to_path_string(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    fn matches_any(&self, candidates: &[&str]) -> bool {
+        let path = self.to_path_string();
+        candidates
+            .iter()
+            .any(|candidate| candidate.trim_start_matches("::") == path)
+    }
+}
+
+/// Methods on [`syn::Ident`] deriving new identifiers that keep the original span,
+/// raw identifiers are unrawed first (`r#type` -> `with_type`).
+///
+/// Derived keywords stay raw (`Type` -> `r#type`), a name that can't be an identifier even raw,
+/// e.g. `Self` -> `self` or a prefix with a `-`, is an error spanned at the original identifier.
+///
+/// @since 0.4.0
+pub trait IdentExt {
+    /// `HttpError` -> `http_error`, see [`try_to_snake_case`].
+    fn to_snake_ident(&self) -> syn::Result<Ident>;
+    /// `name` -> `with_name`
+    fn prefixed(&self, prefix: &str) -> syn::Result<Ident>;
+    /// `Hello` -> `HelloBuilder`
+    fn suffixed(&self, suffix: &str) -> syn::Result<Ident>;
+}
+
+impl IdentExt for Ident {
+    fn to_snake_ident(&self) -> syn::Result<Ident> {
+        derived_ident(&try_to_snake_case(&unraw(self)), self.span())
+    }
+
+    fn prefixed(&self, prefix: &str) -> syn::Result<Ident> {
+        derived_ident(&format!("{}{}", prefix, unraw(self)), self.span())
+    }
+
+    fn suffixed(&self, suffix: &str) -> syn::Result<Ident> {
+        derived_ident(&format!("{}{}", unraw(self), suffix), self.span())
+    }
+}
+
+fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    match name.strip_prefix("r#") {
+        Some(name) => name.to_string(),
+        None => name,
+    }
+}
+
+fn derived_ident(name: &str, span: Span) -> syn::Result<Ident> {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        return Ok(Ident::new(name, span));
+    }
+
+    // keywords, e.g. `Type` -> `type`, have to stay raw
+    match name {
+        "self" | "Self" | "super" | "crate" | "_" => None,
+        _ => syn::parse_str::<Ident>(&format!("r#{}", name)).ok(),
+    }
+    .map(|_| Ident::new_raw(name, span))
+    .ok_or_else(|| {
+        syn::Error::new(
+            span,
+            format!("synext: `{}` can't be used as an identifier", name),
+        )
+    })
+}
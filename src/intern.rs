@@ -0,0 +1,147 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// intern
+
+// ----------------------------------------------------------------
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+// ----------------------------------------------------------------
+
+/// A handle to a string stored in an [`Interner`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A local string interner for the repeated small strings (key names, prefixes, generated
+/// method names) created while processing large structs.
+///
+/// Each distinct string is allocated once, shared by the lookup map and the symbol table,
+/// and symbols are handed out in first-seen order, so iteration over [`Interner::iter`]
+/// is deterministic.
+///
+/// # Examples
+///
+///```ignore
+/// let mut interner = Interner::new();
+/// let each = interner.intern("each");
+/// assert_eq!(interner.intern("each"), each);
+/// assert_eq!(interner.resolve(each), "each");
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    lookup: HashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            lookup: HashMap::with_capacity(capacity),
+            strings: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn intern(&mut self, value: &str) -> Symbol {
+        if let Some(symbol) = self.lookup.get(value) {
+            return *symbol;
+        }
+
+        let symbol = Symbol(self.strings.len() as u32);
+        let value: Arc<str> = Arc::from(value);
+        self.strings.push(Arc::clone(&value));
+        self.lookup.insert(value, symbol);
+
+        symbol
+    }
+
+    /// Intern the concatenation `prefix + value` (`"with_" + "name"`), only a temporary buffer
+    /// is allocated when it has been interned before.
+    pub fn intern_concat(&mut self, prefix: &str, value: &str) -> Symbol {
+        let mut buffer = String::with_capacity(prefix.len() + value.len());
+        buffer.push_str(prefix);
+        buffer.push_str(value);
+
+        self.intern(&buffer)
+    }
+
+    pub fn get(&self, value: &str) -> Option<Symbol> {
+        self.lookup.get(value).copied()
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// The interned strings in first-seen order.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (Symbol(index as u32), &**value))
+    }
+}
+
+// ----------------------------------------------------------------
+
+fn global() -> &'static Mutex<HashSet<&'static str>> {
+    static GLOBAL: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    GLOBAL.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Intern a `&'static str` in the process-wide, thread-safe interner, returning the first
+/// copy seen of an equal string.
+///
+/// Only static strings are accepted, so nothing is allocated per string and the interner
+/// cannot grow with user input in a long-lived proc-macro server; derived names such as
+/// `"with_" + field` go to a per-expansion [`Interner`], see [`Interner::intern_concat`].
+///
+/// @since 0.4.0
+pub fn intern(value: &'static str) -> &'static str {
+    let mut set = global()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(interned) = set.get(value) {
+        return interned;
+    }
+    set.insert(value);
+
+    value
+}
@@ -18,10 +18,66 @@
 
 // ----------------------------------------------------------------
 
+#![cfg_attr(synext_diagnostics, feature(proc_macro_diagnostic, proc_macro_span))]
+
+#[doc(inline)]
+pub use syntax::analysis::parser::*;
+#[doc(inline)]
+pub use syntax::attr::inherit::*;
+#[doc(inline)]
+pub use syntax::attr::literal::*;
 /// @since 0.3.0
 #[doc(inline)]
 pub use syntax::attr::parser::*;
 #[doc(inline)]
+pub use syntax::attr::resolve::*;
+#[doc(inline)]
+pub use syntax::attr::schema::*;
+#[doc(inline)]
+pub use syntax::attr::template::*;
+#[doc(inline)]
+pub use syntax::codegen::generator::*;
+#[doc(inline)]
 pub use syntax::derive::parser::*;
+#[doc(inline)]
+pub use syntax::generics::parser::*;
+#[doc(inline)]
+pub use syntax::peek::parser::*;
+#[doc(inline)]
+pub use syntax::types::compare::*;
+#[doc(inline)]
+pub use syntax::types::registry::*;
+#[doc(inline)]
+pub use syntax::types::rewrite::*;
+#[doc(inline)]
+pub use syntax::types::tree::*;
 
+/// @since 0.4.0
+#[cfg(feature = "syn2")]
+pub mod compat;
+/// @since 0.4.0
+#[cfg(feature = "darling")]
+pub mod darling;
+/// @since 0.4.0
+pub mod diag;
+/// @since 0.4.0
+pub mod error;
+/// @since 0.4.0
+pub mod expand;
+/// @since 0.4.0
+pub mod ext;
+/// @since 0.4.0
+pub mod intern;
+#[doc(hidden)]
+pub mod macros;
+/// @since 0.4.0
+pub mod model;
 pub mod syntax;
+
+/// @since 0.4.0
+pub mod testing;
+
+/// @since 0.4.0
+pub mod v1;
+/// @since 0.4.0
+pub mod v2;
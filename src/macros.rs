@@ -0,0 +1,191 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// macros
+
+// ----------------------------------------------------------------
+
+extern crate proc_macro;
+
+use std::fmt::Display;
+
+use proc_macro2::Span;
+use quote::ToTokens;
+
+use crate::syntax::derive::parser::{make_new_compile_error, make_new_spanned_compile_error};
+
+#[doc(hidden)]
+pub use syn;
+
+// ----------------------------------------------------------------
+
+/// Build a `compile_error!` [`proc_macro::TokenStream`] reported on a [`proc_macro2::Span`]
+/// or on any [`quote::ToTokens`] value, with a `format!`-style message.
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_derive(Builder, attributes(builder))]
+/// pub fn builder(input: TokenStream) -> TokenStream {
+///     let input = parse_macro_input!(input as DeriveInput);
+///     if !matches!(input.data, Data::Struct(_)) {
+///         return synext::compile_err!(input.ident, "`Builder` only supports structs, got `{}`", input.ident);
+///     }
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+#[macro_export]
+macro_rules! compile_err {
+    ($target:expr, $($message:tt)+) => {{
+        #[allow(unused_imports)]
+        use $crate::macros::{SpanTarget as _, TokensTarget as _};
+        (&$crate::macros::ErrorTarget(&$target))
+            .compile_error(::std::format!($($message)+))
+    }};
+}
+
+/// Early-return a [`compile_err!`] from a proc-macro function.
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_derive(Builder, attributes(builder))]
+/// pub fn builder(input: TokenStream) -> TokenStream {
+///     let input = parse_macro_input!(input as DeriveInput);
+///     for field in try_match_fields(&input) {
+///         if try_predicate_is_not_option(&field.ty) {
+///             synext::bail!(field.ty, "expected `Option<_>`, got `{}`", field.ty.to_token_stream());
+///         }
+///     }
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+#[macro_export]
+macro_rules! bail {
+    ($target:expr, $($message:tt)+) => {
+        return $crate::compile_err!($target, $($message)+)
+    };
+}
+
+/// Early-return a [`compile_err!`] unless a predicate holds, typically one of the
+/// `try_predicate_*` helpers. Without a message, the predicate itself is reported.
+///
+/// # Examples
+///
+///```ignore
+/// for field in try_match_fields(&input) {
+///     synext::ensure!(
+///         try_predicate_is_option(&field.ty),
+///         field.ty,
+///         "`#[builder(optional)]` expects `Option<_>`"
+///     );
+///     synext::ensure!(try_predicate_is_not_vec(&field.ty), field.ty);
+///     // -> "synext: `try_predicate_is_not_vec(&field.ty)` does not hold"
+/// }
+/// ```
+/// @since 0.4.0
+#[macro_export]
+macro_rules! ensure {
+    ($predicate:expr, $target:expr $(,)?) => {
+        $crate::ensure!(
+            $predicate,
+            $target,
+            "synext: `{}` does not hold",
+            ::std::stringify!($predicate)
+        )
+    };
+    ($predicate:expr, $target:expr, $($message:tt)+) => {
+        if !$predicate {
+            $crate::bail!($target, $($message)+);
+        }
+    };
+}
+
+/// Define `try_predicate_is_*`/`try_unwrap_*` functions for a custom single-argument wrapper,
+/// matched like the built-in ones and the types of a [`crate::TypeRegistry`]: bare or qualified
+/// paths (`Shared<T>`, `crate::sync::Shared<T>`), through [`crate::try_unwrap_types_any`].
+///
+/// # Examples
+///
+///```ignore
+/// synext::define_predicates! {
+///     /// `Shared<T>` service handles.
+///     pub Shared => try_predicate_is_shared, try_unwrap_shared;
+///     pub(crate) Lazy => try_predicate_is_lazy, try_unwrap_lazy;
+/// }
+///
+/// ->
+/// pub fn try_unwrap_shared(ty: &syn::Type) -> Option<&syn::Type> { ... }
+/// pub fn try_predicate_is_shared(ty: &syn::Type) -> bool { ... }
+/// ```
+/// @since 0.4.0
+#[macro_export]
+macro_rules! define_predicates {
+    ($($(#[$meta:meta])* $vis:vis $wrapper:ident => $predicate:ident, $unwrap:ident;)*) => {
+        $(
+            $(#[$meta])*
+            #[doc = ::std::concat!("\n\nTry unwrap the type argument of `", ::std::stringify!($wrapper), "<T>`.")]
+            $vis fn $unwrap(ty: &$crate::macros::syn::Type) -> ::std::option::Option<&$crate::macros::syn::Type> {
+                $crate::try_unwrap_types_in(::std::stringify!($wrapper), 1..=1, ty).map(|types| types[0])
+            }
+
+            $(#[$meta])*
+            #[doc = ::std::concat!("\n\nTry to predicate that the type is `", ::std::stringify!($wrapper), "<T>`.")]
+            $vis fn $predicate(ty: &$crate::macros::syn::Type) -> bool {
+                $unwrap(ty).is_some()
+            }
+        )*
+    };
+}
+
+// ---------------------------------------------------------------- macros.support
+
+/// Dispatches [`compile_err!`] targets: spans go to [`make_new_compile_error`], everything
+/// else to [`make_new_spanned_compile_error`].
+#[doc(hidden)]
+pub struct ErrorTarget<T>(pub T);
+
+#[doc(hidden)]
+pub trait SpanTarget {
+    fn compile_error<M: Display>(&self, message: M) -> proc_macro::TokenStream;
+}
+
+impl SpanTarget for ErrorTarget<&Span> {
+    fn compile_error<M: Display>(&self, message: M) -> proc_macro::TokenStream {
+        make_new_compile_error(*self.0, message)
+    }
+}
+
+impl SpanTarget for ErrorTarget<&&Span> {
+    fn compile_error<M: Display>(&self, message: M) -> proc_macro::TokenStream {
+        make_new_compile_error(**self.0, message)
+    }
+}
+
+#[doc(hidden)]
+pub trait TokensTarget {
+    fn compile_error<M: Display>(&self, message: M) -> proc_macro::TokenStream;
+}
+
+impl<T: ToTokens> TokensTarget for &ErrorTarget<&T> {
+    fn compile_error<M: Display>(&self, message: M) -> proc_macro::TokenStream {
+        make_new_spanned_compile_error(self.0, message)
+    }
+}
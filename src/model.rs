@@ -0,0 +1,259 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// model
+
+// ----------------------------------------------------------------
+
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{
+    Attribute, Data, DataStruct, DeriveInput, Field, Fields, Ident, Lit, Member, Meta, NestedMeta,
+    Type,
+};
+
+use crate::diag::ErrorAccumulator;
+use crate::syntax::attr::parser::mark_attr_consumed;
+use crate::syntax::attr::schema::{AttributeValue, AttributeValues};
+use crate::syntax::derive::parser::{try_predicate_is_option, try_predicate_is_vec};
+
+// ----------------------------------------------------------------
+
+/// The helper attributes of one item, each `#[name(...)]` parsed exactly once.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Default)]
+pub struct HelperAttributes {
+    entries: Vec<(String, AttributeValues)>,
+}
+
+impl HelperAttributes {
+    /// Parse every `#[helper(...)]` of `attrs` whose path is one of `helpers`,
+    /// collecting all problems into one combined [`syn::Error`].
+    ///
+    /// Nested lists are kept as nested values: `#[builder(setter(into, prefix = "with"))]`.
+    pub fn parse(attrs: &[Attribute], helpers: &[&str]) -> syn::Result<Self> {
+        let mut parsed = HelperAttributes::default();
+        let mut errors = ErrorAccumulator::new();
+
+        for attr in attrs {
+            let helper = match helpers.iter().find(|helper| attr.path.is_ident(helper)) {
+                Some(helper) => *helper,
+                None => continue,
+            };
+            mark_attr_consumed(attr);
+            let result = parse_helper_attribute(attr);
+            match result {
+                Ok(values) => match parsed.entries.iter_mut().find(|(name, _)| name == helper) {
+                    Some((_, existing)) => existing.extend(values),
+                    None => parsed.entries.push((helper.to_string(), values)),
+                },
+                Err(error) => errors.push(error),
+            }
+        }
+
+        errors.finish_with(parsed)
+    }
+
+    pub fn get(&self, helper: &str) -> Option<&AttributeValues> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == helper)
+            .map(|(_, values)| values)
+    }
+
+    pub fn contains(&self, helper: &str) -> bool {
+        self.get(helper).is_some()
+    }
+
+    pub fn value(&self, helper: &str, key: &str) -> Option<&AttributeValue> {
+        self.get(helper)?.get(key)
+    }
+
+    pub fn lit(&self, helper: &str, key: &str) -> Option<&Lit> {
+        self.value(helper, key)?.value.as_ref()
+    }
+
+    pub fn str(&self, helper: &str, key: &str) -> Option<String> {
+        self.get(helper)?.str(key)
+    }
+
+    pub fn flag(&self, helper: &str, key: &str) -> bool {
+        self.get(helper).is_some_and(|values| values.flag(key))
+    }
+
+    /// The values of the nested `key(...)` list, e.g. `#[builder(setter(into))]`.
+    pub fn nested(&self, helper: &str, key: &str) -> Option<&AttributeValues> {
+        self.get(helper)?.nested(key)
+    }
+
+    /// The parsed helpers in the order they first appear.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &AttributeValues)> {
+        self.entries
+            .iter()
+            .map(|(name, values)| (name.as_str(), values))
+    }
+}
+
+fn parse_helper_attribute(attr: &Attribute) -> syn::Result<AttributeValues> {
+    match attr.parse_meta()? {
+        Meta::List(list) => parse_helper_values(list.nested),
+        Meta::Path(_) => Ok(AttributeValues::default()),
+        Meta::NameValue(nv) => Err(syn::Error::new_spanned(
+            nv,
+            "expected a list of `key = value` arguments",
+        )),
+    }
+}
+
+fn parse_helper_values(nested: Punctuated<NestedMeta, Comma>) -> syn::Result<AttributeValues> {
+    let mut values = AttributeValues::default();
+    for nested in nested {
+        match nested {
+            NestedMeta::Meta(Meta::Path(path)) => values.push(AttributeValue {
+                key: path,
+                value: None,
+                nested: None,
+            }),
+            NestedMeta::Meta(Meta::NameValue(nv)) => values.push(AttributeValue {
+                key: nv.path,
+                value: Some(nv.lit),
+                nested: None,
+            }),
+            NestedMeta::Meta(Meta::List(list)) => values.push(AttributeValue {
+                key: list.path,
+                value: None,
+                nested: Some(parse_helper_values(list.nested)?),
+            }),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "expected `key = value`, `key` or `key(...)`",
+                ))
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+// ----------------------------------------------------------------
+
+/// A field with its helper attributes and type predicates computed once.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct FieldModel<'a> {
+    pub field: &'a Field,
+    pub index: usize,
+    pub member: Member,
+    pub helpers: HelperAttributes,
+    pub is_option: bool,
+    pub is_vec: bool,
+}
+
+impl<'a> FieldModel<'a> {
+    pub fn parse(field: &'a Field, index: usize, helpers: &[&str]) -> syn::Result<Self> {
+        let member = match field.ident {
+            Some(ref ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        };
+
+        Ok(Self {
+            field,
+            index,
+            member,
+            helpers: HelperAttributes::parse(&field.attrs, helpers)?,
+            is_option: try_predicate_is_option(&field.ty),
+            is_vec: try_predicate_is_vec(&field.ty),
+        })
+    }
+
+    pub fn ident(&self) -> Option<&'a Ident> {
+        self.field.ident.as_ref()
+    }
+
+    pub fn ty(&self) -> &'a Type {
+        &self.field.ty
+    }
+}
+
+/// A struct with every field pre-parsed, so a macro processing hundreds of fields and many keys
+/// stays `O(fields × keys)` instead of re-parsing each attribute for every key it looks up.
+///
+/// # Examples
+///
+///```ignore
+/// let model = StructModel::parse(&derive_input, &["builder"])?;
+/// for field in &model.fields {
+///     let each = field.helpers.str("builder", "each");
+///     let skip = field.helpers.flag("builder", "skip");
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct StructModel<'a> {
+    pub input: &'a DeriveInput,
+    pub helpers: HelperAttributes,
+    pub fields: Vec<FieldModel<'a>>,
+}
+
+impl<'a> StructModel<'a> {
+    /// Parse the container and field helper attributes of a named or tuple struct,
+    /// collecting every field's problems into one combined [`syn::Error`].
+    pub fn parse(input: &'a DeriveInput, helpers: &[&str]) -> syn::Result<Self> {
+        let fields: Vec<&Field> = match &input.data {
+            Data::Struct(DataStruct { fields, .. }) => match fields {
+                Fields::Named(named) => named.named.iter().collect(),
+                Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect(),
+                Fields::Unit => Vec::new(),
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    format!(
+                        "synext: Only structs are supported! target:`{}`",
+                        input.ident
+                    ),
+                ))
+            }
+        };
+        let mut errors = ErrorAccumulator::new();
+
+        let container = errors
+            .handle(HelperAttributes::parse(&input.attrs, helpers))
+            .unwrap_or_default();
+
+        let models = fields
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, field)| errors.handle(FieldModel::parse(field, index, helpers)))
+            .collect();
+
+        errors.finish_with(Self {
+            input,
+            helpers: container,
+            fields: models,
+        })
+    }
+
+    pub fn ident(&self) -> &'a Ident {
+        &self.input.ident
+    }
+}
@@ -22,3 +22,18 @@ pub mod derive;
 
 /// @since 0.3.0
 pub mod attr;
+
+/// @since 0.4.0
+pub mod generics;
+
+/// @since 0.4.0
+pub mod analysis;
+
+/// @since 0.4.0
+pub mod codegen;
+
+/// @since 0.4.0
+pub mod peek;
+
+/// @since 0.4.0
+pub mod types;
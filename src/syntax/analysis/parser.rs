@@ -0,0 +1,1766 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/analysis/parser
+
+// ----------------------------------------------------------------
+
+use std::collections::VecDeque;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprAsync, ExprAwait, ExprBlock, ExprBreak, ExprClosure,
+    ExprContinue, ExprField, ExprForLoop, ExprLoop, ExprReturn, ExprTry, ExprWhile, Field, Fields,
+    GenericArgument, GenericParam, Ident, Item, Lit, Member, Meta, MetaNameValue, NestedMeta, Path,
+    PathArguments, TraitBoundModifier, Type, TypeParamBound, TypePath, Variant, WherePredicate,
+};
+
+use crate::diag::ErrorAccumulator;
+use crate::syntax::attr::parser::{
+    mark_attr_consumed, try_extract_attribute_path_value, try_find_marker_attribute,
+};
+use crate::syntax::derive::parser::try_unwrap_single_type;
+use crate::syntax::types::compare::{type_to_canonical_string, types_equal};
+
+// ----------------------------------------------------------------
+
+/// The `Sized`-ness report of a [`syn::DeriveInput`].
+///
+/// Codegen helpers consult it before emitting by-value constructors or `Default` impls,
+/// which cannot compile when the last field may be a dynamically sized type.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct UnsizedReport<'a> {
+    /// Type parameters relaxed with `?Sized`, either inline or in the where clause.
+    pub maybe_unsized_params: Vec<&'a Ident>,
+    /// The trailing field whose type may be unsized (`str`, `[T]`, `dyn Trait`, `T: ?Sized`).
+    pub unsized_field: Option<&'a Field>,
+}
+
+impl<'a> UnsizedReport<'a> {
+    /// Whether the item is guaranteed to be `Sized`.
+    pub fn is_sized(&self) -> bool {
+        self.unsized_field.is_none()
+    }
+
+    /// Whether by-value construction (`fn new(..) -> Self`, `Default`) can be generated.
+    pub fn supports_by_value(&self) -> bool {
+        self.is_sized()
+    }
+}
+
+/// Try to analyze whether [`syn::DeriveInput`] may be unsized.
+///
+/// Only the last field of a struct may be a dynamically sized type,
+/// enums and unions are always reported as `Sized`.
+///
+/// @since 0.4.0
+pub fn try_analyze_unsized(input: &DeriveInput) -> UnsizedReport<'_> {
+    let maybe_unsized_params = try_extract_maybe_unsized_params(input);
+
+    let unsized_field = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.last(),
+            Fields::Unnamed(fields) => fields.unnamed.last(),
+            Fields::Unit => None,
+        },
+        _ => None,
+    }
+    .filter(|field| try_predicate_is_maybe_unsized_type(&field.ty, &maybe_unsized_params));
+
+    UnsizedReport {
+        maybe_unsized_params,
+        unsized_field,
+    }
+}
+
+/// Try to extract the type parameters relaxed with `?Sized`.
+///
+/// - `struct Foo<T: ?Sized>` -> `[T]`
+/// - `struct Foo<T> where T: ?Sized` -> `[T]`
+///
+/// @since 0.4.0
+pub fn try_extract_maybe_unsized_params(input: &DeriveInput) -> Vec<&Ident> {
+    let mut params = Vec::new();
+
+    for param in &input.generics.params {
+        if let GenericParam::Type(tp) = param {
+            if try_predicate_bounds_contain_maybe_sized(tp.bounds.iter()) {
+                params.push(&tp.ident);
+            }
+        }
+    }
+
+    if let Some(ref where_clause) = input.generics.where_clause {
+        for predicate in &where_clause.predicates {
+            if let WherePredicate::Type(pt) = predicate {
+                if let Type::Path(ref tp) = pt.bounded_ty {
+                    if let Some(ident) = tp.path.get_ident() {
+                        if try_predicate_bounds_contain_maybe_sized(pt.bounds.iter())
+                            && !params.contains(&ident)
+                        {
+                            params.push(ident);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    params
+}
+
+/// Try to predicate that [`syn::Type`] may be dynamically sized.
+///
+/// - `str`, `[T]`, `dyn Trait` -> `true`
+/// - `T` where `T: ?Sized` -> `true`
+/// - `String`, `Box<str>`, `&[T]` -> `false`
+///
+/// @since 0.4.0
+pub fn try_predicate_is_maybe_unsized_type(ty: &Type, maybe_unsized_params: &[&Ident]) -> bool {
+    match ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Paren(paren) => {
+            try_predicate_is_maybe_unsized_type(&paren.elem, maybe_unsized_params)
+        }
+        Type::Group(group) => {
+            try_predicate_is_maybe_unsized_type(&group.elem, maybe_unsized_params)
+        }
+        Type::Path(tp) if tp.qself.is_none() => match tp.path.get_ident() {
+            Some(ident) => ident == "str" || maybe_unsized_params.contains(&ident),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn try_predicate_bounds_contain_maybe_sized<'a>(
+    mut bounds: impl Iterator<Item = &'a TypeParamBound>,
+) -> bool {
+    bounds.any(|bound| match bound {
+        TypeParamBound::Trait(tb) => {
+            matches!(tb.modifier, TraitBoundModifier::Maybe(_)) && tb.path.is_ident("Sized")
+        }
+        TypeParamBound::Lifetime(_) => false,
+    })
+}
+
+// ----------------------------------------------------------------
+
+/// The best-effort `Send`/`Sync` status of a [`syn::Type`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoTraits {
+    pub send: bool,
+    pub sync: bool,
+}
+
+impl AutoTraits {
+    pub const ALL: AutoTraits = AutoTraits {
+        send: true,
+        sync: true,
+    };
+    pub const NONE: AutoTraits = AutoTraits {
+        send: false,
+        sync: false,
+    };
+
+    pub fn and(self, other: AutoTraits) -> AutoTraits {
+        AutoTraits {
+            send: self.send && other.send,
+            sync: self.sync && other.sync,
+        }
+    }
+}
+
+/// The `Send`/`Sync` report of a [`syn::DeriveInput`], listing the offending fields.
+///
+/// Generated companion types that hold the same fields inherit these auto traits,
+/// so macros can emit documented `unsafe impl` opt-ins or warnings instead of silent surprises.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Default)]
+pub struct AutoTraitReport<'a> {
+    pub non_send_fields: Vec<&'a Field>,
+    pub non_sync_fields: Vec<&'a Field>,
+}
+
+impl<'a> AutoTraitReport<'a> {
+    pub fn is_send(&self) -> bool {
+        self.non_send_fields.is_empty()
+    }
+
+    pub fn is_sync(&self) -> bool {
+        self.non_sync_fields.is_empty()
+    }
+}
+
+/// Try to analyze the `Send`/`Sync` status of every field of [`syn::DeriveInput`],
+/// including the fields of enum variants.
+///
+/// The analysis is best-effort: it only knows the std types that opt out of the auto traits
+/// (`Rc`, `rc::Weak`, `Cell`, `RefCell`, `UnsafeCell`, `NonNull`, raw pointers, lock guards, `dyn Trait`
+/// without `Send`/`Sync` bounds), generic parameters and unknown types are assumed to be `Send + Sync`.
+///
+/// @since 0.4.0
+pub fn try_analyze_auto_traits(input: &DeriveInput) -> AutoTraitReport<'_> {
+    let mut report = AutoTraitReport::default();
+
+    for field in try_collect_all_fields(input) {
+        let traits = try_analyze_type_auto_traits(&field.ty);
+        if !traits.send {
+            report.non_send_fields.push(field);
+        }
+        if !traits.sync {
+            report.non_sync_fields.push(field);
+        }
+    }
+
+    report
+}
+
+/// Try to analyze the best-effort `Send`/`Sync` status of [`syn::Type`].
+///
+/// - `Rc<T>`, `*const T` -> `!Send + !Sync`
+/// - `RefCell<T>` -> `Send + !Sync`
+/// - `Arc<RefCell<T>>` -> `!Send + !Sync`
+/// - `Vec<String>` -> `Send + Sync`
+///
+/// @since 0.4.0
+pub fn try_analyze_type_auto_traits(ty: &Type) -> AutoTraits {
+    match ty {
+        Type::Ptr(_) => AutoTraits::NONE,
+        Type::Reference(reference) => {
+            let inner = try_analyze_type_auto_traits(&reference.elem);
+            if reference.mutability.is_some() {
+                inner
+            } else {
+                AutoTraits {
+                    send: inner.sync,
+                    sync: inner.sync,
+                }
+            }
+        }
+        Type::Paren(paren) => try_analyze_type_auto_traits(&paren.elem),
+        Type::Group(group) => try_analyze_type_auto_traits(&group.elem),
+        Type::Array(array) => try_analyze_type_auto_traits(&array.elem),
+        Type::Slice(slice) => try_analyze_type_auto_traits(&slice.elem),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .map(try_analyze_type_auto_traits)
+            .fold(AutoTraits::ALL, AutoTraits::and),
+        Type::TraitObject(object) => AutoTraits {
+            send: try_predicate_bounds_contain_trait(object.bounds.iter(), "Send"),
+            sync: try_predicate_bounds_contain_trait(object.bounds.iter(), "Sync"),
+        },
+        Type::Path(tp) => {
+            let segment = match tp.path.segments.last() {
+                Some(segment) => segment,
+                None => return AutoTraits::ALL,
+            };
+            let inner = try_analyze_generic_arguments_auto_traits(&segment.arguments);
+            let in_rc_module = tp.path.segments.iter().any(|s| s.ident == "rc");
+
+            match segment.ident.to_string().as_str() {
+                "Rc" | "NonNull" => AutoTraits::NONE,
+                "Weak" if in_rc_module => AutoTraits::NONE,
+                "Cell" | "RefCell" | "UnsafeCell" | "OnceCell" => AutoTraits {
+                    send: inner.send,
+                    sync: false,
+                },
+                "MutexGuard" | "RwLockReadGuard" | "RwLockWriteGuard" => AutoTraits {
+                    send: false,
+                    sync: inner.sync,
+                },
+                "Arc" | "Weak" => {
+                    let both = inner.send && inner.sync;
+                    AutoTraits {
+                        send: both,
+                        sync: both,
+                    }
+                }
+                "Mutex" => AutoTraits {
+                    send: inner.send,
+                    sync: inner.send,
+                },
+                "RwLock" => AutoTraits {
+                    send: inner.send,
+                    sync: inner.send && inner.sync,
+                },
+                _ => inner,
+            }
+        }
+        _ => AutoTraits::ALL,
+    }
+}
+
+fn try_analyze_generic_arguments_auto_traits(arguments: &PathArguments) -> AutoTraits {
+    match arguments {
+        PathArguments::AngleBracketed(bracketed) => bracketed
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(try_analyze_type_auto_traits(ty)),
+                _ => None,
+            })
+            .fold(AutoTraits::ALL, AutoTraits::and),
+        _ => AutoTraits::ALL,
+    }
+}
+
+fn try_predicate_bounds_contain_trait<'a>(
+    mut bounds: impl Iterator<Item = &'a TypeParamBound>,
+    name: &str,
+) -> bool {
+    bounds.any(|bound| match bound {
+        TypeParamBound::Trait(tb) => tb
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == name)
+            .unwrap_or(false),
+        TypeParamBound::Lifetime(_) => false,
+    })
+}
+
+// ----------------------------------------------------------------
+
+/// Try to collect every field of [`syn::DeriveInput`] in declaration order:
+/// struct fields, then the fields of each enum variant, then union fields.
+///
+/// @since 0.4.0
+pub fn try_collect_all_fields(input: &DeriveInput) -> Vec<&Field> {
+    match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(data) => data.fields.named.iter().collect(),
+    }
+}
+
+// ---------------------------------------------------------------- async.runtime
+
+/// Crate roots recognized as async runtimes by [`try_classify_async_type`].
+///
+/// @since 0.4.0
+#[cfg(feature = "async")]
+pub const ASYNC_RUNTIME_CRATES: [&str; 5] =
+    ["tokio", "async_std", "futures", "smol", "async_channel"];
+
+/// The kind of a known async runtime wrapper type.
+///
+/// @since 0.4.0
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncTypeKind {
+    Mutex,
+    RwLock,
+    Semaphore,
+    Notify,
+    JoinHandle,
+    JoinSet,
+    Sender,
+    Receiver,
+    UnboundedSender,
+    UnboundedReceiver,
+}
+
+#[cfg(feature = "async")]
+impl AsyncTypeKind {
+    /// Whether access to the wrapped value goes through `.await` (locks and receivers).
+    pub fn requires_await(&self) -> bool {
+        !matches!(self, AsyncTypeKind::UnboundedSender | AsyncTypeKind::Notify)
+    }
+}
+
+/// A classified async runtime wrapper type.
+///
+/// @since 0.4.0
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AsyncType<'a> {
+    pub kind: AsyncTypeKind,
+    /// The wrapped type, e.g. `T` of `tokio::sync::Mutex<T>` or `mpsc::Sender<T>`.
+    pub inner: Option<&'a Type>,
+}
+
+/// Try to classify [`syn::Type`] as a known async runtime wrapper.
+///
+/// Names shared with `std` (`Mutex`, `RwLock`, `JoinHandle`, `Sender`, `Receiver`) are only
+/// classified when the path names a runtime crate from [`ASYNC_RUNTIME_CRATES`], e.g.
+/// `tokio::sync::Mutex<T>` or `tokio::sync::mpsc::Sender<T>`. Async-only names
+/// (`Semaphore`, `Notify`, `JoinSet`, `UnboundedSender`, `UnboundedReceiver`) are classified bare as well.
+///
+/// @since 0.4.0
+#[cfg(feature = "async")]
+pub fn try_classify_async_type(ty: &Type) -> Option<AsyncType<'_>> {
+    let path = match ty {
+        Type::Path(tp) if tp.qself.is_none() => &tp.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    let qualified = path
+        .segments
+        .iter()
+        .any(|s| ASYNC_RUNTIME_CRATES.iter().any(|c| s.ident == c));
+
+    let kind = match (segment.ident.to_string().as_str(), qualified) {
+        ("Semaphore", _) => AsyncTypeKind::Semaphore,
+        ("Notify", _) => AsyncTypeKind::Notify,
+        ("JoinSet", _) => AsyncTypeKind::JoinSet,
+        ("UnboundedSender", _) => AsyncTypeKind::UnboundedSender,
+        ("UnboundedReceiver", _) => AsyncTypeKind::UnboundedReceiver,
+        ("Mutex", true) => AsyncTypeKind::Mutex,
+        ("RwLock", true) => AsyncTypeKind::RwLock,
+        ("JoinHandle", true) => AsyncTypeKind::JoinHandle,
+        ("Sender", true) => AsyncTypeKind::Sender,
+        ("Receiver", true) => AsyncTypeKind::Receiver,
+        _ => return None,
+    };
+
+    let inner = match &segment.arguments {
+        PathArguments::AngleBracketed(bracketed) => {
+            bracketed.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    };
+
+    Some(AsyncType { kind, inner })
+}
+
+/// Try to predicate that [`syn::Type`] is a known async runtime wrapper, see [`try_classify_async_type`].
+///
+/// @since 0.4.0
+#[cfg(feature = "async")]
+pub fn try_predicate_is_async_type(ty: &Type) -> bool {
+    try_classify_async_type(ty).is_some()
+}
+
+// ---------------------------------------------------------------- error.type
+
+/// Try to predicate that [`syn::Type`] is a boxed trait-object error:
+/// `Box<dyn Error>`, `Box<dyn std::error::Error + Send + Sync>`, ...
+///
+/// @since 0.4.0
+pub fn try_predicate_is_boxed_error(ty: &Type) -> bool {
+    match try_unwrap_single_type("Box", ty) {
+        Some(Type::TraitObject(object)) => {
+            try_predicate_bounds_contain_trait(object.bounds.iter(), "Error")
+        }
+        _ => false,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is `anyhow::Error` (bare `Error` is ambiguous and not matched).
+///
+/// @since 0.4.0
+pub fn try_predicate_is_anyhow_error(ty: &Type) -> bool {
+    match ty {
+        Type::Path(tp) if tp.qself.is_none() => {
+            let segments = &tp.path.segments;
+            segments.len() >= 2
+                && segments[segments.len() - 2].ident == "anyhow"
+                && segments[segments.len() - 1].ident == "Error"
+        }
+        _ => false,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a type-erased error, see
+/// [`try_predicate_is_boxed_error`] and [`try_predicate_is_anyhow_error`].
+///
+/// @since 0.4.0
+pub fn try_predicate_is_dyn_error(ty: &Type) -> bool {
+    try_predicate_is_boxed_error(ty) || try_predicate_is_anyhow_error(ty)
+}
+
+/// The thiserror-style markers of an error field.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorMarkers<'a> {
+    /// `#[source]`
+    pub source: Option<&'a Attribute>,
+    /// `#[from]`, which implies `#[source]`.
+    pub from: Option<&'a Attribute>,
+    /// `#[backtrace]`
+    pub backtrace: Option<&'a Attribute>,
+}
+
+impl<'a> ErrorMarkers<'a> {
+    /// Whether the field is the error source, explicitly or through `#[from]`.
+    pub fn is_source(&self) -> bool {
+        self.source.is_some() || self.from.is_some()
+    }
+}
+
+/// Try to extract the `#[source]`/`#[from]`/`#[backtrace]` markers of [`syn::Field`].
+///
+/// @since 0.4.0
+pub fn try_extract_error_markers(field: &Field) -> ErrorMarkers<'_> {
+    ErrorMarkers {
+        source: try_find_marker_attribute(&field.attrs, "source"),
+        from: try_find_marker_attribute(&field.attrs, "from"),
+        backtrace: try_find_marker_attribute(&field.attrs, "backtrace"),
+    }
+}
+
+/// Try to find the source field among `fields`: the first field marked `#[source]`/`#[from]`,
+/// otherwise the field named `source` (the thiserror convention).
+///
+/// @since 0.4.0
+pub fn try_find_error_source_field<'a, I>(fields: I) -> Option<&'a Field>
+where
+    I: IntoIterator<Item = &'a Field>,
+{
+    let mut named_source = None;
+    for field in fields {
+        if try_extract_error_markers(field).is_source() {
+            return Some(field);
+        }
+        if named_source.is_none() && field.ident.as_ref().is_some_and(|ident| ident == "source") {
+            named_source = Some(field);
+        }
+    }
+
+    named_source
+}
+
+// ---------------------------------------------------------------- from.conversion
+
+/// A `#[from]` conversion: `impl From<source_ty> for Self` built from `member` of `variant`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct FromConversion<'a> {
+    /// The enum variant holding the source, `None` for structs.
+    pub variant: Option<&'a Variant>,
+    pub field: &'a Field,
+    pub member: Member,
+    pub source_ty: &'a Type,
+    /// The `#[backtrace]` field constructed alongside the source, if any.
+    pub backtrace: Option<(Member, &'a Field)>,
+}
+
+impl<'a> FromConversion<'a> {
+    /// Build the `Self::Variant { member: value, .. }` (or `Self { .. }`) expression of the conversion,
+    /// initializing a `#[backtrace]` field with `::core::convert::From::from(Backtrace::capture())`.
+    pub fn to_constructor_tokens(&self, value: &TokenStream) -> TokenStream {
+        let path = match self.variant {
+            Some(variant) => {
+                let ident = &variant.ident;
+                quote!(Self::#ident)
+            }
+            None => quote!(Self),
+        };
+        let member = &self.member;
+        let backtrace = self.backtrace.as_ref().map(|(member, _)| {
+            quote!(#member: ::core::convert::From::from(::std::backtrace::Backtrace::capture()),)
+        });
+
+        quote!(#path { #member: #value, #backtrace })
+    }
+}
+
+/// Try to collect every `#[from]` conversion of [`syn::DeriveInput`].
+///
+/// A `#[from]` field must be the only field of its variant (or struct) apart from an optional
+/// `#[backtrace]` field, and each source type may only be converted from once. Source types are
+/// compared with [`types_equal`], `std::io::Error` and `::std::io::Error` are the same source.
+/// Conflicting declarations produce one combined [`syn::Error`] spanning every offending field.
+///
+/// @since 0.4.0
+pub fn try_collect_from_conversions(input: &DeriveInput) -> syn::Result<Vec<FromConversion<'_>>> {
+    let scopes: Vec<(Option<&Variant>, &Fields)> = match &input.data {
+        Data::Struct(data) => vec![(None, &data.fields)],
+        Data::Enum(data) => data.variants.iter().map(|v| (Some(v), &v.fields)).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+
+    let mut conversions: Vec<FromConversion<'_>> = Vec::new();
+    let mut errors = ErrorAccumulator::new();
+
+    for (variant, fields) in scopes {
+        let mut source: Option<(Member, &Field)> = None;
+        let mut backtrace: Option<(Member, &Field)> = None;
+        let mut others = Vec::new();
+
+        for (index, field) in fields.iter().enumerate() {
+            let member = match field.ident {
+                Some(ref ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(index.into()),
+            };
+            let markers = try_extract_error_markers(field);
+            if let Some(from) = markers.from {
+                if source.is_some() {
+                    errors.push(syn::Error::new_spanned(
+                        from,
+                        "duplicate `#[from]` attribute",
+                    ));
+                    continue;
+                }
+                source = Some((member, field));
+            } else if markers.backtrace.is_some() {
+                backtrace = Some((member, field));
+            } else {
+                others.push(field);
+            }
+        }
+
+        let (member, field) = match source {
+            Some(source) => source,
+            None => continue,
+        };
+        if !others.is_empty() {
+            for other in others {
+                errors.push(syn::Error::new_spanned(
+                    other,
+                    "a `#[from]` variant may only contain the source and an optional `#[backtrace]` field",
+                ));
+            }
+            continue;
+        }
+
+        conversions.push(FromConversion {
+            variant,
+            field,
+            member,
+            source_ty: &field.ty,
+            backtrace,
+        });
+    }
+
+    for (index, conversion) in conversions.iter().enumerate() {
+        let first = conversions[..index]
+            .iter()
+            .find(|c| types_equal(c.source_ty, conversion.source_ty));
+        if let Some(first) = first {
+            let key = type_to_canonical_string(conversion.source_ty);
+            let mut error = syn::Error::new_spanned(
+                conversion.source_ty,
+                format!("conflicting `#[from]` conversion from `{}`", key),
+            );
+            error.combine(syn::Error::new_spanned(
+                first.source_ty,
+                format!("`{}` is first converted here", key),
+            ));
+            errors.push(error);
+        }
+    }
+
+    errors.finish_with(conversions)
+}
+
+// ---------------------------------------------------------------- state.machine
+
+/// One `#[attribute(from = "A", to = "B", on = "event")]` transition.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct Transition<'a> {
+    pub from: &'a Variant,
+    pub to: &'a Variant,
+    pub event: Ident,
+    pub attr: &'a Attribute,
+}
+
+/// The typed transition table of a state enum, see [`try_parse_transition_table`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct TransitionTable<'a> {
+    pub initial: &'a Variant,
+    pub states: Vec<&'a Variant>,
+    pub transitions: Vec<Transition<'a>>,
+}
+
+impl<'a> TransitionTable<'a> {
+    /// The distinct events in declaration order.
+    pub fn events(&self) -> Vec<&Ident> {
+        let mut events: Vec<&Ident> = Vec::new();
+        for transition in &self.transitions {
+            if !events.contains(&&transition.event) {
+                events.push(&transition.event);
+            }
+        }
+
+        events
+    }
+
+    /// The transitions leaving `state`.
+    pub fn transitions_from(&self, state: &Ident) -> Vec<&Transition<'a>> {
+        self.transitions
+            .iter()
+            .filter(|transition| transition.from.ident == *state)
+            .collect()
+    }
+
+    /// The target of `event` fired in `state`, if any.
+    pub fn target(&self, state: &Ident, event: &Ident) -> Option<&'a Variant> {
+        self.transitions
+            .iter()
+            .find(|transition| transition.from.ident == *state && transition.event == *event)
+            .map(|transition| transition.to)
+    }
+}
+
+/// Try to parse the transition table of a state enum.
+///
+/// Every variant is a state, the first variant is the initial state unless another one is marked
+/// `#[attribute(initial)]`. Transitions are declared on variants with
+/// `#[attribute(to = "B", on = "event")]`, `from` defaults to the annotated variant.
+///
+/// Diagnostics, combined into one [`syn::Error`]:
+///
+/// - unknown keys and unknown `from`/`to` states
+/// - the same event declared twice for one state
+/// - states unreachable from the initial state
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(StateMachine)]
+/// pub enum Job {
+///     #[transition(to = "Running", on = "start")]
+///     Idle,
+///     #[transition(to = "Idle", on = "stop")]
+///     #[transition(from = "Running", to = "Done", on = "finish")]
+///     Running,
+///     Done,
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_parse_transition_table<'a>(
+    input: &'a DeriveInput,
+    attribute: &str,
+) -> syn::Result<TransitionTable<'a>> {
+    let data = match &input.data {
+        Data::Enum(data) if !data.variants.is_empty() => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!(
+                    "synext: Only non-empty enums are supported! target:`{}`",
+                    input.ident
+                ),
+            ))
+        }
+    };
+
+    let states: Vec<&Variant> = data.variants.iter().collect();
+    let find_state = |lit: &syn::LitStr| {
+        let name = lit.value();
+        states
+            .iter()
+            .copied()
+            .find(|state| state.ident == name)
+            .ok_or_else(|| syn::Error::new_spanned(lit, format!("unknown state `{}`", name)))
+    };
+
+    let mut initial = None;
+    let mut transitions: Vec<Transition> = Vec::new();
+    let mut errors = ErrorAccumulator::new();
+
+    for &variant in &states {
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident(attribute))
+        {
+            mark_attr_consumed(attr);
+            let nested = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list.nested,
+                Ok(other) => {
+                    errors.push(syn::Error::new_spanned(
+                        other,
+                        format!(r#"expected `{}(to = "...", on = "...")`"#, attribute),
+                    ));
+                    continue;
+                }
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+
+            let (mut from, mut to, mut on) = (None, None, None);
+            for meta in &nested {
+                match meta {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("initial") => {
+                        if initial.is_some() {
+                            errors.push(syn::Error::new_spanned(path, "duplicate initial state"));
+                        }
+                        initial = Some(variant);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) => match (&nv.lit, nv.path.get_ident()) {
+                        (Lit::Str(value), Some(key)) if key == "from" => from = Some(value),
+                        (Lit::Str(value), Some(key)) if key == "to" => to = Some(value),
+                        (Lit::Str(value), Some(key)) if key == "on" => on = Some(value),
+                        _ => errors.push(syn::Error::new_spanned(
+                            nv,
+                            r#"expected `from = "..."`, `to = "..."` or `on = "..."`"#,
+                        )),
+                    },
+                    other => errors.push(syn::Error::new_spanned(
+                        other,
+                        r#"expected `from = "..."`, `to = "..."`, `on = "..."` or `initial`"#,
+                    )),
+                }
+            }
+
+            let (to, on) = match (to, on) {
+                (Some(to), Some(on)) => (to, on),
+                (None, None) if from.is_none() => continue,
+                _ => {
+                    errors.push(syn::Error::new_spanned(
+                        attr,
+                        "a transition requires both `to` and `on`",
+                    ));
+                    continue;
+                }
+            };
+            let resolved = (
+                from.map_or(Ok(variant), find_state),
+                find_state(to),
+                on.parse::<Ident>(),
+            );
+            let (from, to, event) = match resolved {
+                (Ok(from), Ok(to), Ok(event)) => (from, to, event),
+                (from, to, event) => {
+                    for error in [from.err(), to.err(), event.err()].into_iter().flatten() {
+                        errors.push(error);
+                    }
+                    continue;
+                }
+            };
+
+            if transitions
+                .iter()
+                .any(|t| t.from.ident == from.ident && t.event == event)
+            {
+                errors.push(syn::Error::new_spanned(
+                    on,
+                    format!(
+                        "duplicate transition on `{}` from state `{}`",
+                        event, from.ident
+                    ),
+                ));
+                continue;
+            }
+
+            transitions.push(Transition {
+                from,
+                to,
+                event,
+                attr,
+            });
+        }
+    }
+
+    let initial = initial.unwrap_or(states[0]);
+    let mut reached = vec![initial];
+    let mut cursor = 0;
+    while cursor < reached.len() {
+        let state = reached[cursor];
+        for transition in transitions.iter().filter(|t| t.from.ident == state.ident) {
+            if !reached.iter().any(|r| r.ident == transition.to.ident) {
+                reached.push(transition.to);
+            }
+        }
+        cursor += 1;
+    }
+    for state in &states {
+        if !reached.iter().any(|r| r.ident == state.ident) {
+            errors.push(syn::Error::new_spanned(
+                &state.ident,
+                format!(
+                    "state `{}` is unreachable from the initial state `{}`",
+                    state.ident, initial.ident
+                ),
+            ));
+        }
+    }
+
+    errors.finish_with(TransitionTable {
+        initial,
+        states,
+        transitions,
+    })
+}
+
+// ---------------------------------------------------------------- default.expression
+
+/// The parsed `#[attribute(key = "...")]` default expression of a named field,
+/// with the other fields it reads through `self.<field>`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct FieldDefault<'a> {
+    pub field: &'a Field,
+    pub expr: Expr,
+    /// The fields read by `expr`, in order of first appearance.
+    pub dependencies: Vec<Ident>,
+}
+
+impl<'a> FieldDefault<'a> {
+    pub fn ident(&self) -> &'a Ident {
+        self.field
+            .ident
+            .as_ref()
+            .expect("synext: defaults are only collected for named fields")
+    }
+}
+
+/// Try to extract the fields read by an expression through `self.<field>`, in order of first appearance.
+///
+/// - `self.width * self.height + self.width` -> `[width, height]`
+///
+/// @since 0.4.0
+pub fn try_extract_field_dependencies(expr: &Expr) -> Vec<Ident> {
+    struct SelfFieldVisitor(Vec<Ident>);
+
+    impl<'ast> Visit<'ast> for SelfFieldVisitor {
+        fn visit_expr_field(&mut self, node: &'ast ExprField) {
+            if let (Expr::Path(base), Member::Named(ident)) = (&*node.base, &node.member) {
+                if base.path.is_ident("self") && !self.0.contains(ident) {
+                    self.0.push(ident.clone());
+                }
+            }
+            visit::visit_expr_field(self, node);
+        }
+    }
+
+    let mut visitor = SelfFieldVisitor(Vec::new());
+    visitor.visit_expr(expr);
+
+    visitor.0
+}
+
+/// Try to collect the default expressions of the named fields of a struct,
+/// `#[attribute(key = "self.other_field + 1")]`, in declaration order.
+///
+/// Expressions are parsed with the span of their string literal. References to unknown fields
+/// and fields depending on themselves are reported, combined into one [`syn::Error`].
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Builder)]
+/// pub struct Rect {
+///     width: u32,
+///     #[builder(default = "self.width")]
+///     height: u32,
+///     #[builder(default = "self.width * self.height")]
+///     area: u32,
+/// }
+///
+/// ->
+/// let defaults = try_collect_field_defaults(&input, "builder", "default")?;
+/// let ordered = try_order_field_defaults(&defaults)?; // height, area
+/// ```
+/// @since 0.4.0
+pub fn try_collect_field_defaults<'a>(
+    input: &'a DeriveInput,
+    attribute: &str,
+    key: &str,
+) -> syn::Result<Vec<FieldDefault<'a>>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Ok(Vec::new()),
+        },
+        _ => return Ok(Vec::new()),
+    };
+    let known: Vec<&Ident> = fields.iter().filter_map(|f| f.ident.as_ref()).collect();
+
+    let mut defaults = Vec::new();
+    let mut errors = ErrorAccumulator::new();
+
+    for field in fields {
+        let lit = match try_extract_attribute_path_value(&field.attrs, attribute, key) {
+            Ok(Some(lit)) => lit,
+            Ok(None) => continue,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        let expr: Expr = match lit.parse() {
+            Ok(expr) => expr,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        let dependencies = try_extract_field_dependencies(&expr);
+        for dependency in &dependencies {
+            if !known.contains(&dependency) {
+                errors.push(syn::Error::new_spanned(
+                    dependency,
+                    format!(
+                        "unknown field `{}` in `{}` of `{}`",
+                        dependency,
+                        key,
+                        field.ident.as_ref().unwrap()
+                    ),
+                ));
+            } else if field.ident.as_ref() == Some(dependency) {
+                errors.push(syn::Error::new_spanned(
+                    dependency,
+                    format!(
+                        "the `{}` of `{}` cannot read the field itself",
+                        key, dependency
+                    ),
+                ));
+            }
+        }
+
+        defaults.push(FieldDefault {
+            field,
+            expr,
+            dependencies,
+        });
+    }
+
+    errors.finish_with(defaults)
+}
+
+/// Try to order field defaults so that every default comes after the defaults it reads.
+///
+/// Fields without a default are provided by the caller and impose no ordering. Ties keep
+/// declaration order. A dependency cycle is reported on every field of the cycle, with the
+/// cycle path; fields that only depend on a cycle are not reported, and a default reading its
+/// own field is left to [`try_collect_field_defaults`].
+///
+/// - `a <- b`, `b <- a`, `c <- a` -> cyclic default: `a` -> `b` -> `a`, cyclic default: `b` -> `a` -> `b`
+///
+/// @since 0.4.0
+pub fn try_order_field_defaults<'a, 'b>(
+    defaults: &'b [FieldDefault<'a>],
+) -> syn::Result<Vec<&'b FieldDefault<'a>>> {
+    let edges: Vec<Vec<usize>> = defaults
+        .iter()
+        .enumerate()
+        .map(|(index, default)| {
+            default
+                .dependencies
+                .iter()
+                .filter_map(|dependency| {
+                    defaults
+                        .iter()
+                        .position(|other| other.ident() == dependency)
+                })
+                .filter(|dependency| *dependency != index)
+                .collect()
+        })
+        .collect();
+
+    let mut done = vec![false; defaults.len()];
+    let mut ordered: Vec<&FieldDefault<'a>> = Vec::with_capacity(defaults.len());
+    while let Some(index) = (0..defaults.len())
+        .find(|&index| !done[index] && edges[index].iter().all(|&dependency| done[dependency]))
+    {
+        done[index] = true;
+        ordered.push(&defaults[index]);
+    }
+
+    (0..defaults.len())
+        .filter(|&index| !done[index])
+        .filter_map(|index| find_default_cycle(&edges, index))
+        .map(|cycle| {
+            let path = cycle
+                .iter()
+                .map(|&index| format!("`{}`", defaults[index].ident()))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            syn::Error::new_spanned(
+                defaults[cycle[0]].ident(),
+                format!("cyclic default: {}", path),
+            )
+        })
+        .collect::<ErrorAccumulator>()
+        .finish_with(ordered)
+}
+
+/// The shortest dependency path from `start` back to itself, `start` first and last,
+/// `None` if `start` is not part of a cycle.
+fn find_default_cycle(edges: &[Vec<usize>], start: usize) -> Option<Vec<usize>> {
+    let mut parents: Vec<Option<usize>> = vec![None; edges.len()];
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        for &next in &edges[node] {
+            if next == start {
+                let mut cycle = vec![node];
+                while let Some(parent) = parents[*cycle.last().unwrap()] {
+                    cycle.push(parent);
+                }
+                cycle.reverse();
+                cycle.push(start);
+                return Some(cycle);
+            }
+            if parents[next].is_none() {
+                parents[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+// ---------------------------------------------------------------- expression.context
+
+/// What the generated function that embeds a user expression supports.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExprContext {
+    /// The generated fn is `async`, `.await` is allowed.
+    pub is_async: bool,
+    /// The generated fn returns a `Result` or an `Option`, `?` is allowed.
+    pub allows_try: bool,
+    /// `return` is allowed.
+    pub allows_return: bool,
+}
+
+impl ExprContext {
+    /// A plain, synchronous fn returning a value.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn asynchronous(mut self) -> Self {
+        self.is_async = true;
+        self
+    }
+
+    pub fn returning_result(mut self) -> Self {
+        self.allows_try = true;
+        self
+    }
+
+    pub fn allowing_return(mut self) -> Self {
+        self.allows_return = true;
+        self
+    }
+}
+
+/// Ensure a user-provided expression can be embedded in a generated function,
+/// reporting `.await` outside async fns, `?` outside fns returning `Result`/`Option`,
+/// `return` where it is not allowed and `break`/`continue` outside of a loop of the expression.
+///
+/// Closures and `async` blocks of the expression are their own context: `?` and `return` inside
+/// a closure and `.await` inside an `async` block are accepted.
+///
+/// # Examples
+///
+///```ignore
+/// #[builder(default = "load().await")] // error: `.await` is only allowed inside async functions ...
+/// ->
+/// let expr: Expr = lit.parse()?;
+/// ensure_expr_allowed(&expr, ExprContext::new())?;
+/// ```
+/// @since 0.4.0
+pub fn ensure_expr_allowed(expr: &Expr, context: ExprContext) -> syn::Result<()> {
+    struct ContextVisitor {
+        context: ExprContext,
+        in_closure: bool,
+        loop_depth: usize,
+        errors: ErrorAccumulator,
+    }
+
+    impl ContextVisitor {
+        fn push<T: ToTokens>(&mut self, tokens: T, message: &str) {
+            self.errors.push(syn::Error::new_spanned(tokens, message));
+        }
+    }
+
+    impl<'ast> Visit<'ast> for ContextVisitor {
+        fn visit_expr_await(&mut self, node: &'ast ExprAwait) {
+            if !self.context.is_async {
+                self.push(
+                    node,
+                    "`.await` is only allowed inside async functions and blocks, \
+                     the generated function is not async",
+                );
+            }
+            visit::visit_expr_await(self, node);
+        }
+
+        fn visit_expr_try(&mut self, node: &'ast ExprTry) {
+            if !self.context.allows_try && !self.in_closure {
+                self.push(
+                    node,
+                    "the `?` operator is not allowed here, \
+                     the generated function does not return a `Result` or an `Option`",
+                );
+            }
+            visit::visit_expr_try(self, node);
+        }
+
+        fn visit_expr_return(&mut self, node: &'ast ExprReturn) {
+            if !self.context.allows_return && !self.in_closure {
+                self.push(
+                    node,
+                    "`return` is not allowed here, the expression is embedded in generated code",
+                );
+            }
+            visit::visit_expr_return(self, node);
+        }
+
+        fn visit_expr_break(&mut self, node: &'ast ExprBreak) {
+            if self.loop_depth == 0 {
+                self.push(node, "`break` outside of a loop of the expression");
+            }
+            visit::visit_expr_break(self, node);
+        }
+
+        fn visit_expr_continue(&mut self, node: &'ast ExprContinue) {
+            if self.loop_depth == 0 {
+                self.push(node, "`continue` outside of a loop of the expression");
+            }
+            visit::visit_expr_continue(self, node);
+        }
+
+        fn visit_expr_loop(&mut self, node: &'ast ExprLoop) {
+            self.loop_depth += 1;
+            visit::visit_expr_loop(self, node);
+            self.loop_depth -= 1;
+        }
+
+        fn visit_expr_while(&mut self, node: &'ast ExprWhile) {
+            self.loop_depth += 1;
+            visit::visit_expr_while(self, node);
+            self.loop_depth -= 1;
+        }
+
+        fn visit_expr_for_loop(&mut self, node: &'ast ExprForLoop) {
+            self.loop_depth += 1;
+            visit::visit_expr_for_loop(self, node);
+            self.loop_depth -= 1;
+        }
+
+        // `'label: { ... break 'label value; }`
+        fn visit_expr_block(&mut self, node: &'ast ExprBlock) {
+            let labeled = node.label.is_some() as usize;
+            self.loop_depth += labeled;
+            visit::visit_expr_block(self, node);
+            self.loop_depth -= labeled;
+        }
+
+        fn visit_expr_closure(&mut self, node: &'ast ExprClosure) {
+            let (in_closure, loop_depth) = (self.in_closure, self.loop_depth);
+            self.in_closure = true;
+            self.loop_depth = 0;
+            visit::visit_expr_closure(self, node);
+            self.in_closure = in_closure;
+            self.loop_depth = loop_depth;
+        }
+
+        fn visit_expr_async(&mut self, node: &'ast ExprAsync) {
+            let (context, in_closure, loop_depth) =
+                (self.context, self.in_closure, self.loop_depth);
+            self.context.is_async = true;
+            self.in_closure = true;
+            self.loop_depth = 0;
+            visit::visit_expr_async(self, node);
+            self.context = context;
+            self.in_closure = in_closure;
+            self.loop_depth = loop_depth;
+        }
+
+        // Items nested in a block are checked by rustc on their own.
+        fn visit_item(&mut self, _node: &'ast Item) {}
+    }
+
+    let mut visitor = ContextVisitor {
+        context,
+        in_closure: false,
+        loop_depth: 0,
+        errors: ErrorAccumulator::new(),
+    };
+    visitor.visit_expr(expr);
+
+    visitor.errors.finish()
+}
+
+// ---------------------------------------------------------------- conversion.pair
+
+/// The direction of a conversion declared with `#[attribute(into = "Dto")]` and friends.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionDirection {
+    /// `into = "Dto"`: `impl From<Self> for Dto`
+    Into,
+    /// `from = "Dto"`: `impl From<Dto> for Self`
+    From,
+    /// `try_into = "Dto"`: `impl TryFrom<Self> for Dto`
+    TryInto,
+    /// `try_from = "Dto"`: `impl TryFrom<Dto> for Self`
+    TryFrom,
+}
+
+impl ConversionDirection {
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "into" => Some(ConversionDirection::Into),
+            "from" => Some(ConversionDirection::From),
+            "try_into" => Some(ConversionDirection::TryInto),
+            "try_from" => Some(ConversionDirection::TryFrom),
+            _ => None,
+        }
+    }
+
+    /// The attribute key declaring the conversion, `try_from`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ConversionDirection::Into => "into",
+            ConversionDirection::From => "from",
+            ConversionDirection::TryInto => "try_into",
+            ConversionDirection::TryFrom => "try_from",
+        }
+    }
+
+    /// Whether the annotated struct is the source of the conversion.
+    pub fn is_outgoing(&self) -> bool {
+        matches!(
+            self,
+            ConversionDirection::Into | ConversionDirection::TryInto
+        )
+    }
+
+    pub fn is_fallible(&self) -> bool {
+        matches!(
+            self,
+            ConversionDirection::TryInto | ConversionDirection::TryFrom
+        )
+    }
+}
+
+/// One declared conversion between the annotated struct and `target`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ConversionTarget {
+    pub direction: ConversionDirection,
+    pub target: Path,
+}
+
+/// A field of the annotated struct and the field of the target type it maps to.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct FieldPair<'a> {
+    pub field: &'a Field,
+    /// The field name on the target type, the field's own name unless `#[attribute(rename = "...")]`.
+    pub target: Ident,
+    /// `#[attribute(skip)]`: not mapped, initialized with `Default::default()` when converting into `Self`.
+    pub skip: bool,
+}
+
+impl<'a> FieldPair<'a> {
+    pub fn ident(&self) -> &'a Ident {
+        self.field.ident.as_ref().unwrap()
+    }
+}
+
+/// The conversions of a struct and its field mapping, see [`try_parse_conversion_plan`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ConversionPlan<'a> {
+    pub input: &'a DeriveInput,
+    pub targets: Vec<ConversionTarget>,
+    /// `#[attribute(error = "...")]`, the error type of fallible conversions.
+    pub error: Option<Path>,
+    pub pairs: Vec<FieldPair<'a>>,
+}
+
+impl<'a> ConversionPlan<'a> {
+    /// Check the mapping against the definition of a target type, when the macro sees it
+    /// (e.g. both structs processed by [`crate::expand::expand_batch`]).
+    ///
+    /// Mapped fields missing from the target are reported on the source field, target fields
+    /// nothing maps to are reported on the target field, combined into one [`syn::Error`].
+    pub fn check_against(&self, target: &DeriveInput) -> syn::Result<()> {
+        let target_fields: Vec<&Ident> = match &target.data {
+            Data::Struct(data) => data
+                .fields
+                .iter()
+                .filter_map(|f| f.ident.as_ref())
+                .collect(),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &target.ident,
+                    "conversion targets must be structs with named fields",
+                ))
+            }
+        };
+
+        let mut errors = ErrorAccumulator::new();
+
+        for pair in self.pairs.iter().filter(|pair| !pair.skip) {
+            if !target_fields.contains(&&pair.target) {
+                errors.push(syn::Error::new_spanned(
+                    pair.field,
+                    format!(
+                        "`{}` has no field `{}` to map `{}` to",
+                        target.ident,
+                        pair.target,
+                        pair.ident()
+                    ),
+                ));
+            }
+        }
+        for field in target_fields {
+            let mapped = self
+                .pairs
+                .iter()
+                .any(|pair| !pair.skip && pair.target == *field);
+            if !mapped {
+                errors.push(syn::Error::new_spanned(
+                    field,
+                    format!(
+                        "no field of `{}` maps to `{}::{}`",
+                        self.input.ident, target.ident, field
+                    ),
+                ));
+            }
+        }
+
+        errors.finish()
+    }
+
+    /// Generate the `From`/`TryFrom` impl of every declared conversion,
+    /// converting each field with `From`/`TryFrom` too.
+    pub fn to_impl_tokens(&self) -> TokenStream {
+        let ident = &self.input.ident;
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+        let mapped: Vec<&FieldPair> = self.pairs.iter().filter(|pair| !pair.skip).collect();
+        let skipped = self
+            .pairs
+            .iter()
+            .filter(|pair| pair.skip)
+            .map(|pair| pair.ident());
+
+        let impls = self.targets.iter().map(|conversion| {
+            let target = &conversion.target;
+            let (from_ty, into_ty) = match conversion.direction.is_outgoing() {
+                true => (quote!(#ident #ty_generics), quote!(#target)),
+                false => (quote!(#target), quote!(#ident #ty_generics)),
+            };
+            let (from_members, into_members): (Vec<&Ident>, Vec<&Ident>) =
+                match conversion.direction.is_outgoing() {
+                    true => mapped.iter().map(|p| (p.ident(), &p.target)).unzip(),
+                    false => mapped.iter().map(|p| (&p.target, p.ident())).unzip(),
+                };
+            let defaults = match conversion.direction.is_outgoing() {
+                true => None,
+                false => {
+                    let skipped = skipped.clone();
+                    Some(quote!(#(#skipped: ::core::default::Default::default(),)*))
+                }
+            };
+
+            match conversion.direction.is_fallible() {
+                false => quote! {
+                    impl #impl_generics ::core::convert::From<#from_ty> for #into_ty #where_clause {
+                        fn from(value: #from_ty) -> Self {
+                            Self {
+                                #(#into_members: ::core::convert::From::from(value.#from_members),)*
+                                #defaults
+                            }
+                        }
+                    }
+                },
+                true => {
+                    let error = match self.error {
+                        Some(ref error) => error,
+                        None => {
+                            return syn::Error::new_spanned(
+                                target,
+                                format!(
+                                    "`{}` requires the error type of the conversion",
+                                    conversion.direction.key()
+                                ),
+                            )
+                            .to_compile_error()
+                        }
+                    };
+                    quote! {
+                        impl #impl_generics ::core::convert::TryFrom<#from_ty> for #into_ty #where_clause {
+                            type Error = #error;
+
+                            fn try_from(value: #from_ty) -> ::core::result::Result<Self, Self::Error> {
+                                ::core::result::Result::Ok(Self {
+                                    #(#into_members: ::core::convert::TryFrom::try_from(value.#from_members)?,)*
+                                    #defaults
+                                })
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        quote!(#(#impls)*)
+    }
+}
+
+/// Try to parse the conversions declared on a struct with named fields and its field mapping.
+///
+/// - container: `#[attribute(into = "Dto", try_from = "Row", error = "ConvertError")]`
+/// - `error` is required by `try_into`/`try_from`; `from` and `try_from` (or `into` and `try_into`)
+///   on the same target are rejected, the infallible one already implies `TryFrom`
+/// - targets are compared with [`types_equal`], so `Dto` and `crate::Dto` are different targets but
+///   `Option<Dto>` and `std::option::Option<Dto>` are the same; a target declared twice is rejected
+/// - field: `#[attribute(rename = "user_name")]` maps to another target field, `#[attribute(skip)]` maps to none
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Convert)]
+/// #[convert(into = "UserDto", from = "UserDto")]
+/// pub struct User {
+///     #[convert(rename = "user_name")]
+///     name: String,
+///     #[convert(skip)]
+///     cache: Cache,
+/// }
+///
+/// ->
+/// let plan = try_parse_conversion_plan(&input, "convert")?;
+/// let impls = plan.to_impl_tokens(); // From<User> for UserDto, From<UserDto> for User
+/// ```
+/// @since 0.4.0
+pub fn try_parse_conversion_plan<'a>(
+    input: &'a DeriveInput,
+    attribute: &str,
+) -> syn::Result<ConversionPlan<'a>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "conversions are only supported on structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!(
+                    "synext: Only structs are supported! target:`{}`",
+                    input.ident
+                ),
+            ))
+        }
+    };
+
+    let mut errors = ErrorAccumulator::new();
+
+    let mut targets = Vec::new();
+    let mut error = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(attribute))
+    {
+        mark_attr_consumed(attr);
+        let nested = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested,
+            Ok(other) => {
+                errors.push(syn::Error::new_spanned(
+                    other,
+                    format!(r#"expected `{}(into = "...")`"#, attribute),
+                ));
+                continue;
+            }
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        for meta in nested {
+            let (key, value) = match meta {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(value),
+                    ..
+                })) => (path, value),
+                other => {
+                    errors.push(syn::Error::new_spanned(
+                        other,
+                        format!(r#"expected `{}(into = "...")`"#, attribute),
+                    ));
+                    continue;
+                }
+            };
+            let name = key
+                .get_ident()
+                .map(|ident| ident.to_string())
+                .unwrap_or_default();
+            let path = match value.parse::<Path>() {
+                Ok(path) => path,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+            match ConversionDirection::from_key(&name) {
+                Some(direction) => targets.push(ConversionTarget {
+                    direction,
+                    target: path,
+                }),
+                None if name == "error" => error = Some(path),
+                None => errors.push(syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "unknown `{}` key `{}`, expected `into`, `from`, `try_into`, `try_from` or `error`",
+                        attribute, name
+                    ),
+                )),
+            }
+        }
+    }
+
+    for (index, conversion) in targets.iter().enumerate() {
+        if conversion.direction.is_fallible() && error.is_none() {
+            errors.push(syn::Error::new_spanned(
+                &conversion.target,
+                format!(
+                    r#"`{}` requires the error type of the conversion: `{}(error = "...")`"#,
+                    conversion.direction.key(),
+                    attribute
+                ),
+            ));
+        }
+        let target = path_type(&conversion.target);
+        let earlier = targets[..index].iter().find(|other| {
+            other.direction.is_outgoing() == conversion.direction.is_outgoing()
+                && types_equal(&path_type(&other.target), &target)
+        });
+        let other = match earlier {
+            Some(other) => other,
+            None => continue,
+        };
+        let target = type_to_canonical_string(&target);
+        let mut conflict_error = match other.direction == conversion.direction {
+            true => syn::Error::new_spanned(
+                &conversion.target,
+                format!(
+                    "duplicate `{}` conversion for `{}`",
+                    conversion.direction.key(),
+                    target
+                ),
+            ),
+            // `From<T>` already implies `TryFrom<T>` through std's blanket impl
+            false => syn::Error::new_spanned(
+                &conversion.target,
+                format!(
+                    "`{}` and `{}` conflict for `{}`: the infallible conversion already implements `TryFrom` through std's blanket impl",
+                    other.direction.key(),
+                    conversion.direction.key(),
+                    target
+                ),
+            ),
+        };
+        conflict_error.combine(syn::Error::new_spanned(
+            &other.target,
+            "first declared here",
+        ));
+        errors.push(conflict_error);
+    }
+
+    let mut pairs = Vec::with_capacity(fields.len());
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let skip = try_find_flag_attribute(&field.attrs, attribute, "skip");
+        let target = match try_extract_attribute_path_value(&field.attrs, attribute, "rename") {
+            Ok(Some(rename)) => match rename.parse::<Ident>() {
+                Ok(target) => target,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            },
+            Ok(None) => ident.clone(),
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        pairs.push(FieldPair {
+            field,
+            target,
+            skip,
+        });
+    }
+
+    for (index, pair) in pairs.iter().enumerate().filter(|(_, pair)| !pair.skip) {
+        let first = pairs[..index]
+            .iter()
+            .find(|other| !other.skip && other.target == pair.target);
+        if let Some(first) = first {
+            let mut error = syn::Error::new_spanned(
+                pair.field,
+                format!("`{}` is mapped more than once", pair.target),
+            );
+            error.combine(syn::Error::new_spanned(first.field, "first mapped here"));
+            errors.push(error);
+        }
+    }
+
+    errors.finish_with(ConversionPlan {
+        input,
+        targets,
+        error,
+        pairs,
+    })
+}
+
+fn path_type(path: &Path) -> Type {
+    Type::Path(TypePath {
+        qself: None,
+        path: path.clone(),
+    })
+}
+
+fn try_find_flag_attribute(attrs: &[Attribute], attribute: &str, flag: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(attribute))
+        .inspect(|attr| mark_attr_consumed(attr))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .any(|meta| matches!(meta, NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident(flag)))
+}
@@ -18,7 +18,32 @@
 
 // ----------------------------------------------------------------
 
+#[doc(inline)]
+pub use inherit::*;
+#[doc(inline)]
+pub use literal::*;
 #[doc(inline)]
 pub use parser::*;
+#[doc(inline)]
+pub use resolve::*;
+#[doc(inline)]
+pub use schema::*;
+#[doc(inline)]
+pub use template::*;
 
 pub mod parser;
+
+/// @since 0.4.0
+pub mod literal;
+
+/// @since 0.4.0
+pub mod schema;
+
+/// @since 0.4.0
+pub mod inherit;
+
+/// @since 0.4.0
+pub mod template;
+
+/// @since 0.4.0
+pub mod resolve;
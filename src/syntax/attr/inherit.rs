@@ -0,0 +1,189 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/attr/inherit
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Ident, LitStr, Meta, NestedMeta, Path};
+
+use crate::syntax::attr::schema::{AttributeValue, AttributeValues};
+
+// ----------------------------------------------------------------
+
+/// Encode attribute values as the string carried by an inherited defaults marker,
+/// in the attribute syntax they were written in: `name = "x", skip, limit = 8`.
+///
+/// @since 0.4.0
+pub fn encode_inherited_defaults(values: &AttributeValues) -> String {
+    values
+        .iter()
+        .map(|value| match value.value {
+            Some(ref lit) => format!(
+                "{} = {}",
+                value.key.to_token_stream(),
+                lit.to_token_stream()
+            ),
+            None => value.key.to_token_stream().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Decode the attribute values carried by an inherited defaults marker,
+/// errors are spanned at the marker's string literal.
+///
+/// @since 0.4.0
+pub fn decode_inherited_defaults(encoded: &LitStr) -> syn::Result<AttributeValues> {
+    let nested = encoded.parse_with(Punctuated::<NestedMeta, Comma>::parse_terminated)?;
+    let mut values = AttributeValues::default();
+    for meta in nested {
+        match meta {
+            NestedMeta::Meta(Meta::Path(path)) => values.push(AttributeValue {
+                key: path,
+                value: None,
+                nested: None,
+            }),
+            NestedMeta::Meta(Meta::NameValue(nv)) => values.push(AttributeValue {
+                key: nv.path,
+                value: Some(nv.lit),
+                nested: None,
+            }),
+            other => {
+                return Err(syn::Error::new(
+                    encoded.span(),
+                    format!("invalid inherited default `{}`", other.to_token_stream()),
+                ))
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Fill the keys missing from `values` with the inherited ones, explicit values win.
+///
+/// @since 0.4.0
+pub fn merge_inherited_defaults(values: &mut AttributeValues, inherited: &AttributeValues) {
+    for value in inherited {
+        let declared = value
+            .key
+            .get_ident()
+            .is_some_and(|key| values.contains(&key.to_string()));
+        if !declared {
+            values.push(value.clone());
+        }
+    }
+}
+
+/// The hidden const of an inherited defaults marker: `defaults` -> `__SYNEXT_DEFAULTS_DEFAULTS`.
+///
+/// @since 0.4.0
+pub fn inherited_defaults_const_ident(name: &Ident) -> Ident {
+    format_ident!(
+        "__SYNEXT_DEFAULTS_{}",
+        name.to_string().to_uppercase(),
+        span = name.span()
+    )
+}
+
+/// Generate the module-level marker of inherited defaults named `name`.
+///
+/// Macros cannot read values from the items around them, so the marker is a `macro_rules!`
+/// that hands the encoded defaults to a callback macro, next to a hidden `&str` const
+/// documenting them:
+///
+/// - the configuring macro (e.g. `builder_defaults!(name = "model_defaults", skip_none)`) emits the marker
+/// - a derive in the same module, annotated with `#[builder(inherit = "model_defaults")]`,
+///   emits [`forward_to_inherited_defaults`] instead of its output
+/// - the marker invokes the callback with the defaults and the item, and the callback,
+///   a function-like macro of the same crate, generates the output with [`try_split_inherited_defaults`]
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro]
+/// pub fn builder_defaults(input: TokenStream) -> TokenStream {
+///     let name: Ident = ...;
+///     let values: AttributeValues = ...;
+///     inherited_defaults_marker(&name, &values).into()
+/// }
+///
+/// ->
+/// #[doc(hidden)]
+/// pub(crate) const __SYNEXT_DEFAULTS_MODEL_DEFAULTS: &str = "skip_none";
+/// macro_rules! model_defaults { ([$($callback:tt)*] { $($item:tt)* }) => { $($callback)*! { "skip_none" $($item)* } }; }
+/// pub(crate) use model_defaults;
+/// ```
+/// @since 0.4.0
+pub fn inherited_defaults_marker(name: &Ident, values: &AttributeValues) -> TokenStream {
+    let const_ident = inherited_defaults_const_ident(name);
+    let encoded = LitStr::new(&encode_inherited_defaults(values), name.span());
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        pub(crate) const #const_ident: &str = #encoded;
+
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            ([$($callback:tt)*] { $($item:tt)* }) => {
+                $($callback)*! { #encoded $($item)* }
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use #name;
+    }
+}
+
+/// Generate the call through the marker `defaults` that hands the inherited defaults
+/// and `item` to the function-like macro `callback`.
+///
+/// @since 0.4.0
+pub fn forward_to_inherited_defaults(
+    defaults: &Path,
+    callback: &Path,
+    item: &TokenStream,
+) -> TokenStream {
+    quote! {
+        #defaults! { [#callback] { #item } }
+    }
+}
+
+/// Split the input of a callback macro into the inherited defaults and the forwarded item.
+///
+/// @since 0.4.0
+pub fn try_split_inherited_defaults(
+    input: TokenStream,
+) -> syn::Result<(AttributeValues, TokenStream)> {
+    let parser = |input: ParseStream| {
+        let encoded: LitStr = input.parse()?;
+        let item: TokenStream = input.parse()?;
+        Ok((encoded, item))
+    };
+    let (encoded, item) = parser.parse2(input)?;
+
+    Ok((decode_inherited_defaults(&encoded)?, item))
+}
@@ -0,0 +1,353 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/attr/literal
+
+// ----------------------------------------------------------------
+
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::{Attribute, Lit, Meta, NestedMeta};
+
+use crate::syntax::attr::parser::{mark_attr_consumed, Spanned};
+
+// ----------------------------------------------------------------
+
+/// Try to extract the raw literal of `path_attribute` from the first `#[derive_attribute(...)]`
+/// list in `attrs` that contains it, whatever its kind.
+///
+/// Keep the [`syn::Lit`] around to re-emit it with [`lit_to_tokens`]: hex integers stay hex,
+/// raw strings stay raw and suffixes are preserved, unlike re-rendering a parsed value.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Register)]
+/// pub struct Control {
+///     #[register(mask = 0xFF_00)] // -> try_extract_attribute_lit(&field.attrs, "register", "mask")
+///     bits: u16,
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_extract_attribute_lit(
+    attrs: &[Attribute],
+    derive_attribute: &str,
+    path_attribute: &str,
+) -> syn::Result<Option<Lit>> {
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(derive_attribute))
+    {
+        mark_attr_consumed(attr);
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                    if nv.path.is_ident(path_attribute) {
+                        return Ok(Some(nv.lit));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Re-emit a literal exactly as the user wrote it (`0xFF`, `r#"..."#`, `1_000u32`, `b"\x7f"`).
+///
+/// @since 0.4.0
+pub fn lit_to_tokens(lit: &Lit) -> TokenStream {
+    lit.to_token_stream()
+}
+
+/// Re-span a literal while keeping its original representation.
+///
+/// @since 0.4.0
+pub fn lit_with_span(lit: &Lit, span: Span) -> Lit {
+    let mut lit = lit.clone();
+    lit.set_span(span);
+    lit
+}
+
+/// The source text of a literal as the user wrote it, e.g. for error messages.
+///
+/// - `0xFF` -> `"0xFF"` (not `"255"`)
+/// - `r"a\b"` -> `"r\"a\\b\""`
+///
+/// @since 0.4.0
+pub fn lit_source_text(lit: &Lit) -> String {
+    lit.to_token_stream().to_string()
+}
+
+// ---------------------------------------------------------------- byte.string
+
+/// The accepted length of a byte-string attribute value.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteLen {
+    Any,
+    Exact(usize),
+    AtMost(usize),
+    /// Inclusive bounds.
+    Between(usize, usize),
+}
+
+impl ByteLen {
+    pub fn accepts(&self, len: usize) -> bool {
+        match *self {
+            ByteLen::Any => true,
+            ByteLen::Exact(expected) => len == expected,
+            ByteLen::AtMost(max) => len <= max,
+            ByteLen::Between(min, max) => min <= len && len <= max,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match *self {
+            ByteLen::Any => "any number of bytes".to_string(),
+            ByteLen::Exact(expected) => format!("exactly {} bytes", expected),
+            ByteLen::AtMost(max) => format!("at most {} bytes", max),
+            ByteLen::Between(min, max) => format!("between {} and {} bytes", min, max),
+        }
+    }
+}
+
+/// Try to convert a byte-string (`b"\x7fELF"`) or byte (`b'\x7f'`) literal into its bytes.
+///
+/// @since 0.4.0
+pub fn try_lit_to_bytes(lit: &Lit) -> syn::Result<Spanned<Vec<u8>>> {
+    match lit {
+        Lit::ByteStr(bytes) => Ok(Spanned::new(bytes.value(), bytes.span())),
+        Lit::Byte(byte) => Ok(Spanned::new(vec![byte.value()], byte.span())),
+        other => Err(syn::Error::new_spanned(
+            other,
+            r#"expected a byte string literal, e.g. `b"\x7fELF"`"#,
+        )),
+    }
+}
+
+/// Ensure the length of extracted bytes, reporting a mismatch on the literal.
+///
+/// @since 0.4.0
+pub fn ensure_byte_len(bytes: &Spanned<Vec<u8>>, len: ByteLen) -> syn::Result<()> {
+    if len.accepts(bytes.len()) {
+        return Ok(());
+    }
+
+    Err(bytes.error(format!("expected {}, got {}", len.describe(), bytes.len())))
+}
+
+/// Try to extract the byte-string value of `path_attribute`, validating its length.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Header)]
+/// #[header(magic = b"\x7fELF")] // -> try_extract_attribute_bytes(&input.attrs, "header", "magic", ByteLen::Exact(4))
+/// pub struct ElfHeader {
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_extract_attribute_bytes(
+    attrs: &[Attribute],
+    derive_attribute: &str,
+    path_attribute: &str,
+    len: ByteLen,
+) -> syn::Result<Option<Spanned<Vec<u8>>>> {
+    let lit = match try_extract_attribute_lit(attrs, derive_attribute, path_attribute)? {
+        Some(lit) => lit,
+        None => return Ok(None),
+    };
+
+    let bytes = try_lit_to_bytes(&lit)?;
+    ensure_byte_len(&bytes, len)?;
+
+    Ok(Some(bytes))
+}
+
+// ---------------------------------------------------------------- numeric
+
+const PRIMITIVE_NUMERIC_SUFFIXES: [&str; 14] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64",
+];
+
+/// Try to convert an integer literal into `N`, with a spanned error when it is not an integer,
+/// does not fit `N`, or carries a numeric suffix of another type (`300u16` as `u8`).
+///
+/// - `lit_int_as::<u16>(&lit)` with `8080` -> `Ok(8080)`
+/// - `lit_int_as::<i32>(&lit)` with `-5` -> `Ok(-5)`
+/// - `lit_int_as::<u8>(&lit)` with `300` -> `Err("`300` does not fit in `u8`")`
+///
+/// @since 0.4.0
+pub fn lit_int_as<N>(lit: &Lit) -> syn::Result<N>
+where
+    N: TryFrom<i128> + TryFrom<u128>,
+{
+    let int = match lit {
+        Lit::Int(int) => int,
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "expected an integer literal",
+            ))
+        }
+    };
+
+    let target = short_type_name::<N>();
+    let suffix = int.suffix();
+    if !suffix.is_empty() && PRIMITIVE_NUMERIC_SUFFIXES.contains(&suffix) && suffix != target {
+        return Err(syn::Error::new(
+            int.span(),
+            format!("expected a `{}` literal, got `{}`", target, int),
+        ));
+    }
+
+    // negative values are parsed signed, the others unsigned to keep the full `u128` range
+    let value = match int.base10_digits().starts_with('-') {
+        true => N::try_from(int.base10_parse::<i128>()?).ok(),
+        false => N::try_from(int.base10_parse::<u128>()?).ok(),
+    };
+    value.ok_or_else(|| {
+        syn::Error::new(
+            int.span(),
+            format!("`{}` does not fit in `{}`", int.base10_digits(), target),
+        )
+    })
+}
+
+/// A float type targeted by [`lit_float_as`].
+///
+/// @since 0.4.0
+pub trait FromLitFloat: Sized {
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+impl FromLitFloat for f32 {
+    fn from_f64(value: f64) -> Option<Self> {
+        let narrowed = value as f32;
+        if narrowed.is_finite() {
+            Some(narrowed)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromLitFloat for f64 {
+    fn from_f64(value: f64) -> Option<Self> {
+        if value.is_finite() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+/// Try to convert a float (or integer) literal into `N`, with a spanned error when the value
+/// overflows `N` or carries a numeric suffix of another type.
+///
+/// - `lit_float_as::<f32>(&lit)` with `0.5` -> `Ok(0.5)`
+/// - `lit_float_as::<f32>(&lit)` with `1e39` -> `Err("`1e39` does not fit in `f32`")`
+///
+/// @since 0.4.0
+pub fn lit_float_as<N: FromLitFloat>(lit: &Lit) -> syn::Result<N> {
+    let (value, digits, suffix, span) = match lit {
+        Lit::Float(float) => (
+            float.base10_parse::<f64>()?,
+            float.base10_digits().to_string(),
+            float.suffix().to_string(),
+            float.span(),
+        ),
+        Lit::Int(int) => (
+            int.base10_parse::<f64>()?,
+            int.base10_digits().to_string(),
+            int.suffix().to_string(),
+            int.span(),
+        ),
+        other => return Err(syn::Error::new_spanned(other, "expected a float literal")),
+    };
+
+    let target = short_type_name::<N>();
+    if !suffix.is_empty()
+        && PRIMITIVE_NUMERIC_SUFFIXES.contains(&suffix.as_str())
+        && suffix != target
+    {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "expected a `{}` literal, got `{}{}`",
+                target, digits, suffix
+            ),
+        ));
+    }
+
+    N::from_f64(value)
+        .ok_or_else(|| syn::Error::new(span, format!("`{}` does not fit in `{}`", digits, target)))
+}
+
+/// Try to strip a unit suffix from an integer literal (`500ms`, `16kb`), converting the value into `N`.
+///
+/// The suffix must be one of `units`; an unsuffixed literal yields an empty unit.
+///
+/// # Examples
+///
+///```ignore
+/// #[retry(timeout = 500ms)]
+/// ->
+/// let (timeout, unit) = lit_int_with_unit::<u64>(&lit, &["ms", "s"])?; // (500, "ms")
+/// ```
+/// @since 0.4.0
+pub fn lit_int_with_unit<N>(lit: &Lit, units: &[&str]) -> syn::Result<(N, Spanned<String>)>
+where
+    N: TryFrom<i128> + TryFrom<u128>,
+{
+    let int = match lit {
+        Lit::Int(int) => int,
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "expected an integer literal",
+            ))
+        }
+    };
+
+    let unit = int.suffix();
+    if !unit.is_empty() && !units.contains(&unit) {
+        return Err(syn::Error::new(
+            int.span(),
+            format!(
+                "unknown unit `{}`, expected one of: {}",
+                unit,
+                units.join(", ")
+            ),
+        ));
+    }
+
+    let stripped = Lit::Int(syn::LitInt::new(int.base10_digits(), int.span()));
+    let value = lit_int_as::<N>(&stripped)?;
+
+    Ok((value, Spanned::new(unit.to_string(), int.span())))
+}
+
+fn short_type_name<N>() -> &'static str {
+    let name = std::any::type_name::<N>();
+    name.rsplit("::").next().unwrap_or(name)
+}
@@ -20,7 +20,17 @@
 
 // ----------------------------------------------------------------
 
-use syn::{AttributeArgs, Lit, Meta, NestedMeta};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use proc_macro2::Span;
+use syn::{
+    Attribute, AttributeArgs, Data, DeriveInput, Field, Fields, Lit, LitStr, Meta, NestedMeta,
+    Variant,
+};
+
+use crate::diag::ErrorAccumulator;
 
 // ----------------------------------------------------------------
 
@@ -54,15 +64,12 @@ pub fn try_extract_attribute_args(attr: &str, args: AttributeArgs) -> Option<Str
     let mut attrbute = None;
 
     for arg in args {
-        match arg {
-            NestedMeta::Meta(Meta::NameValue(nv)) => {
-                if nv.path.is_ident(attr) {
-                    if let Lit::Str(n) = nv.lit {
-                        attrbute = Some(n.value());
-                    }
+        if let NestedMeta::Meta(Meta::NameValue(nv)) = arg {
+            if nv.path.is_ident(attr) {
+                if let Lit::Str(n) = nv.lit {
+                    attrbute = Some(n.value());
                 }
             }
-            _ => {}
         }
     }
 
@@ -103,3 +110,563 @@ pub fn try_extract_attribute_first_args(args: AttributeArgs) -> Option<String> {
         _ => None,
     }
 }
+
+/// Like [`try_extract_attribute_args`], keeping the span of the string literal.
+///
+/// @since 0.4.0
+pub fn try_extract_attribute_args_spanned(
+    attr: &str,
+    args: AttributeArgs,
+) -> Option<Spanned<String>> {
+    let mut attrbute = None;
+
+    for arg in args {
+        if let NestedMeta::Meta(Meta::NameValue(nv)) = arg {
+            if nv.path.is_ident(attr) {
+                if let Lit::Str(n) = nv.lit {
+                    attrbute = Some(Spanned::from(&n));
+                }
+            }
+        }
+    }
+
+    attrbute
+}
+
+/// Like [`try_extract_attribute_first_args`], keeping the span of the string literal.
+///
+/// @since 0.4.0
+pub fn try_extract_attribute_first_args_spanned(args: AttributeArgs) -> Option<Spanned<String>> {
+    match args.first() {
+        Some(NestedMeta::Lit(Lit::Str(v))) => Some(Spanned::from(v)),
+        _ => None,
+    }
+}
+
+/// Try to find the marker attribute `#[name]` (a bare path without arguments) in `attrs`.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Error)]
+/// pub enum AppError {
+///     Io(#[from] std::io::Error), // -> try_find_marker_attribute(&field.attrs, "from")
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_find_marker_attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(name) && attr.tokens.is_empty())
+        .inspect(|attr| mark_attr_consumed(attr))
+}
+
+/// Try to predicate that `attrs` contain the marker attribute `#[name]`.
+///
+/// @since 0.4.0
+pub fn try_predicate_has_marker_attribute(attrs: &[Attribute], name: &str) -> bool {
+    try_find_marker_attribute(attrs, name).is_some()
+}
+
+/// Try to extract the string value of `path_attribute` from the first `#[derive_attribute(...)]`
+/// list in `attrs` that contains it.
+///
+/// Unlike [`crate::try_extract_field_attribute_path_attribute`], every nested meta is inspected and
+/// other keys are left to the caller, so the helper works for container, variant and field attributes.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Accessor)]
+/// pub enum Shape {
+///     #[accessor(rename = "circle")] // -> try_extract_attribute_path_value(&variant.attrs, "accessor", "rename")
+///     Round(f64),
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_extract_attribute_path_value(
+    attrs: &[Attribute],
+    derive_attribute: &str,
+    path_attribute: &str,
+) -> syn::Result<Option<LitStr>> {
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(derive_attribute))
+    {
+        mark_attr_consumed(attr);
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                    if nv.path.is_ident(path_attribute) {
+                        return match nv.lit {
+                            Lit::Str(value) => Ok(Some(value)),
+                            other => Err(syn::Error::new_spanned(
+                                other,
+                                format!(
+                                    r#"expected `{}({} = "...")`"#,
+                                    derive_attribute, path_attribute
+                                ),
+                            )),
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// ----------------------------------------------------------------
+
+/// Syntax nodes carrying outer attributes: [`syn::Field`], [`syn::Variant`] and [`syn::DeriveInput`].
+///
+/// @since 0.4.0
+pub trait Attributed {
+    fn attributes(&self) -> &[Attribute];
+}
+
+impl Attributed for Field {
+    fn attributes(&self) -> &[Attribute] {
+        &self.attrs
+    }
+}
+
+impl Attributed for Variant {
+    fn attributes(&self) -> &[Attribute] {
+        &self.attrs
+    }
+}
+
+impl Attributed for DeriveInput {
+    fn attributes(&self) -> &[Attribute] {
+        &self.attrs
+    }
+}
+
+/// Ensure the `#[attribute(key = "...")]` values are unique across `items` (fields or variants).
+///
+/// Every conflicting entry is reported, with the first occurrence of each duplicated value,
+/// in one combined [`syn::Error`].
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Table)]
+/// pub struct User {
+///     #[column(name = "id")]
+///     id: u64,
+///     #[column(name = "id")] // error: duplicate `name` value `id`
+///     uid: u64,
+/// }
+///
+/// ->
+/// ensure_unique_attr_values(try_parse_named_fields(&derive_input), "column", "name")?;
+/// ```
+/// @since 0.4.0
+pub fn ensure_unique_attr_values<'a, T, I>(items: I, attribute: &str, key: &str) -> syn::Result<()>
+where
+    T: Attributed + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let mut seen: Vec<(String, LitStr, bool)> = Vec::new();
+    let mut errors = ErrorAccumulator::new();
+
+    for item in items {
+        let value = match try_extract_attribute_path_value(item.attributes(), attribute, key)? {
+            Some(value) => value,
+            None => continue,
+        };
+        let text = value.value();
+
+        let first = seen.iter_mut().find(|(seen, _, _)| *seen == text);
+        let mut error =
+            syn::Error::new_spanned(&value, format!("duplicate `{}` value `{}`", key, text));
+        match first {
+            Some((_, first, reported)) => {
+                if !*reported {
+                    *reported = true;
+                    error.combine(syn::Error::new_spanned(
+                        &*first,
+                        format!("`{}` value `{}` first used here", key, text),
+                    ));
+                }
+            }
+            None => {
+                seen.push((text, value, false));
+                continue;
+            }
+        }
+
+        errors.push(error);
+    }
+
+    errors.finish()
+}
+
+// ----------------------------------------------------------------
+
+/// An extracted attribute value together with the span of the user's original token,
+/// so later validation errors can always point at it.
+///
+/// # Examples
+///
+///```ignore
+/// let name = try_extract_attribute_args_spanned("value", args).unwrap();
+/// if name.is_empty() {
+///     return name.error("`value` must not be empty").to_compile_error().into();
+/// }
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy)]
+pub struct Spanned<T> {
+    value: T,
+    span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span) -> Self {
+        Self { value, span }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Spanned<U> {
+        Spanned {
+            value: f(self.value),
+            span: self.span,
+        }
+    }
+
+    /// Try to convert the value, attaching any error to the original span.
+    pub fn try_map<U, E, F>(self, f: F) -> syn::Result<Spanned<U>>
+    where
+        E: std::fmt::Display,
+        F: FnOnce(T) -> Result<U, E>,
+    {
+        let span = self.span;
+        f(self.value)
+            .map(|value| Spanned { value, span })
+            .map_err(|error| syn::Error::new(span, error))
+    }
+
+    /// Build a [`syn::Error`] pointing at the original token.
+    pub fn error<M: std::fmt::Display>(&self, message: M) -> syn::Error {
+        syn::Error::new(self.span, message)
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl From<&LitStr> for Spanned<String> {
+    fn from(lit: &LitStr) -> Self {
+        Spanned::new(lit.value(), lit.span())
+    }
+}
+
+impl From<LitStr> for Spanned<String> {
+    fn from(lit: LitStr) -> Self {
+        Spanned::from(&lit)
+    }
+}
+
+/// Like [`try_extract_attribute_path_value`], returning the string value with its literal span.
+///
+/// @since 0.4.0
+pub fn try_extract_attribute_path_value_spanned(
+    attrs: &[Attribute],
+    derive_attribute: &str,
+    path_attribute: &str,
+) -> syn::Result<Option<Spanned<String>>> {
+    try_extract_attribute_path_value(attrs, derive_attribute, path_attribute)
+        .map(|value| value.map(Spanned::from))
+}
+
+// ---------------------------------------------------------------- attribute.usage
+
+/// The helper attributes read by extraction calls during one expansion, see [`ConsumedAttrs::track`].
+///
+/// Attributes are identified by their address, so identical attributes (`#[builder(skip)]` on
+/// the container and on a field) are told apart: read them from the same [`syn::DeriveInput`]
+/// that is checked, not from a clone of it.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsumedAttrs {
+    keys: HashSet<usize>,
+}
+
+thread_local! {
+    static CONSUMED_ATTRS_SCOPES: RefCell<Vec<ConsumedAttrs>> = const { RefCell::new(Vec::new()) };
+}
+
+impl ConsumedAttrs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f`, collecting every attribute the synext helpers (and [`mark_attr_consumed`]) read
+    /// meanwhile; scopes nest, an attribute is recorded by the innermost one.
+    ///
+    /// # Examples
+    ///
+    ///```ignore
+    /// let (fields, consumed) = ConsumedAttrs::track(|| generate_fields(&input));
+    /// ensure_no_unconsumed_helper_attrs(&input, "builder", &consumed)?;
+    /// ```
+    pub fn track<R>(f: impl FnOnce() -> R) -> (R, ConsumedAttrs) {
+        struct Scope;
+
+        impl Drop for Scope {
+            fn drop(&mut self) {
+                CONSUMED_ATTRS_SCOPES.with(|scopes| scopes.borrow_mut().pop());
+            }
+        }
+
+        CONSUMED_ATTRS_SCOPES.with(|scopes| scopes.borrow_mut().push(ConsumedAttrs::new()));
+        let scope = Scope;
+        let value = f();
+        let consumed = CONSUMED_ATTRS_SCOPES
+            .with(|scopes| scopes.borrow_mut().last_mut().map(std::mem::take))
+            .unwrap_or_default();
+        drop(scope);
+
+        (value, consumed)
+    }
+
+    pub fn mark(&mut self, attr: &Attribute) {
+        self.keys.insert(attr_key(attr));
+    }
+
+    pub fn contains(&self, attr: &Attribute) -> bool {
+        self.keys.contains(&attr_key(attr))
+    }
+}
+
+fn attr_key(attr: &Attribute) -> usize {
+    attr as *const Attribute as usize
+}
+
+/// Record that `attr` was read, in the innermost [`ConsumedAttrs::track`] scope;
+/// outside of a scope nothing is recorded.
+///
+/// Every synext extraction helper records the attributes it reads;
+/// call this from hand-written extraction code so [`ensure_no_unconsumed_helper_attrs`] knows about it.
+///
+/// @since 0.4.0
+pub fn mark_attr_consumed(attr: &Attribute) {
+    CONSUMED_ATTRS_SCOPES.with(|scopes| {
+        if let Some(consumed) = scopes.borrow_mut().last_mut() {
+            consumed.mark(attr);
+        }
+    });
+}
+
+/// Ensure every `#[attribute(...)]` of the item (container, variants, fields) was read
+/// by an extraction call, reporting the ones left over, e.g. a `#[builder(...)]` put on an
+/// enum variant when the macro only handles fields.
+///
+/// `consumed` holds the attributes read by the extraction calls, see [`ConsumedAttrs::track`].
+///
+/// # Examples
+///
+///```ignore
+/// let input = try_derive_input(input);
+/// let (_, consumed) = ConsumedAttrs::track(|| {
+///     for field in try_match_fields(&input) {
+///         let each = try_extract_attribute_path_value(&field.attrs, "builder", "each")?;
+///         // ...
+///     }
+///     Ok(())
+/// });
+/// ensure_no_unconsumed_helper_attrs(&input, "builder", &consumed)?;
+/// ```
+/// @since 0.4.0
+pub fn ensure_no_unconsumed_helper_attrs(
+    item: &DeriveInput,
+    attribute: &str,
+    consumed: &ConsumedAttrs,
+) -> syn::Result<()> {
+    let mut attrs: Vec<&Attribute> = item.attrs.iter().collect();
+    match &item.data {
+        Data::Struct(data) => collect_fields_attrs(&data.fields, &mut attrs),
+        Data::Enum(data) => {
+            for variant in &data.variants {
+                attrs.extend(variant.attrs.iter());
+                collect_fields_attrs(&variant.fields, &mut attrs);
+            }
+        }
+        Data::Union(data) => attrs.extend(data.fields.named.iter().flat_map(|f| f.attrs.iter())),
+    }
+
+    attrs
+        .into_iter()
+        .filter(|attr| attr.path.is_ident(attribute))
+        .filter(|attr| !consumed.contains(attr))
+        .map(|attr| {
+            syn::Error::new_spanned(
+                attr,
+                format!(
+                    "unused `#[{}(...)]` attribute: it is not supported in this position",
+                    attribute
+                ),
+            )
+        })
+        .collect::<ErrorAccumulator>()
+        .finish()
+}
+
+fn collect_fields_attrs<'a>(fields: &'a Fields, attrs: &mut Vec<&'a Attribute>) {
+    attrs.extend(fields.iter().flat_map(|field| field.attrs.iter()));
+}
+
+// ---------------------------------------------------------------- meta.query
+
+/// Try to find the first meta matching a path query in `attrs`, in declaration order.
+///
+/// The first segment is the attribute name, every following segment is looked up anywhere
+/// below the previous match, so intermediate groups can be left out of the query.
+///
+/// - `#[x(group(a, inner(method = "y")))]`, `["x", "inner", "method"]` -> `method = "y"`
+/// - `#[x(group(a, inner(method = "y")))]`, `["x", "group", "a"]` -> `a`
+///
+/// # Examples
+///
+///```ignore
+/// if let Some(Meta::NameValue(nv)) = find_meta(&field.attrs, ["x", "inner", "method"])? {
+///     // nv.lit == "y"
+/// }
+/// ```
+/// @since 0.4.0
+pub fn find_meta<'q, Q>(attrs: &[Attribute], query: Q) -> syn::Result<Option<Meta>>
+where
+    Q: AsRef<[&'q str]>,
+{
+    Ok(find_metas(attrs, query)?.into_iter().next())
+}
+
+/// Try to find every meta matching a path query in `attrs`, in declaration order,
+/// see [`find_meta`].
+///
+/// @since 0.4.0
+pub fn find_metas<'q, Q>(attrs: &[Attribute], query: Q) -> syn::Result<Vec<Meta>>
+where
+    Q: AsRef<[&'q str]>,
+{
+    let query = query.as_ref();
+    let (attribute, rest) = match query.split_first() {
+        Some(split) => split,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut found = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(attribute)) {
+        mark_attr_consumed(attr);
+        collect_query_matches(attr.parse_meta()?, rest, &mut found);
+    }
+
+    Ok(found)
+}
+
+fn collect_query_matches(meta: Meta, query: &[&str], found: &mut Vec<Meta>) {
+    let (segment, rest) = match query.split_first() {
+        Some(split) => split,
+        None => return found.push(meta),
+    };
+    let nested = match meta {
+        Meta::List(list) => list.nested,
+        _ => return,
+    };
+
+    for nested in nested {
+        let meta = match nested {
+            NestedMeta::Meta(meta) => meta,
+            NestedMeta::Lit(_) => continue,
+        };
+        if meta.path().is_ident(segment) {
+            collect_query_matches(meta, rest, found);
+        } else {
+            collect_query_matches(meta, query, found);
+        }
+    }
+}
+
+// ---------------------------------------------------------------- key.suggestion
+
+/// Suggest the candidate closest to an unknown key, by edit distance (a swap of two adjacent
+/// characters counts as one edit), `None` when nothing is close enough.
+///
+/// - `suggest_closest(&["method", "each"], "mehtod")` -> `Some("method")`
+/// - `suggest_closest(&["method", "each"], "skip")` -> `None`
+///
+/// @since 0.4.0
+pub fn suggest_closest<'a>(candidates: &[&'a str], got: &str) -> Option<&'a str> {
+    if let Some(candidate) = candidates
+        .iter()
+        .find(|candidate| candidate.eq_ignore_ascii_case(got))
+    {
+        return Some(candidate);
+    }
+
+    let threshold = got.chars().count().max(3) / 3;
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(candidate, got), *candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The `, did you mean `method`?` tail of an unknown key message, empty without a suggestion.
+///
+/// @since 0.4.0
+pub fn did_you_mean(candidates: &[&str], got: &str) -> String {
+    suggest_closest(candidates, got)
+        .map(|candidate| format!(", did you mean `{}`?", candidate))
+        .unwrap_or_default()
+}
+
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let (lhs, rhs) = (
+        lhs.chars().collect::<Vec<_>>(),
+        rhs.chars().collect::<Vec<_>>(),
+    );
+    let mut rows = vec![vec![0; rhs.len() + 1]; lhs.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=lhs.len() {
+        for j in 1..=rhs.len() {
+            let cost = usize::from(lhs[i - 1] != rhs[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && lhs[i - 1] == rhs[j - 2] && lhs[i - 2] == rhs[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+
+    rows[lhs.len()][rhs.len()]
+}
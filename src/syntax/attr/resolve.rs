@@ -0,0 +1,191 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/attr/resolve
+
+// ----------------------------------------------------------------
+
+use std::fmt;
+
+use proc_macro2::Span;
+use syn::Attribute;
+
+use crate::syntax::attr::parser::try_extract_attribute_path_value;
+
+// ----------------------------------------------------------------
+
+/// Where a value resolved by [`ValueResolver`] came from.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    Field,
+    Container,
+    /// The environment variable, read when the macro expands.
+    Env(String),
+    Default,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::Field => f.write_str("field attribute"),
+            ValueSource::Container => f.write_str("container attribute"),
+            ValueSource::Env(name) => write!(f, "environment variable `{}`", name),
+            ValueSource::Default => f.write_str("default"),
+        }
+    }
+}
+
+/// A value resolved by [`ValueResolver`] with its provenance.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ResolvedValue {
+    pub value: String,
+    pub source: ValueSource,
+    /// The span of the attribute literal, the call site for environment and default values.
+    pub span: Span,
+}
+
+/// Resolve a configuration key by cascading field attribute, container attribute,
+/// environment variable and default, reporting which one provided the value.
+///
+/// Environment variables are read while the macro expands and aren't tracked by cargo:
+/// changing one doesn't trigger a rebuild by itself.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Config)]
+/// #[config(prefix = "app")]
+/// pub struct Settings {
+///     #[config(prefix = "db")]
+///     url: String,
+///     port: u16,
+/// }
+///
+/// ->
+/// let resolved = ValueResolver::new("config", "prefix")
+///     .field(&field.attrs)
+///     .container(&input.attrs)
+///     .env("CONFIG_PREFIX")
+///     .default_value("")
+///     .resolve()?
+///     .unwrap();
+/// // url: "db" from the field attribute, port: "app" from the container attribute
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ValueResolver<'a> {
+    attribute: String,
+    key: String,
+    field: Option<&'a [Attribute]>,
+    container: Option<&'a [Attribute]>,
+    env: Option<String>,
+    default: Option<String>,
+}
+
+impl<'a> ValueResolver<'a> {
+    pub fn new(attribute: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            attribute: attribute.into(),
+            key: key.into(),
+            field: None,
+            container: None,
+            env: None,
+            default: None,
+        }
+    }
+
+    pub fn field(mut self, attrs: &'a [Attribute]) -> Self {
+        self.field = Some(attrs);
+        self
+    }
+
+    pub fn container(mut self, attrs: &'a [Attribute]) -> Self {
+        self.container = Some(attrs);
+        self
+    }
+
+    pub fn env(mut self, name: impl Into<String>) -> Self {
+        self.env = Some(name.into());
+        self
+    }
+
+    pub fn default_value(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// Try to resolve the value, `None` when no source provides it.
+    pub fn resolve(&self) -> syn::Result<Option<ResolvedValue>> {
+        let attributes = [
+            (self.field, ValueSource::Field),
+            (self.container, ValueSource::Container),
+        ];
+        for (attrs, source) in attributes {
+            let attrs = match attrs {
+                Some(attrs) => attrs,
+                None => continue,
+            };
+            if let Some(lit) = try_extract_attribute_path_value(attrs, &self.attribute, &self.key)?
+            {
+                return Ok(Some(ResolvedValue {
+                    value: lit.value(),
+                    source,
+                    span: lit.span(),
+                }));
+            }
+        }
+
+        if let Some(ref name) = self.env {
+            if let Ok(value) = std::env::var(name) {
+                return Ok(Some(ResolvedValue {
+                    value,
+                    source: ValueSource::Env(name.clone()),
+                    span: Span::call_site(),
+                }));
+            }
+        }
+
+        Ok(self.default.as_ref().map(|default| ResolvedValue {
+            value: default.clone(),
+            source: ValueSource::Default,
+            span: Span::call_site(),
+        }))
+    }
+
+    /// Like [`ValueResolver::resolve`], with an error on `span` when no source provides the value.
+    pub fn resolve_required(&self, span: Span) -> syn::Result<ResolvedValue> {
+        self.resolve()?.ok_or_else(|| {
+            let mut sources = vec![format!("`#[{}({} = \"...\")]`", self.attribute, self.key)];
+            if let Some(ref name) = self.env {
+                sources.push(format!("the `{}` environment variable", name));
+            }
+            syn::Error::new(
+                span,
+                format!(
+                    "missing `{}`, set it with {}",
+                    self.key,
+                    sources.join(" or ")
+                ),
+            )
+        })
+    }
+}
@@ -0,0 +1,1045 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/attr/schema
+
+// ----------------------------------------------------------------
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt as _;
+use syn::spanned::Spanned as _;
+use syn::{Attribute, Data, DeriveInput, Ident, Lit, Meta, NestedMeta, Path};
+
+use crate::diag::ErrorAccumulator;
+use crate::syntax::attr::literal::try_lit_to_bytes;
+use crate::syntax::attr::parser::{did_you_mean, mark_attr_consumed, Spanned};
+
+// ----------------------------------------------------------------
+
+/// The expected value kind of an attribute key.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// `key = "..."`
+    Str,
+    /// `key = 42`
+    Int,
+    /// `key = 4.2` (integers are accepted too)
+    Float,
+    /// `key = true`
+    Bool,
+    /// `key = b"..."` (a byte literal `b'.'` is accepted too)
+    ByteStr,
+    /// A bare `key`
+    Flag,
+}
+
+impl ValueKind {
+    fn accepts(&self, value: Option<&Lit>) -> bool {
+        matches!(
+            (self, value),
+            (ValueKind::Flag, None)
+                | (ValueKind::Str, Some(Lit::Str(_)))
+                | (ValueKind::Int, Some(Lit::Int(_)))
+                | (ValueKind::Float, Some(Lit::Float(_) | Lit::Int(_)))
+                | (ValueKind::Bool, Some(Lit::Bool(_)))
+                | (ValueKind::ByteStr, Some(Lit::ByteStr(_) | Lit::Byte(_)))
+        )
+    }
+
+    /// A short, human-readable name of the kind, used by generated docs.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ValueKind::Str => "string",
+            ValueKind::Int => "integer",
+            ValueKind::Float => "number",
+            ValueKind::Bool => "bool",
+            ValueKind::ByteStr => "byte string",
+            ValueKind::Flag => "flag",
+        }
+    }
+
+    fn describe(&self, key: &str) -> String {
+        match self {
+            ValueKind::Str => format!(r#"`{} = "..."`"#, key),
+            ValueKind::Int => format!("`{} = <integer>`", key),
+            ValueKind::Float => format!("`{} = <number>`", key),
+            ValueKind::Bool => format!("`{} = true|false`", key),
+            ValueKind::ByteStr => format!(r#"`{} = b"..."`"#, key),
+            ValueKind::Flag => format!("`{}`", key),
+        }
+    }
+}
+
+/// Where a helper attribute is written.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrPosition {
+    /// On the struct, enum or union itself.
+    Container,
+    /// On a field of a struct or of an enum variant.
+    Field,
+    /// On an enum variant.
+    Variant,
+    /// On a function argument.
+    FnArg,
+}
+
+impl AttrPosition {
+    /// The plural noun used in error messages and generated docs.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttrPosition::Container => "containers",
+            AttrPosition::Field => "fields",
+            AttrPosition::Variant => "variants",
+            AttrPosition::FnArg => "function arguments",
+        }
+    }
+}
+
+/// A key declared by an [`AttributeSchema`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct AttributeKey {
+    pub name: String,
+    pub kind: ValueKind,
+    pub required: bool,
+    /// The default applied when the key is absent, as shown in generated docs.
+    pub default: Option<String>,
+    /// The version of the macro crate that introduced the key.
+    pub since: Option<String>,
+    pub description: Option<String>,
+    /// Where the key is legal, `None` for anywhere.
+    pub positions: Option<Vec<AttrPosition>>,
+}
+
+impl AttributeKey {
+    pub fn new(name: impl Into<String>, kind: ValueKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            required: false,
+            default: None,
+            since: None,
+            description: None,
+            positions: None,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn default_value(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    pub fn since(mut self, since: impl Into<String>) -> Self {
+        self.since = Some(since.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Restrict the key to `positions`, e.g. `each` only on fields.
+    pub fn only(mut self, positions: &[AttrPosition]) -> Self {
+        self.positions = Some(positions.to_vec());
+        self
+    }
+
+    pub fn allows(&self, position: AttrPosition) -> bool {
+        match self.positions {
+            Some(ref positions) => positions.contains(&position),
+            None => true,
+        }
+    }
+
+    fn describe_positions(&self) -> String {
+        let labels = self
+            .positions
+            .iter()
+            .flatten()
+            .map(|position| position.label())
+            .collect::<Vec<_>>();
+
+        labels.join(" and ")
+    }
+}
+
+/// A custom cross-key check of a [`KeyRule::Custom`].
+///
+/// @since 0.4.0
+pub type KeyCheck = Rc<dyn Fn(&AttributeValues) -> syn::Result<()>>;
+
+/// A validation rule between related keys of an [`AttributeSchema`],
+/// checked once every key has been parsed and only when both keys are present.
+///
+/// @since 0.4.0
+#[derive(Clone)]
+pub enum KeyRule {
+    /// `lower < upper`, e.g. `start < end`.
+    LessThan(String, String),
+    /// `lower <= upper`, e.g. `min <= max`.
+    LessOrEqual(String, String),
+    /// A custom check over all parsed values.
+    Custom(KeyCheck),
+}
+
+impl std::fmt::Debug for KeyRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyRule::LessThan(lower, upper) => write!(f, "LessThan({:?}, {:?})", lower, upper),
+            KeyRule::LessOrEqual(lower, upper) => {
+                write!(f, "LessOrEqual({:?}, {:?})", lower, upper)
+            }
+            KeyRule::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// One parsed `key = value` (or bare `key`, or nested `key(...)`) of an attribute.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct AttributeValue {
+    pub key: Path,
+    /// `None` for flags and nested lists.
+    pub value: Option<Lit>,
+    /// The values of a nested `key(...)` list, `None` otherwise.
+    pub nested: Option<AttributeValues>,
+}
+
+impl AttributeValue {
+    /// The span of the value, or of the key for flags.
+    pub fn span(&self) -> proc_macro2::Span {
+        match self.value {
+            Some(ref lit) => lit.span(),
+            None => self.key.span(),
+        }
+    }
+
+    fn as_number(&self) -> Option<Number> {
+        match self.value {
+            Some(Lit::Int(ref int)) => int
+                .base10_parse::<i128>()
+                .map(Number::Int)
+                .or_else(|_| int.base10_parse::<u128>().map(Number::UInt))
+                .ok(),
+            Some(Lit::Float(ref float)) => float.base10_parse::<f64>().ok().map(Number::Float),
+            _ => None,
+        }
+    }
+}
+
+/// A numeric value compared by [`KeyRule`], integers are compared exactly.
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Int(i128),
+    /// Above `i128::MAX`.
+    UInt(u128),
+    Float(f64),
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(value) => value as f64,
+            Number::UInt(value) => value as f64,
+            Number::Float(value) => value,
+        }
+    }
+
+    fn compare(self, other: Number) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(l), Number::Int(r)) => Some(l.cmp(&r)),
+            (Number::UInt(l), Number::UInt(r)) => Some(l.cmp(&r)),
+            (Number::Int(_), Number::UInt(_)) => Some(Ordering::Less),
+            (Number::UInt(_), Number::Int(_)) => Some(Ordering::Greater),
+            (l, r) => l.as_f64().partial_cmp(&r.as_f64()),
+        }
+    }
+}
+
+/// The values parsed by [`AttributeSchema::parse`], in declaration order,
+/// indexed by key so lookups don't scan every value.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Default)]
+pub struct AttributeValues {
+    values: Vec<AttributeValue>,
+    /// The position of the first value of each single-identifier key.
+    index: HashMap<String, usize>,
+}
+
+impl AttributeValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a value, a later value of an already present key is kept but not looked up.
+    pub fn push(&mut self, value: AttributeValue) {
+        if let Some(ident) = value.key.get_ident() {
+            self.index
+                .entry(ident.to_string())
+                .or_insert(self.values.len());
+        }
+        self.values.push(value);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, AttributeValue> {
+        self.values.iter()
+    }
+
+    pub fn as_slice(&self) -> &[AttributeValue] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&AttributeValue> {
+        self.index.get(key).map(|&position| &self.values[position])
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn flag(&self, key: &str) -> bool {
+        self.contains(key)
+    }
+
+    /// The values of the nested `key(...)` list.
+    pub fn nested(&self, key: &str) -> Option<&AttributeValues> {
+        self.get(key)?.nested.as_ref()
+    }
+
+    /// The raw literal of `key`, to be re-emitted with its original representation.
+    pub fn lit(&self, key: &str) -> Option<&Lit> {
+        self.get(key)?.value.as_ref()
+    }
+
+    pub fn str(&self, key: &str) -> Option<String> {
+        match self.get(key)?.value {
+            Some(Lit::Str(ref value)) => Some(value.value()),
+            _ => None,
+        }
+    }
+
+    pub fn str_spanned(&self, key: &str) -> Option<Spanned<String>> {
+        match self.get(key)?.value {
+            Some(Lit::Str(ref value)) => Some(Spanned::from(value)),
+            _ => None,
+        }
+    }
+
+    pub fn bytes(&self, key: &str) -> Option<Spanned<Vec<u8>>> {
+        self.lit(key).and_then(|lit| try_lit_to_bytes(lit).ok())
+    }
+
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)?.value {
+            Some(Lit::Bool(ref value)) => Some(value.value),
+            _ => None,
+        }
+    }
+
+    pub fn int<N>(&self, key: &str) -> syn::Result<Option<N>>
+    where
+        N: FromStr,
+        N::Err: Display,
+    {
+        match self.get(key).and_then(|v| v.value.as_ref()) {
+            Some(Lit::Int(value)) => value.base10_parse::<N>().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn int_spanned<N>(&self, key: &str) -> syn::Result<Option<Spanned<N>>>
+    where
+        N: FromStr,
+        N::Err: Display,
+    {
+        match self.get(key).and_then(|v| v.value.as_ref()) {
+            Some(Lit::Int(value)) => value
+                .base10_parse::<N>()
+                .map(|n| Some(Spanned::new(n, value.span()))),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn float<N>(&self, key: &str) -> syn::Result<Option<N>>
+    where
+        N: FromStr,
+        N::Err: Display,
+    {
+        match self.get(key).and_then(|v| v.value.as_ref()) {
+            Some(Lit::Float(value)) => value.base10_parse::<N>().map(Some),
+            Some(Lit::Int(value)) => value.base10_parse::<N>().map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Extend<AttributeValue> for AttributeValues {
+    fn extend<I: IntoIterator<Item = AttributeValue>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl FromIterator<AttributeValue> for AttributeValues {
+    fn from_iter<I: IntoIterator<Item = AttributeValue>>(iter: I) -> Self {
+        let mut values = AttributeValues::new();
+        values.extend(iter);
+        values
+    }
+}
+
+impl IntoIterator for AttributeValues {
+    type Item = AttributeValue;
+    type IntoIter = std::vec::IntoIter<AttributeValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AttributeValues {
+    type Item = &'a AttributeValue;
+    type IntoIter = std::slice::Iter<'a, AttributeValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+/// The declared keys and cross-key rules of one helper attribute, e.g. `#[builder(...)]`.
+///
+/// # Examples
+///
+///```ignore
+/// let schema = AttributeSchema::new("range")
+///     .key(AttributeKey::new("min", ValueKind::Int).required())
+///     .key(AttributeKey::new("max", ValueKind::Int).required())
+///     .rule(KeyRule::LessOrEqual("min".into(), "max".into()));
+///
+/// #[range(min = 10, max = 1)] // error: `max` must be greater than or equal to `min`
+/// ->
+/// let values = schema.parse(&field.attrs)?;
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct AttributeSchema {
+    pub attribute: String,
+    pub keys: Vec<AttributeKey>,
+    pub rules: Vec<KeyRule>,
+}
+
+impl AttributeSchema {
+    pub fn new(attribute: impl Into<String>) -> Self {
+        Self {
+            attribute: attribute.into(),
+            keys: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Declare a key, whose name must be an identifier (`max_len`, `type`): a key like `max-len`
+    /// is not a meta path and so could never be parsed, the schema panics on it instead.
+    pub fn key(mut self, key: AttributeKey) -> Self {
+        if syn::parse::Parser::parse_str(Ident::parse_any, &key.name).is_err() {
+            panic!(
+                "synext: `{}` key `{}` is not an identifier",
+                self.attribute, key.name
+            );
+        }
+        self.keys.push(key);
+        self
+    }
+
+    pub fn rule(mut self, rule: KeyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn find_key(&self, name: &str) -> Option<&AttributeKey> {
+        self.keys.iter().find(|key| key.name == name)
+    }
+
+    /// Parse every `#[attribute(...)]` in `attrs` against the schema.
+    ///
+    /// Unknown keys, mistyped values, duplicated keys, missing required keys and violated
+    /// [`KeyRule`]s are all reported in one combined [`syn::Error`].
+    pub fn parse(&self, attrs: &[Attribute]) -> syn::Result<AttributeValues> {
+        self.parse_with(attrs, None)
+    }
+
+    /// Like [`AttributeSchema::parse`], for attributes written at `position`:
+    /// keys restricted to other positions are reported (`` `each` is only valid on fields ``)
+    /// and are not required there.
+    ///
+    /// # Examples
+    ///
+    ///```ignore
+    /// let schema = AttributeSchema::new("builder")
+    ///     .key(AttributeKey::new("each", ValueKind::Str).only(&[AttrPosition::Field]))
+    ///     .key(AttributeKey::new("name", ValueKind::Str).only(&[AttrPosition::Container]));
+    ///
+    /// let container = schema.parse_at(&input.attrs, AttrPosition::Container)?;
+    /// let field = schema.parse_at(&field.attrs, AttrPosition::Field)?;
+    /// ```
+    pub fn parse_at(
+        &self,
+        attrs: &[Attribute],
+        position: AttrPosition,
+    ) -> syn::Result<AttributeValues> {
+        self.parse_with(attrs, Some(position))
+    }
+
+    /// Report every key of the item written at a position it is not legal in,
+    /// walking the container, variants and fields of `item`.
+    ///
+    /// Only misplaced keys are reported, the values are parsed with [`AttributeSchema::parse_at`].
+    pub fn ensure_positions(&self, item: &DeriveInput) -> syn::Result<()> {
+        let mut errors = ErrorAccumulator::new();
+        self.check_positions(&item.attrs, AttrPosition::Container, &mut errors);
+        match &item.data {
+            Data::Struct(data) => {
+                for field in &data.fields {
+                    self.check_positions(&field.attrs, AttrPosition::Field, &mut errors);
+                }
+            }
+            Data::Enum(data) => {
+                for variant in &data.variants {
+                    self.check_positions(&variant.attrs, AttrPosition::Variant, &mut errors);
+                    for field in &variant.fields {
+                        self.check_positions(&field.attrs, AttrPosition::Field, &mut errors);
+                    }
+                }
+            }
+            Data::Union(data) => {
+                for field in &data.fields.named {
+                    self.check_positions(&field.attrs, AttrPosition::Field, &mut errors);
+                }
+            }
+        }
+
+        errors.finish()
+    }
+
+    fn check_positions(
+        &self,
+        attrs: &[Attribute],
+        position: AttrPosition,
+        errors: &mut ErrorAccumulator,
+    ) {
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident(&self.attribute))
+        {
+            let nested = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list.nested,
+                _ => continue,
+            };
+            for meta in nested {
+                let path = match meta {
+                    NestedMeta::Meta(Meta::Path(path)) => path,
+                    NestedMeta::Meta(Meta::NameValue(nv)) => nv.path,
+                    _ => continue,
+                };
+                if let Some(error) = self.misplaced_key_error(&path, position) {
+                    errors.push(error);
+                }
+            }
+        }
+    }
+
+    fn misplaced_key_error(&self, path: &Path, position: AttrPosition) -> Option<syn::Error> {
+        let key = self.find_key(&path.get_ident()?.to_string())?;
+        match key.allows(position) {
+            true => None,
+            false => Some(syn::Error::new_spanned(
+                path,
+                format!(
+                    "`{}` is only valid on {}",
+                    key.name,
+                    key.describe_positions()
+                ),
+            )),
+        }
+    }
+
+    fn parse_with(
+        &self,
+        attrs: &[Attribute],
+        position: Option<AttrPosition>,
+    ) -> syn::Result<AttributeValues> {
+        let mut values = AttributeValues::default();
+        let mut errors = ErrorAccumulator::new();
+        let mut last_attr = None;
+
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident(&self.attribute))
+        {
+            last_attr = Some(attr);
+            mark_attr_consumed(attr);
+            let nested = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list.nested,
+                Ok(Meta::Path(_)) => continue,
+                Ok(other) => {
+                    errors.push(syn::Error::new_spanned(
+                        other,
+                        format!("expected `{}(...)`", self.attribute),
+                    ));
+                    continue;
+                }
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+
+            for meta in nested {
+                let value = match meta {
+                    NestedMeta::Meta(Meta::Path(path)) => AttributeValue {
+                        key: path,
+                        value: None,
+                        nested: None,
+                    },
+                    NestedMeta::Meta(Meta::NameValue(nv)) => AttributeValue {
+                        key: nv.path,
+                        value: Some(nv.lit),
+                        nested: None,
+                    },
+                    other => {
+                        errors.push(syn::Error::new_spanned(
+                            other,
+                            format!("unsupported `{}` argument", self.attribute),
+                        ));
+                        continue;
+                    }
+                };
+
+                let name = value
+                    .key
+                    .get_ident()
+                    .map(|ident| ident.to_string())
+                    .unwrap_or_default();
+                let key = match self.find_key(&name) {
+                    Some(key) => key,
+                    None => {
+                        errors.push(syn::Error::new_spanned(
+                            &value.key,
+                            format!(
+                                "unknown `{}` key `{}`{}",
+                                self.attribute,
+                                name,
+                                did_you_mean(
+                                    &self
+                                        .keys
+                                        .iter()
+                                        .map(|key| key.name.as_str())
+                                        .collect::<Vec<_>>(),
+                                    &name
+                                )
+                            ),
+                        ));
+                        continue;
+                    }
+                };
+                if let Some(error) =
+                    position.and_then(|position| self.misplaced_key_error(&value.key, position))
+                {
+                    errors.push(error);
+                    continue;
+                }
+                if !key.kind.accepts(value.value.as_ref()) {
+                    errors.push(syn::Error::new(
+                        value.span(),
+                        format!("expected {}", key.kind.describe(&key.name)),
+                    ));
+                    continue;
+                }
+                if values.contains(&name) {
+                    errors.push(syn::Error::new_spanned(
+                        &value.key,
+                        format!("duplicate `{}` key `{}`", self.attribute, name),
+                    ));
+                    continue;
+                }
+
+                values.push(value);
+            }
+        }
+
+        for key in self.keys.iter().filter(|key| {
+            key.required
+                && position
+                    .map(|position| key.allows(position))
+                    .unwrap_or(true)
+        }) {
+            if !values.contains(&key.name) {
+                let message = format!(
+                    "missing required {} in `#[{}(...)]`",
+                    key.kind.describe(&key.name),
+                    self.attribute
+                );
+                errors.push(match last_attr {
+                    Some(attr) => syn::Error::new_spanned(attr, message),
+                    None => syn::Error::new(proc_macro2::Span::call_site(), message),
+                });
+            }
+        }
+
+        for rule in &self.rules {
+            if let Err(error) = check_key_rule(rule, &values) {
+                errors.push(error);
+            }
+        }
+
+        errors.finish_with(values)
+    }
+
+    /// Render the reference documentation of the attribute as a markdown table,
+    /// one row per key in declaration order.
+    ///
+    /// # Examples
+    ///
+    ///```ignore
+    /// let schema = AttributeSchema::new("builder")
+    ///     .key(AttributeKey::new("each", ValueKind::Str).since("0.2.0").description("Name of the per-item setter."));
+    ///
+    /// ->
+    /// ### `#[builder(...)]`
+    ///
+    /// | Key | Type | Required | Default | Since | Description |
+    /// | --- | --- | --- | --- | --- | --- |
+    /// | `each` | string | no | - | 0.2.0 | Name of the per-item setter. |
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("### `#[{}(...)]`\n\n", self.attribute);
+        markdown.push_str("| Key | Type | Required | Default | Since | Description |\n");
+        markdown.push_str("| --- | --- | --- | --- | --- | --- |\n");
+        for key in &self.keys {
+            markdown.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {} |\n",
+                key.name,
+                key.kind.label(),
+                if key.required { "yes" } else { "no" },
+                key.default
+                    .as_deref()
+                    .map_or_else(|| "-".to_string(), |default| format!("`{}`", default)),
+                key.since.as_deref().unwrap_or("-"),
+                escape_markdown_cell(key.description.as_deref().unwrap_or("")),
+            ));
+        }
+
+        markdown
+    }
+
+    /// Render [`AttributeSchema::to_markdown`] as `#[doc = "..."]` attributes,
+    /// to attach the attribute reference to the derive macro itself.
+    ///
+    /// # Examples
+    ///
+    ///```ignore
+    /// let docs = schema.to_doc_tokens();
+    /// quote! {
+    ///     #docs
+    ///     pub struct BuilderAttributes;
+    /// }
+    /// ```
+    pub fn to_doc_tokens(&self) -> TokenStream {
+        let lines = self
+            .to_markdown()
+            .lines()
+            .map(|line| match line.is_empty() {
+                true => String::new(),
+                false => format!(" {}", line),
+            })
+            .collect::<Vec<_>>();
+
+        quote! { #(#[doc = #lines])* }
+    }
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn check_key_rule(rule: &KeyRule, values: &AttributeValues) -> syn::Result<()> {
+    let (lower, upper, strict) = match rule {
+        KeyRule::LessThan(lower, upper) => (lower, upper, true),
+        KeyRule::LessOrEqual(lower, upper) => (lower, upper, false),
+        KeyRule::Custom(check) => return check(values),
+    };
+
+    let (lower_value, upper_value) = match (values.get(lower), values.get(upper)) {
+        (Some(l), Some(u)) => (l, u),
+        _ => return Ok(()),
+    };
+
+    let ok = match (lower_value.as_number(), upper_value.as_number()) {
+        (Some(l), Some(u)) if strict => l.compare(u) == Some(Ordering::Less),
+        (Some(l), Some(u)) => matches!(l.compare(u), Some(Ordering::Less | Ordering::Equal)),
+        _ => {
+            let mut error = syn::Error::new(
+                lower_value.span(),
+                format!(
+                    "`{}` must be numeric to be compared with `{}`",
+                    lower, upper
+                ),
+            );
+            error.combine(syn::Error::new(
+                upper_value.span(),
+                format!(
+                    "`{}` must be numeric to be compared with `{}`",
+                    upper, lower
+                ),
+            ));
+            return Err(error);
+        }
+    };
+
+    if ok {
+        return Ok(());
+    }
+
+    let relation = if strict {
+        "greater than"
+    } else {
+        "greater than or equal to"
+    };
+    let mut error = syn::Error::new(
+        upper_value.span(),
+        format!("`{}` must be {} `{}`", upper, relation, lower),
+    );
+    error.combine(syn::Error::new(
+        lower_value.span(),
+        format!("`{}` is declared here", lower),
+    ));
+
+    Err(error)
+}
+
+// ---------------------------------------------------------------- schema.constants
+
+/// Generate a module of string constants and marker types for the helper-attribute vocabulary
+/// of a macro crate, one sub-module per schema, so a misspelled attribute or key name in the
+/// macro's own code is a compile error instead of a silently ignored string.
+///
+/// Every key gets a `SCREAMING_CASE` constant and a `CamelCase` marker type carrying
+/// `ATTRIBUTE`/`NAME` consts, both documented with the key's description, kind and default.
+/// Proc macros can't run on their own crate, so write the module from a build script and
+/// `include!` it.
+///
+/// Names that can't be identifiers (`self`, `crate`, `_`, ...) and names colliding within a
+/// module, e.g. a key `attribute` with the `ATTRIBUTE` const or `max-len` with `max_len`, are errors.
+///
+/// # Examples
+///
+///```ignore
+/// // build.rs
+/// let tokens = generate_helper_constants("attrs", &[builder_schema()])?;
+/// std::fs::write(out_dir.join("attrs.rs"), tokens.to_string())?;
+///
+/// // lib.rs
+/// include!(concat!(env!("OUT_DIR"), "/attrs.rs"));
+///
+/// ->
+/// pub mod attrs {
+///     pub mod builder {
+///         pub const ATTRIBUTE: &str = "builder";
+///         /// Name of the per-item setter.
+///         pub const EACH: &str = "each";
+///         /// Name of the per-item setter.
+///         pub struct Each;
+///         impl Each {
+///             pub const ATTRIBUTE: &'static str = "builder";
+///             pub const NAME: &'static str = "each";
+///         }
+///     }
+/// }
+///
+/// schema.find_key(attrs::builder::EACH)
+/// ```
+/// @since 0.4.0
+pub fn generate_helper_constants(
+    module: &str,
+    schemas: &[AttributeSchema],
+) -> syn::Result<TokenStream> {
+    let module = constant_ident(module)?;
+    let mut modules = ConstantNames::new(format!("`{}`", module));
+    let schemas = schemas
+        .iter()
+        .map(|schema| {
+            modules.insert(&schema.attribute, &schema.attribute)?;
+            schema_constants(schema)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        #[allow(dead_code)]
+        pub mod #module {
+            #(#schemas)*
+        }
+    })
+}
+
+fn schema_constants(schema: &AttributeSchema) -> syn::Result<TokenStream> {
+    let module = constant_ident(&schema.attribute)?;
+    let attribute = &schema.attribute;
+    let module_doc = format!(" Helper attribute `#[{}(...)]`.", attribute);
+    let mut names = ConstantNames::new(format!("`{}`", attribute));
+    names.insert("ATTRIBUTE", "ATTRIBUTE")?;
+
+    let keys = schema.keys.iter().map(|key| {
+        let name = &key.name;
+        let constant = constant_ident(&name.to_uppercase())?;
+        let marker = constant_ident(&camel_case(name))?;
+        names.insert(&constant.to_string(), name)?;
+        names.insert(&marker.to_string(), name)?;
+        let mut docs = Vec::new();
+        if let Some(description) = key.description.as_deref() {
+            docs.push(format!(" {}", description));
+            docs.push(String::new());
+        }
+        let usage = match key.kind {
+            ValueKind::Flag => format!("#[{}({})]", attribute, name),
+            _ => format!("#[{}({} = ...)]", attribute, name),
+        };
+        docs.push(format!(
+            " `{}`, {}{}.",
+            usage,
+            key.kind.label(),
+            if key.required { ", required" } else { "" }
+        ));
+        if let Some(default) = key.default.as_deref() {
+            docs.push(format!(" Defaults to `{}`.", default));
+        }
+
+        Ok(quote! {
+            #(#[doc = #docs])*
+            pub const #constant: &str = #name;
+
+            #(#[doc = #docs])*
+            #[derive(Debug, Clone, Copy, Default)]
+            pub struct #marker;
+
+            impl #marker {
+                pub const ATTRIBUTE: &'static str = #attribute;
+                pub const NAME: &'static str = #name;
+            }
+        })
+    });
+    let keys = keys.collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        #[doc = #module_doc]
+        pub mod #module {
+            pub const ATTRIBUTE: &str = #attribute;
+
+            #(#keys)*
+        }
+    })
+}
+
+fn constant_ident(name: &str) -> syn::Result<proc_macro2::Ident> {
+    let name = name.replace('-', "_");
+    if let Ok(ident) = syn::parse_str::<proc_macro2::Ident>(&name) {
+        return Ok(ident);
+    }
+
+    let error = || {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("synext: `{}` can't be used as a constant name", name),
+        )
+    };
+    match name.as_str() {
+        "self" | "Self" | "super" | "crate" | "_" => Err(error()),
+        _ => syn::parse_str::<proc_macro2::Ident>(&format!("r#{}", name)).map_err(|_| error()),
+    }
+}
+
+/// The names generated into one module, to report two keys generating the same name.
+struct ConstantNames {
+    scope: String,
+    names: HashMap<String, String>,
+}
+
+impl ConstantNames {
+    fn new(scope: impl Into<String>) -> Self {
+        Self {
+            scope: scope.into(),
+            names: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, generated: &str, source: &str) -> syn::Result<()> {
+        match self.names.get(generated) {
+            Some(previous) => Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "synext: `{}` and `{}` both generate `{}` in {}",
+                    previous, source, generated, self.scope
+                ),
+            )),
+            None => {
+                self.names.insert(generated.to_string(), source.to_string());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    name.split(['_', '-'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,168 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/attr/template
+
+// ----------------------------------------------------------------
+
+use syn::{Lit, LitStr};
+
+use crate::diag::ErrorAccumulator;
+use crate::syntax::attr::parser::Spanned;
+use crate::syntax::attr::schema::AttributeValues;
+
+// ----------------------------------------------------------------
+
+/// Variables available to attribute value templates, usually defined by container attributes.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars {
+    vars: Vec<(String, String)>,
+}
+
+impl TemplateVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect every string, integer, float and bool value of `values` as a variable,
+    /// e.g. the container's `#[x(prefix = "api", version = 2)]`.
+    pub fn from_values(values: &AttributeValues) -> Self {
+        let mut vars = TemplateVars::new();
+        for value in values {
+            let name = match value.key.get_ident() {
+                Some(ident) => ident.to_string(),
+                None => continue,
+            };
+            let text = match value.value {
+                Some(Lit::Str(ref lit)) => lit.value(),
+                Some(Lit::Int(ref lit)) => lit.base10_digits().to_string(),
+                Some(Lit::Float(ref lit)) => lit.base10_digits().to_string(),
+                Some(Lit::Bool(ref lit)) => lit.value.to_string(),
+                _ => continue,
+            };
+            vars = vars.with(name, text);
+        }
+
+        vars
+    }
+
+    /// Define `name`, replacing an earlier definition.
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let (name, value) = (name.into(), value.into());
+        match self.vars.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.vars.push((name, value)),
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.vars.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// Try to render a templated attribute value, replacing `{name}` with the variable `name`.
+///
+/// `{{` and `}}` are literal braces. Undefined variables and unbalanced braces are reported on
+/// the template literal, all problems combined into one [`syn::Error`].
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Route)]
+/// #[route(prefix = "api")]
+/// pub struct Users {
+///     #[route(path = "{prefix}/users")]
+///     list: Handler,
+/// }
+///
+/// ->
+/// let vars = TemplateVars::from_values(&container_values);
+/// let path = render_template(&lit, &vars)?; // "api/users"
+/// ```
+/// @since 0.4.0
+pub fn render_template(template: &LitStr, vars: &TemplateVars) -> syn::Result<Spanned<String>> {
+    let source = template.value();
+    let mut rendered = String::with_capacity(source.len());
+    let mut errors = ErrorAccumulator::new();
+    let mut push_error = |message: String| errors.push(syn::Error::new(template.span(), message));
+
+    let mut chars = source.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for ch in chars.by_ref() {
+                    if ch == '}' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(ch);
+                }
+                if !closed {
+                    push_error(format!(
+                        "unclosed `{{{}` in template, use `{{{{` for a literal brace",
+                        name
+                    ));
+                    continue;
+                }
+                match vars.get(name.trim()) {
+                    Some(value) => rendered.push_str(value),
+                    None => {
+                        let known = vars
+                            .names()
+                            .map(|name| format!("`{}`", name))
+                            .collect::<Vec<_>>();
+                        push_error(match known.is_empty() {
+                            true => format!("undefined template variable `{}`", name.trim()),
+                            false => format!(
+                                "undefined template variable `{}`, expected one of {}",
+                                name.trim(),
+                                known.join(", ")
+                            ),
+                        })
+                    }
+                }
+            }
+            '}' => {
+                push_error("unmatched `}` in template, use `}}` for a literal brace".to_string())
+            }
+            ch => rendered.push(ch),
+        }
+    }
+
+    errors.finish_with(Spanned::new(rendered, template.span()))
+}
@@ -0,0 +1,1668 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/codegen/generator
+
+// ----------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use proc_macro2::{Group, Literal, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt as _;
+use syn::spanned::Spanned as _;
+use syn::{
+    Attribute, BinOp, Data, DeriveInput, Expr, Field, Fields, GenericParam, Generics, Ident,
+    Lifetime, LifetimeDef, Lit, LitStr, Member, Meta, NestedMeta, Path, Type, Variant,
+    WherePredicate,
+};
+
+use crate::diag::ErrorAccumulator;
+use crate::syntax::analysis::parser::try_collect_all_fields;
+use crate::syntax::attr::parser::{mark_attr_consumed, try_extract_attribute_path_value};
+use crate::syntax::generics::parser::{
+    collect_type_params_in_type, normalize_where_clause_with, OutputOrder,
+};
+
+// ----------------------------------------------------------------
+
+/// Try to convert an `UpperCamelCase` identifier into `snake_case`.
+///
+/// - `HttpError` -> `http_error`
+/// - `IOError` -> `io_error`
+/// - `V2` -> `v2`
+///
+/// @since 0.4.0
+pub fn try_to_snake_case(ident: &str) -> String {
+    let chars: Vec<char> = ident.chars().collect();
+    let mut snake = String::with_capacity(ident.len() + 4);
+
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let prev = index.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(index + 1);
+            let boundary = match prev {
+                Some(prev) if prev != '_' => {
+                    prev.is_lowercase()
+                        || prev.is_ascii_digit()
+                        || (prev.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+                }
+                _ => false,
+            };
+            if boundary {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+
+    snake
+}
+
+// ---------------------------------------------------------------- span.attribution
+
+/// Where the tokens generated for a field, variant or attribute are spanned.
+///
+/// Taken by [`try_generate_variant_accessors_with`], [`try_generate_flag_set_with`],
+/// [`DispatchTable::with_span_mode`], [`PinProjection::with_span_mode`],
+/// [`emit_assert_impl_with`], [`emit_field_bound_checks_with`] and [`render_impl_header_with`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpanMode {
+    /// At the macro call site, rustc reports errors on the `#[derive(...)]`.
+    #[default]
+    CallSite,
+    /// At the originating field, variant or attribute, rustc reports errors
+    /// (e.g. a missing trait impl inside a generated impl) on the user's declaration.
+    Origin,
+}
+
+impl SpanMode {
+    /// The span generated tokens take for `origin` in this mode.
+    ///
+    /// # Examples
+    ///
+    ///```ignore
+    /// for field in fields {
+    ///     let span = mode.span_of(&field.ty);
+    ///     let ty = &field.ty;
+    ///     getters.push(quote_spanned! {span=>
+    ///         pub fn #getter(&self) -> &#ty { &self.#ident }
+    ///     });
+    /// }
+    /// ```
+    pub fn span_of<T: ToTokens>(&self, origin: &T) -> Span {
+        match self {
+            SpanMode::CallSite => Span::call_site(),
+            SpanMode::Origin => origin.span(),
+        }
+    }
+}
+
+/// Rewrite the span of every token of `tokens`, recursing into groups, to `span`.
+///
+/// Spliced user expressions keep their own spans: respan them to control where rustc reports
+/// errors about them, e.g. to the attribute they were written in.
+///
+/// # Examples
+///
+///```ignore
+/// let default: Expr = lit.parse()?;
+/// let default = respan(default.to_token_stream(), lit.span());
+/// quote! { #ident: #default, }
+/// ```
+/// @since 0.4.0
+pub fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tree| respan_token_tree(tree, span))
+        .collect()
+}
+
+fn respan_token_tree(mut tree: TokenTree, span: Span) -> TokenTree {
+    match tree {
+        TokenTree::Group(ref group) => {
+            let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+            respanned.set_span(span);
+            TokenTree::Group(respanned)
+        }
+        _ => {
+            tree.set_span(span);
+            tree
+        }
+    }
+}
+
+// ---------------------------------------------------------------- variant.accessor
+
+/// Try to generate `is_x()`, `as_x()` and `into_x()` accessors for every variant of an enum.
+///
+/// - unit variants get `is_x(&self) -> bool`
+/// - variants with payload additionally get `as_x(&self) -> Option<&T>` and
+///   `into_x(self) -> Result<T, Self>`, multi-field payloads are returned as tuples
+/// - `x` is the snake_case variant name, overridden with `#[attribute(rename = "name")]`
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Accessor)]
+/// pub enum Shape {
+///     Empty,
+///     #[accessor(rename = "circle")]
+///     Round(f64),
+///     Rect { w: f64, h: f64 },
+/// }
+///
+/// ->
+/// try_generate_variant_accessors(&derive_input, "accessor")
+/// // is_empty(), is_circle(), as_circle(), into_circle(), is_rect(), as_rect(), into_rect()
+/// ```
+/// @since 0.4.0
+pub fn try_generate_variant_accessors(
+    input: &DeriveInput,
+    attribute: &str,
+) -> syn::Result<TokenStream> {
+    try_generate_variant_accessors_with(input, attribute, SpanMode::CallSite)
+}
+
+/// Like [`try_generate_variant_accessors`], with the accessors of every variant spanned
+/// according to `mode`: with [`SpanMode::Origin`] errors inside them point at the variant.
+///
+/// @since 0.4.0
+pub fn try_generate_variant_accessors_with(
+    input: &DeriveInput,
+    attribute: &str,
+    mode: SpanMode,
+) -> syn::Result<TokenStream> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("synext: Only enums are supported! target:`{}`", input.ident),
+            ))
+        }
+    };
+
+    let mut methods = Vec::with_capacity(data.variants.len() * 3);
+    for variant in &data.variants {
+        let variant_ident = &variant.ident;
+        let span = mode.span_of(variant);
+        let name = match try_extract_attribute_path_value(&variant.attrs, attribute, "rename")? {
+            Some(rename) => rename.parse::<Ident>()?.to_string(),
+            None => try_to_snake_case(&variant_ident.to_string()),
+        };
+        let is_fn = format_ident!("is_{}", name, span = variant_ident.span());
+        let as_fn = format_ident!("as_{}", name, span = variant_ident.span());
+        let into_fn = format_ident!("into_{}", name, span = variant_ident.span());
+
+        let bindings: Vec<Ident> = (0..variant.fields.len())
+            .map(|index| format_ident!("__synext_{}", index))
+            .collect();
+        let types: Vec<_> = variant.fields.iter().map(|field| &field.ty).collect();
+        let pattern = match &variant.fields {
+            Fields::Unit => quote!(Self::#variant_ident),
+            Fields::Unnamed(_) => quote!(Self::#variant_ident(#(#bindings),*)),
+            Fields::Named(fields) => {
+                let members = fields.named.iter().map(|field| &field.ident);
+                quote!(Self::#variant_ident { #(#members: #bindings),* })
+            }
+        };
+        let wildcard = match &variant.fields {
+            Fields::Unit => quote!(Self::#variant_ident),
+            Fields::Unnamed(_) => quote!(Self::#variant_ident(..)),
+            Fields::Named(_) => quote!(Self::#variant_ident { .. }),
+        };
+
+        methods.push(quote_spanned! {span=>
+            #[inline]
+            pub fn #is_fn(&self) -> bool {
+                matches!(self, #wildcard)
+            }
+        });
+
+        if variant.fields.is_empty() {
+            continue;
+        }
+
+        let (ref_ty, owned_ty, value) = if types.len() == 1 {
+            let ty = types[0];
+            let binding = &bindings[0];
+            (quote!(&#ty), quote!(#ty), quote!(#binding))
+        } else {
+            (
+                quote!((#(&#types),*)),
+                quote!((#(#types),*)),
+                quote!((#(#bindings),*)),
+            )
+        };
+
+        methods.push(quote_spanned! {span=>
+            #[inline]
+            pub fn #as_fn(&self) -> ::core::option::Option<#ref_ty> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value),
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            #[inline]
+            pub fn #into_fn(self) -> ::core::result::Result<#owned_ty, Self> {
+                match self {
+                    #pattern => ::core::result::Result::Ok(#value),
+                    #[allow(unreachable_patterns)]
+                    other => ::core::result::Result::Err(other),
+                }
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+// ---------------------------------------------------------------- dispatch.table
+
+/// One entry of a [`DispatchTable`]: the variant and the handler it dispatches to.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct DispatchEntry<'a> {
+    pub variant: &'a Variant,
+    pub handler: Path,
+    /// Whether the handler is the fallback rather than declared on the variant.
+    pub fallback: bool,
+}
+
+/// A dispatch table from enum variants to the handler paths declared with
+/// `#[attribute(handler = "path::to::handler")]`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct DispatchTable<'a> {
+    pub entries: Vec<DispatchEntry<'a>>,
+    /// Where the arms and handlers of every variant are spanned, [`SpanMode::CallSite`] by default.
+    pub span_mode: SpanMode,
+}
+
+impl<'a> DispatchTable<'a> {
+    /// Span the rendered arms and handlers of every variant according to `mode`: with
+    /// [`SpanMode::Origin`] a handler with the wrong signature is reported on its variant.
+    pub fn with_span_mode(mut self, mode: SpanMode) -> Self {
+        self.span_mode = mode;
+        self
+    }
+
+    /// Render `match scrutinee { Self::A { .. } => handler_a(args), ... }`.
+    pub fn to_match_tokens(&self, scrutinee: &TokenStream, args: &TokenStream) -> TokenStream {
+        let arms = self.entries.iter().map(|entry| {
+            let ident = &entry.variant.ident;
+            let handler = &entry.handler;
+            let span = self.span_mode.span_of(entry.variant);
+            quote_spanned!(span=> Self::#ident { .. } => #handler(#args),)
+        });
+
+        quote! {
+            match #scrutinee {
+                #(#arms)*
+            }
+        }
+    }
+
+    /// Render `match scrutinee { Self::A { .. } => 0usize, ... }`, the index of each variant
+    /// in the array rendered by [`DispatchTable::to_fn_table_tokens`].
+    pub fn to_index_match_tokens(&self, scrutinee: &TokenStream) -> TokenStream {
+        let arms = self.entries.iter().enumerate().map(|(index, entry)| {
+            let ident = &entry.variant.ident;
+            let span = self.span_mode.span_of(entry.variant);
+            quote_spanned!(span=> Self::#ident { .. } => #index,)
+        });
+
+        quote! {
+            match #scrutinee {
+                #(#arms)*
+            }
+        }
+    }
+
+    /// Render the fn-pointer array `[handler_a as fn_ty, handler_b as fn_ty, ...]` in variant order.
+    pub fn to_fn_table_tokens(&self, fn_ty: &Type) -> TokenStream {
+        let handlers = self.entries.iter().map(|entry| {
+            let handler = &entry.handler;
+            let span = self.span_mode.span_of(entry.variant);
+            quote_spanned!(span=> #handler as #fn_ty)
+        });
+
+        quote!([#(#handlers),*])
+    }
+}
+
+/// Try to parse the dispatch table of an enum from `#[attribute(handler = "...")]` variant attributes.
+///
+/// The table must be exhaustive: every variant needs a handler unless a `fallback` handler is given,
+/// and a `#[non_exhaustive]` enum always requires a `fallback`, so variants added later keep dispatching.
+/// All variants missing a handler or declaring a malformed one are reported in one combined
+/// [`syn::Error`].
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Dispatch)]
+/// pub enum Command {
+///     #[dispatch(handler = "handlers::start")]
+///     Start,
+///     #[dispatch(handler = "handlers::stop")]
+///     Stop { force: bool },
+/// }
+///
+/// ->
+/// let table = try_parse_dispatch_table(&derive_input, "dispatch", None)?;
+/// let body = table.to_match_tokens(&quote!(self), &quote!(self, ctx));
+/// ```
+/// @since 0.4.0
+pub fn try_parse_dispatch_table<'a>(
+    input: &'a DeriveInput,
+    attribute: &str,
+    fallback: Option<&Path>,
+) -> syn::Result<DispatchTable<'a>> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("synext: Only enums are supported! target:`{}`", input.ident),
+            ))
+        }
+    };
+
+    if fallback.is_none()
+        && input
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("non_exhaustive"))
+    {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            format!(
+                "synext: `#[non_exhaustive]` enum `{}` requires a fallback dispatch handler",
+                input.ident
+            ),
+        ));
+    }
+
+    let mut entries = Vec::with_capacity(data.variants.len());
+    let mut errors = ErrorAccumulator::new();
+    for variant in &data.variants {
+        let declared = match errors.handle(try_extract_attribute_path_value(
+            &variant.attrs,
+            attribute,
+            "handler",
+        )) {
+            Some(declared) => declared,
+            None => continue,
+        };
+        let entry = match (declared, fallback) {
+            (Some(handler), _) => match errors.handle(handler.parse()) {
+                Some(handler) => DispatchEntry {
+                    variant,
+                    handler,
+                    fallback: false,
+                },
+                None => continue,
+            },
+            (None, Some(fallback)) => DispatchEntry {
+                variant,
+                handler: fallback.clone(),
+                fallback: true,
+            },
+            (None, None) => {
+                let error = syn::Error::new_spanned(
+                    &variant.ident,
+                    format!(
+                        r#"missing dispatch handler, expected `#[{}(handler = "...")]`"#,
+                        attribute
+                    ),
+                );
+                errors.push(error);
+                continue;
+            }
+        };
+        entries.push(entry);
+    }
+
+    errors.finish_with(DispatchTable {
+        entries,
+        span_mode: SpanMode::CallSite,
+    })
+}
+
+// ---------------------------------------------------------------- bitflags
+
+/// A validated flag variant: a fieldless variant with a single-bit discriminant.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct FlagVariant<'a> {
+    pub variant: &'a Variant,
+    pub bits: u128,
+}
+
+/// The validated flags of a fieldless `#[repr(uN)]` enum.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct FlagSet<'a> {
+    /// The unsigned integer of `#[repr(...)]`.
+    pub repr: Ident,
+    pub flags: Vec<FlagVariant<'a>>,
+}
+
+impl<'a> FlagSet<'a> {
+    /// The union of all flag bits.
+    pub fn all_bits(&self) -> u128 {
+        self.flags.iter().fold(0, |all, flag| all | flag.bits)
+    }
+}
+
+/// Try to parse and validate the flags of a bitflags-style enum.
+///
+/// - the enum must be `#[repr(u8|u16|u32|u64|u128|usize)]`
+/// - every variant must be fieldless with an explicit `N` or `1 << N` discriminant
+/// - every discriminant must be a power of two that fits the repr, and must be unique
+///
+/// All invalid variants are reported in one combined [`syn::Error`].
+///
+/// @since 0.4.0
+pub fn try_parse_flag_set(input: &DeriveInput) -> syn::Result<FlagSet<'_>> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("synext: Only enums are supported! target:`{}`", input.ident),
+            ))
+        }
+    };
+
+    let (repr, width) = try_extract_unsigned_repr(input)?;
+    let mut flags: Vec<FlagVariant> = Vec::with_capacity(data.variants.len());
+    let mut errors = ErrorAccumulator::new();
+
+    for variant in &data.variants {
+        if !variant.fields.is_empty() {
+            errors.push(syn::Error::new_spanned(
+                &variant.fields,
+                "flag variants must be fieldless",
+            ));
+            continue;
+        }
+        let discriminant = match &variant.discriminant {
+            Some((_, expr)) => expr,
+            None => {
+                errors.push(syn::Error::new_spanned(
+                    &variant.ident,
+                    "flag variants require an explicit discriminant, e.g. `= 1 << 2`",
+                ));
+                continue;
+            }
+        };
+        let bits = match try_evaluate_flag_discriminant(discriminant) {
+            Ok(bits) => bits,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        if !bits.is_power_of_two() {
+            errors.push(syn::Error::new_spanned(
+                discriminant,
+                format!("flag discriminant `{:#x}` must be a power of two", bits),
+            ));
+        } else if width < 128 && bits >> width != 0 {
+            errors.push(syn::Error::new_spanned(
+                discriminant,
+                format!("flag discriminant `{:#x}` overflows `{}`", bits, repr),
+            ));
+        } else if let Some(other) = flags.iter().find(|flag| flag.bits == bits) {
+            errors.push(syn::Error::new_spanned(
+                discriminant,
+                format!(
+                    "flag discriminant `{:#x}` is already used by `{}`",
+                    bits, other.variant.ident
+                ),
+            ));
+        } else {
+            flags.push(FlagVariant { variant, bits });
+        }
+    }
+
+    errors.finish_with(FlagSet { repr, flags })
+}
+
+/// Try to generate the companion flag set `set_ident` of a bitflags-style enum, see [`try_parse_flag_set`].
+///
+/// The set is a `Copy` newtype over the repr with `empty`/`all`/`bits`/`from_bits`/`from_bits_truncate`,
+/// `is_empty`/`contains`/`insert`/`remove`/`iter`, the `|`, `&`, `^`, `!` operators (and their assign forms),
+/// `From<Enum>` and `Enum | Enum -> Set`.
+///
+/// The bits are stored in the repr, a `#[repr(usize)]` enum in a `u64` so the generated set does not
+/// depend on the pointer width of the target.
+///
+/// @since 0.4.0
+pub fn try_generate_flag_set(input: &DeriveInput, set_ident: &Ident) -> syn::Result<TokenStream> {
+    try_generate_flag_set_with(input, set_ident, SpanMode::CallSite)
+}
+
+/// Like [`try_generate_flag_set`], with the generated items spanned according to `mode`:
+/// with [`SpanMode::Origin`] they are spanned at the enum and the flag arms at their variant.
+///
+/// @since 0.4.0
+pub fn try_generate_flag_set_with(
+    input: &DeriveInput,
+    set_ident: &Ident,
+    mode: SpanMode,
+) -> syn::Result<TokenStream> {
+    let flag_set = try_parse_flag_set(input)?;
+    let span = mode.span_of(&input.ident);
+
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let repr = flag_set_storage(&flag_set.repr);
+    let all = Literal::u128_unsuffixed(flag_set.all_bits());
+    let count = flag_set.flags.len();
+    let bits: Vec<Literal> = flag_set
+        .flags
+        .iter()
+        .map(|flag| Literal::u128_unsuffixed(flag.bits))
+        .collect();
+    let arms: Vec<TokenStream> = flag_set
+        .flags
+        .iter()
+        .zip(&bits)
+        .map(|(flag, bit)| {
+            let variant = &flag.variant.ident;
+            let span = mode.span_of(flag.variant);
+            quote_spanned!(span=> #bit => #ident::#variant,)
+        })
+        .collect();
+
+    Ok(quote_spanned! {span=>
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+        #vis struct #set_ident(#repr);
+
+        impl #set_ident {
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            pub const fn all() -> Self {
+                Self(#all)
+            }
+
+            pub const fn bits(&self) -> #repr {
+                self.0
+            }
+
+            pub const fn from_bits(bits: #repr) -> ::core::option::Option<Self> {
+                if bits & !#all == 0 {
+                    ::core::option::Option::Some(Self(bits))
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            pub const fn from_bits_truncate(bits: #repr) -> Self {
+                Self(bits & #all)
+            }
+
+            pub const fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            pub const fn contains(&self, flag: #ident) -> bool {
+                self.0 & (flag as #repr) != 0
+            }
+
+            pub fn insert(&mut self, flag: #ident) {
+                self.0 |= flag as #repr;
+            }
+
+            pub fn remove(&mut self, flag: #ident) {
+                self.0 &= !(flag as #repr);
+            }
+
+            pub fn iter(&self) -> impl ::core::iter::Iterator<Item = #ident> {
+                const BITS: [#repr; #count] = [#(#bits),*];
+                let set = self.0;
+                ::core::iter::IntoIterator::into_iter(BITS)
+                    .filter(move |bit| set & bit != 0)
+                    .map(|bit| match bit {
+                        #(#arms)*
+                        _ => ::core::unreachable!(),
+                    })
+            }
+        }
+
+        impl ::core::convert::From<#ident> for #set_ident {
+            fn from(flag: #ident) -> Self {
+                Self(flag as #repr)
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitOr<T> for #set_ident {
+            type Output = Self;
+            fn bitor(self, rhs: T) -> Self {
+                Self(self.0 | rhs.into().0)
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitAnd<T> for #set_ident {
+            type Output = Self;
+            fn bitand(self, rhs: T) -> Self {
+                Self(self.0 & rhs.into().0)
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitXor<T> for #set_ident {
+            type Output = Self;
+            fn bitxor(self, rhs: T) -> Self {
+                Self(self.0 ^ rhs.into().0)
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitOrAssign<T> for #set_ident {
+            fn bitor_assign(&mut self, rhs: T) {
+                self.0 |= rhs.into().0;
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitAndAssign<T> for #set_ident {
+            fn bitand_assign(&mut self, rhs: T) {
+                self.0 &= rhs.into().0;
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitXorAssign<T> for #set_ident {
+            fn bitxor_assign(&mut self, rhs: T) {
+                self.0 ^= rhs.into().0;
+            }
+        }
+
+        impl ::core::ops::Not for #set_ident {
+            type Output = Self;
+            fn not(self) -> Self {
+                Self(!self.0 & #all)
+            }
+        }
+
+        impl<T: ::core::convert::Into<#set_ident>> ::core::ops::BitOr<T> for #ident {
+            type Output = #set_ident;
+            fn bitor(self, rhs: T) -> #set_ident {
+                #set_ident::from(self) | rhs
+            }
+        }
+    })
+}
+
+/// The fixed-width integer the generated set stores its bits in, a `usize` repr is stored as `u64`
+/// so the set has the same layout and API on every target.
+fn flag_set_storage(repr: &Ident) -> Ident {
+    match repr == "usize" {
+        true => Ident::new("u64", repr.span()),
+        false => repr.clone(),
+    }
+}
+
+fn try_extract_unsigned_repr(input: &DeriveInput) -> syn::Result<(Ident, u32)> {
+    let attr = match input.attrs.iter().find(|attr| attr.path.is_ident("repr")) {
+        Some(attr) => attr,
+        None => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "flag enums require `#[repr(u8|u16|u32|u64|u128|usize)]`",
+            ))
+        }
+    };
+
+    let ident: Ident = attr.parse_args()?;
+    let width = match ident.to_string().as_str() {
+        "u8" => 8,
+        "u16" => 16,
+        "u32" => 32,
+        "u64" => 64,
+        "u128" => 128,
+        // the macro runs on the host, whose `usize` may be wider than the target's
+        "usize" => 64,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "flag enums require an unsigned `#[repr(...)]`",
+            ))
+        }
+    };
+
+    Ok((ident, width))
+}
+
+fn try_evaluate_flag_discriminant(expr: &Expr) -> syn::Result<u128> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse::<u128>(),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "expected an integer discriminant",
+            )),
+        },
+        Expr::Paren(paren) => try_evaluate_flag_discriminant(&paren.expr),
+        Expr::Group(group) => try_evaluate_flag_discriminant(&group.expr),
+        Expr::Binary(binary) if matches!(binary.op, BinOp::Shl(_)) => {
+            let base = try_evaluate_flag_discriminant(&binary.left)?;
+            let shift = try_evaluate_flag_discriminant(&binary.right)?;
+            if shift >= 128 || base.leading_zeros() < shift as u32 {
+                return Err(syn::Error::new_spanned(
+                    binary,
+                    "flag discriminant overflows `u128`",
+                ));
+            }
+            Ok(base << shift)
+        }
+        other => Err(syn::Error::new_spanned(
+            other,
+            "expected an integer literal or `1 << N` flag discriminant",
+        )),
+    }
+}
+
+// ---------------------------------------------------------------- attribute.passthrough
+
+/// Re-emit an attribute onto a generated item, preserving its tokens and spans.
+///
+/// @since 0.4.0
+pub fn attr_to_tokens(attr: &Attribute) -> TokenStream {
+    attr.to_token_stream()
+}
+
+/// Re-emit attributes onto a generated item, preserving their order and spans.
+///
+/// # Examples
+///
+///```ignore
+/// // copy `#[serde(...)]` and doc comments from the source field to the generated mirror field
+/// let attrs = attrs_to_tokens(filter_attrs(&field.attrs, &["serde", "doc"]));
+/// quote! {
+///     #attrs
+///     pub #ident: #ty,
+/// }
+/// ```
+/// @since 0.4.0
+pub fn attrs_to_tokens<'a, I>(attrs: I) -> TokenStream
+where
+    I: IntoIterator<Item = &'a Attribute>,
+{
+    let mut tokens = TokenStream::new();
+    for attr in attrs {
+        attr.to_tokens(&mut tokens);
+    }
+
+    tokens
+}
+
+/// Select the attributes whose path is one of `paths` (`"serde"`, `"doc"`, `"cfg"`), in order.
+///
+/// @since 0.4.0
+pub fn filter_attrs<'a>(attrs: &'a [Attribute], paths: &[&str]) -> Vec<&'a Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| paths.iter().any(|path| attr.path.is_ident(path)))
+        .collect()
+}
+
+/// Decides which attributes of a source field are copied onto generated fields
+/// (mirror structs, builders), since blindly copying helper attributes such as
+/// `#[builder(...)]` onto an item without that derive breaks compilation.
+///
+/// Paths are matched on their full text: `"serde"`, `"rustfmt::skip"`.
+/// An attribute is copied when it passes the allowlist (if any) and is not denied.
+///
+/// # Examples
+///
+///```ignore
+/// let policy = AttrCopyPolicy::allow(&["serde", "doc"]);
+/// let policy = AttrCopyPolicy::copy_all().deny(&["builder"]);
+///
+/// let attrs = policy.to_tokens(&field.attrs);
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrCopyPolicy {
+    allow: Option<Vec<String>>,
+    deny: Vec<String>,
+}
+
+impl Default for AttrCopyPolicy {
+    /// Only copy attributes that are meaningful on any item: docs, `cfg`s and lint levels.
+    fn default() -> Self {
+        Self::allow(&["doc", "cfg", "cfg_attr", "allow", "warn", "deny", "forbid"])
+    }
+}
+
+impl AttrCopyPolicy {
+    pub fn copy_all() -> Self {
+        Self {
+            allow: None,
+            deny: Vec::new(),
+        }
+    }
+
+    pub fn copy_none() -> Self {
+        Self {
+            allow: Some(Vec::new()),
+            deny: Vec::new(),
+        }
+    }
+
+    pub fn allow(paths: &[&str]) -> Self {
+        Self {
+            allow: Some(paths.iter().map(|path| path.to_string()).collect()),
+            deny: Vec::new(),
+        }
+    }
+
+    /// Extend the allowlist, no-op when every attribute is already allowed.
+    pub fn and_allow(mut self, paths: &[&str]) -> Self {
+        if let Some(ref mut allow) = self.allow {
+            allow.extend(paths.iter().map(|path| path.to_string()));
+        }
+        self
+    }
+
+    pub fn deny(mut self, paths: &[&str]) -> Self {
+        self.deny.extend(paths.iter().map(|path| path.to_string()));
+        self
+    }
+
+    pub fn should_copy(&self, attr: &Attribute) -> bool {
+        let path = attr_path_text(attr);
+        let allowed = match self.allow {
+            Some(ref allow) => allow.contains(&path),
+            None => true,
+        };
+
+        allowed && !self.deny.contains(&path)
+    }
+
+    pub fn select<'a>(&self, attrs: &'a [Attribute]) -> Vec<&'a Attribute> {
+        attrs.iter().filter(|attr| self.should_copy(attr)).collect()
+    }
+
+    pub fn to_tokens(&self, attrs: &[Attribute]) -> TokenStream {
+        attrs_to_tokens(self.select(attrs))
+    }
+}
+
+fn attr_path_text(attr: &Attribute) -> String {
+    let mut text = String::new();
+    if attr.path.leading_colon.is_some() {
+        text.push_str("::");
+    }
+    for (index, segment) in attr.path.segments.iter().enumerate() {
+        if index > 0 {
+            text.push_str("::");
+        }
+        text.push_str(&segment.ident.to_string());
+    }
+
+    text
+}
+
+// ---------------------------------------------------------------- attribute.forward
+
+/// Rebuilds an attribute invocation `#[path(args...)]` from the arguments of an attribute macro
+/// or of an existing attribute, with keys removed, renamed or rewritten, so it can be forwarded
+/// onto the emitted item.
+///
+/// Keys are matched on the path of `key = value`, `key(...)` and bare `key` arguments. Keys passed
+/// to [`AttrForwarder::rename`] and [`AttrForwarder::set`] may be keywords, e.g. `crate`, anything
+/// else that isn't an identifier is an error.
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_attribute]
+/// pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
+///     let args = parse_macro_input!(args as AttributeArgs);
+///
+///     // #[component(value = "helloController", lazy, scope = "singleton")]
+///     let forward = AttrForwarder::from_args(parse_quote!(registry), args)
+///         .remove("lazy")
+///         .rename("value", "name")?
+///         .set("scope", parse_quote!("prototype"))?;
+///     // quote! { #forward } -> #[registry(name = "helloController", scope = "prototype")]
+///
+///     let derive = AttrForwarder::new(parse_quote!(derive)).push(parse_quote!(Clone));
+///     // quote! { #derive } -> #[derive(Clone)]
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct AttrForwarder {
+    path: Path,
+    args: Vec<NestedMeta>,
+}
+
+impl AttrForwarder {
+    pub fn new(path: Path) -> Self {
+        Self {
+            path,
+            args: Vec::new(),
+        }
+    }
+
+    /// Forward the arguments of an attribute macro, see [`syn::AttributeArgs`].
+    pub fn from_args(path: Path, args: Vec<NestedMeta>) -> Self {
+        Self { path, args }
+    }
+
+    /// Forward an existing list attribute `#[path(args...)]`, a bare `#[path]` has no arguments.
+    pub fn from_attribute(attr: &Attribute) -> syn::Result<Self> {
+        match attr.parse_meta()? {
+            Meta::Path(path) => Ok(Self::new(path)),
+            Meta::List(list) => Ok(Self::from_args(
+                list.path,
+                list.nested.into_iter().collect(),
+            )),
+            meta @ Meta::NameValue(_) => Err(syn::Error::new_spanned(
+                meta,
+                "synext: Expected a list attribute `#[path(...)]` to forward",
+            )),
+        }
+    }
+
+    /// Forward under another attribute path.
+    pub fn path(mut self, path: Path) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Remove every argument with the key `key`.
+    pub fn remove(mut self, key: &str) -> Self {
+        self.args.retain(|arg| !nested_meta_key_is(arg, key));
+        self
+    }
+
+    /// Rename the key of every argument with the key `from`, keeping its value.
+    pub fn rename(mut self, from: &str, to: &str) -> syn::Result<Self> {
+        let to = parse_attr_key(to)?;
+        for arg in self.args.iter_mut() {
+            if nested_meta_key_is(arg, from) {
+                if let NestedMeta::Meta(meta) = arg {
+                    let path = match meta {
+                        Meta::Path(path) => path,
+                        Meta::List(list) => &mut list.path,
+                        Meta::NameValue(nv) => &mut nv.path,
+                    };
+                    let mut key = to.clone();
+                    key.set_span(path.span());
+                    *path = key.into();
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Set `key = value`, replacing the first argument with the key `key` in place
+    /// (and dropping the others), or appending it.
+    pub fn set(mut self, key: &str, value: Lit) -> syn::Result<Self> {
+        let meta: NestedMeta = {
+            let key = parse_attr_key(key)?;
+            syn::parse_quote!(#key = #value)
+        };
+        match self
+            .args
+            .iter()
+            .position(|arg| nested_meta_key_is(arg, key))
+        {
+            Some(index) => {
+                self.args[index] = meta;
+                let mut position = 0;
+                self.args.retain(|arg| {
+                    let keep = position == index || !nested_meta_key_is(arg, key);
+                    position += 1;
+                    keep
+                });
+            }
+            None => self.args.push(meta),
+        }
+        Ok(self)
+    }
+
+    /// Append an argument, e.g. `Clone` to a forwarded `#[derive(...)]`.
+    pub fn push(mut self, arg: NestedMeta) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    pub fn args(&self) -> &[NestedMeta] {
+        &self.args
+    }
+
+    /// The arguments alone, `name = "helloController", scope = "prototype"`.
+    pub fn to_args_tokens(&self) -> TokenStream {
+        let args = &self.args;
+        quote! { #(#args),* }
+    }
+}
+
+/// The attribute `#[path(args...)]`, or `#[path]` without arguments.
+impl ToTokens for AttrForwarder {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let path = &self.path;
+        match self.args.is_empty() {
+            true => tokens.extend(quote! { #[#path] }),
+            false => {
+                let args = self.to_args_tokens();
+                tokens.extend(quote! { #[#path(#args)] });
+            }
+        }
+    }
+}
+
+fn parse_attr_key(key: &str) -> syn::Result<Ident> {
+    syn::parse::Parser::parse_str(Ident::parse_any, key).map_err(|_| {
+        syn::Error::new(
+            Span::call_site(),
+            format!(
+                "synext: Expected an attribute key identifier, got `{}`",
+                key
+            ),
+        )
+    })
+}
+
+fn nested_meta_key_is(arg: &NestedMeta, key: &str) -> bool {
+    match arg {
+        NestedMeta::Meta(meta) => meta.path().is_ident(key),
+        NestedMeta::Lit(_) => false,
+    }
+}
+
+// ---------------------------------------------------------------- pin.projection
+
+/// A field of a [`PinProjection`], structurally pinned when marked `#[pin]`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct PinField<'a> {
+    pub field: &'a Field,
+    pub member: Member,
+    pub pinned: bool,
+}
+
+impl<'a> PinField<'a> {
+    /// The projected type of the field: `Pin<&'lifetime mut T>` for pinned fields,
+    /// `&'lifetime mut T` otherwise (`&'lifetime T` / `Pin<&'lifetime T>` when not `mutable`).
+    pub fn projected_type(&self, lifetime: &Lifetime, mutable: bool) -> TokenStream {
+        self.projected_type_at(lifetime, mutable, Span::call_site())
+    }
+
+    fn projected_type_at(&self, lifetime: &Lifetime, mutable: bool, span: Span) -> TokenStream {
+        let ty = &self.field.ty;
+        let reference = match mutable {
+            true => quote_spanned!(span=> &#lifetime mut #ty),
+            false => quote_spanned!(span=> &#lifetime #ty),
+        };
+
+        match self.pinned {
+            true => quote_spanned!(span=> ::core::pin::Pin<#reference>),
+            false => reference,
+        }
+    }
+}
+
+/// The pinned and unpinned fields of a struct, see [`try_parse_pin_projection`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct PinProjection<'a> {
+    pub input: &'a DeriveInput,
+    pub fields: Vec<PinField<'a>>,
+    /// Where the projected field types and values are spanned, [`SpanMode::CallSite`] by default.
+    pub span_mode: SpanMode,
+}
+
+impl<'a> PinProjection<'a> {
+    /// Span the projected types and values of every field according to `mode`: with
+    /// [`SpanMode::Origin`] errors about a projected field point at its declaration.
+    pub fn with_span_mode(mut self, mode: SpanMode) -> Self {
+        self.span_mode = mode;
+        self
+    }
+
+    pub fn pinned(&self) -> impl Iterator<Item = &PinField<'a>> {
+        self.fields.iter().filter(|field| field.pinned)
+    }
+
+    /// Generate the projection structs `projection` / `projection_ref` and the
+    /// `project(self: Pin<&mut Self>)` / `project_ref(self: Pin<&Self>)` methods.
+    ///
+    /// The macro stays responsible for the rest of the pinning contract: the struct must not
+    /// implement `Unpin` when a pinned field is `!Unpin`, and its `Drop` must not move pinned fields.
+    pub fn to_projection_tokens(&self, projection: &Ident, projection_ref: &Ident) -> TokenStream {
+        let input = self.input;
+        let ident = &input.ident;
+        let vis = &input.vis;
+        let lifetime = Lifetime::new("'__pin", Span::call_site());
+
+        let mut generics = input.generics.clone();
+        generics.params.insert(
+            0,
+            GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())),
+        );
+        // the structs declare the params with their bounds, the methods only name them
+        let (projection_generics, projection_ty_generics, projection_where_clause) =
+            generics.split_for_impl();
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+        let members: Vec<&Member> = self.fields.iter().map(|field| &field.member).collect();
+        let bindings: Vec<Ident> = (0..self.fields.len())
+            .map(|index| format_ident!("__synext_{}", index))
+            .collect();
+        let values: Vec<TokenStream> = self
+            .fields
+            .iter()
+            .zip(&bindings)
+            .map(|(field, binding)| {
+                let span = self.span_mode.span_of(&field.field.ty);
+                match field.pinned {
+                    true => quote_spanned!(span=> ::core::pin::Pin::new_unchecked(#binding)),
+                    false => quote!(#binding),
+                }
+            })
+            .collect();
+
+        let definition = |name: &Ident, mutable: bool| {
+            let types = self.fields.iter().map(|field| {
+                let span = self.span_mode.span_of(&field.field.ty);
+                field.projected_type_at(&lifetime, mutable, span)
+            });
+            let field_vis = self.fields.iter().map(|field| &field.field.vis);
+            match self.fields.first().map(|field| &field.member) {
+                Some(Member::Unnamed(_)) => quote! {
+                    #[allow(dead_code)]
+                    #vis struct #name #projection_generics (#(#field_vis #types),*) #projection_where_clause;
+                },
+                _ => {
+                    let names = self.fields.iter().map(|field| &field.field.ident);
+                    quote! {
+                        #[allow(dead_code)]
+                        #vis struct #name #projection_generics #projection_where_clause {
+                            #(#field_vis #names: #types,)*
+                        }
+                    }
+                }
+            }
+        };
+        let mut_definition = definition(projection, true);
+        let ref_definition = definition(projection_ref, false);
+
+        quote! {
+            #mut_definition
+            #ref_definition
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #[allow(dead_code)]
+                #vis fn project<#lifetime>(
+                    self: ::core::pin::Pin<&#lifetime mut Self>,
+                ) -> #projection #projection_ty_generics {
+                    // SAFETY: pinned fields are only exposed pinned, unpinned fields are never pinned.
+                    unsafe {
+                        let Self { #(#members: #bindings),* } = self.get_unchecked_mut();
+                        #projection { #(#members: #values),* }
+                    }
+                }
+
+                #[allow(dead_code)]
+                #vis fn project_ref<#lifetime>(
+                    self: ::core::pin::Pin<&#lifetime Self>,
+                ) -> #projection_ref #projection_ty_generics {
+                    // SAFETY: pinned fields are only exposed pinned, unpinned fields are never pinned.
+                    unsafe {
+                        let Self { #(#members: #bindings),* } = self.get_ref();
+                        #projection_ref { #(#members: #values),* }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Try to parse the fields of a struct for pin projection, fields marked `#[marker]`
+/// (usually `#[pin]`) are structurally pinned.
+///
+/// Diagnostics, combined into one [`syn::Error`]:
+///
+/// - enums, unions and structs without fields
+/// - `#[repr(packed)]` structs, whose fields cannot be pinned in place
+/// - `#[marker]` with arguments or repeated on a field
+///
+/// # Examples
+///
+///```ignore
+/// #[pin_project]
+/// pub struct Timeout<F> {
+///     #[pin]
+///     future: F,
+///     deadline: Instant,
+/// }
+///
+/// ->
+/// let projection = try_parse_pin_projection(&input, "pin")?;
+/// projection.to_projection_tokens(&format_ident!("TimeoutProj"), &format_ident!("TimeoutProjRef"))
+/// // fn project(self: Pin<&mut Self>) -> TimeoutProj { future: Pin<&mut F>, deadline: &mut Instant }
+/// ```
+/// @since 0.4.0
+pub fn try_parse_pin_projection<'a>(
+    input: &'a DeriveInput,
+    marker: &str,
+) -> syn::Result<PinProjection<'a>> {
+    let fields = match &input.data {
+        Data::Struct(data) if !data.fields.is_empty() => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "pin projection requires a struct with at least one field",
+            ))
+        }
+    };
+
+    let mut errors = ErrorAccumulator::new();
+
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        let packed = attr.parse_meta().is_ok_and(|meta| match meta {
+            Meta::List(list) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.is_ident("packed"),
+                NestedMeta::Meta(Meta::List(list)) => list.path.is_ident("packed"),
+                _ => false,
+            }),
+            _ => false,
+        });
+        if packed {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "pin projection is not supported on `#[repr(packed)]` structs",
+            ));
+        }
+    }
+
+    let mut projected = Vec::with_capacity(fields.len());
+    for (index, field) in fields.iter().enumerate() {
+        let markers: Vec<&Attribute> = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident(marker))
+            .collect();
+        for attr in &markers {
+            mark_attr_consumed(attr);
+            if !attr.tokens.is_empty() {
+                errors.push(syn::Error::new_spanned(
+                    attr,
+                    format!("`#[{}]` does not take arguments", marker),
+                ));
+            }
+        }
+        for duplicate in markers.iter().skip(1) {
+            errors.push(syn::Error::new_spanned(
+                duplicate,
+                format!("duplicate `#[{}]` attribute", marker),
+            ));
+        }
+
+        projected.push(PinField {
+            field,
+            member: match field.ident {
+                Some(ref ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(index.into()),
+            },
+            pinned: !markers.is_empty(),
+        });
+    }
+
+    errors.finish_with(PinProjection {
+        input,
+        fields: projected,
+        span_mode: SpanMode::CallSite,
+    })
+}
+
+// ---------------------------------------------------------------- const.assert
+
+/// Generate a compile-time assertion, `const _: () = assert!(predicate, message);`.
+///
+/// The predicate must be a const expression and cannot refer to the generic parameters
+/// of the deriving type, which free `const` items do not see.
+/// The message is printed verbatim, braces included.
+///
+/// # Examples
+///
+///```ignore
+/// let predicate = quote!(::core::mem::size_of::<#ty>() <= 64);
+/// emit_const_assert(&predicate, "`Header` must fit in a cache line")
+///
+/// ->
+/// const _: () = assert!(::core::mem::size_of::<Header>() <= 64, "`Header` must fit in a cache line");
+/// ```
+/// @since 0.4.0
+pub fn emit_const_assert(predicate: &TokenStream, message: &str) -> TokenStream {
+    let message = LitStr::new(&escape_format(message), Span::call_site());
+
+    quote! {
+        const _: () = ::core::assert!(#predicate, #message);
+    }
+}
+
+/// Generate a compile-time check that `ty` implements `bound`, failing with rustc's
+/// `the trait bound ... is not satisfied` error.
+///
+/// - `emit_assert_impl(&ty, &quote!(Send + Sync))`
+///
+/// @since 0.4.0
+pub fn emit_assert_impl<T: ToTokens>(ty: &T, bound: &TokenStream) -> TokenStream {
+    emit_assert_impl_with(ty, bound, SpanMode::CallSite)
+}
+
+/// Like [`emit_assert_impl`], spanned according to `mode`: with [`SpanMode::Origin`]
+/// the unsatisfied bound is reported on `ty`.
+///
+/// @since 0.4.0
+pub fn emit_assert_impl_with<T: ToTokens>(
+    ty: &T,
+    bound: &TokenStream,
+    mode: SpanMode,
+) -> TokenStream {
+    let span = mode.span_of(ty);
+
+    quote_spanned! {span=>
+        const _: fn() = || {
+            fn __synext_assert_impl<__T: ?::core::marker::Sized + #bound>() {}
+            __synext_assert_impl::<#ty>();
+        };
+    }
+}
+
+/// Generate a compile-time check that `size_of::<ty>()` is at most `max` bytes.
+///
+/// @since 0.4.0
+pub fn emit_assert_size_le<T: ToTokens>(ty: &T, max: usize) -> TokenStream {
+    let message = format!(
+        "`{}` must not be larger than {} bytes",
+        ty.to_token_stream(),
+        max
+    );
+    let max = Literal::usize_unsuffixed(max);
+
+    emit_const_assert(&quote!(::core::mem::size_of::<#ty>() <= #max), &message)
+}
+
+/// Generate a compile-time check that the type of every field implements `bound`, each spanned
+/// at its field's type, so a missing impl is reported on the user's field instead of inside
+/// generated code.
+///
+/// `generics` (with any bounds the macro adds, e.g. `T: Clone`) scope the field types.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Snapshot)]
+/// pub struct Config {
+///     name: String,
+///     handle: Rc<Handle>, // error[E0277]: `Rc<Handle>` cannot be sent between threads safely
+/// }
+///
+/// ->
+/// let checks = emit_field_bound_checks(&input.generics, try_collect_all_fields(&input), &quote!(Send));
+/// ```
+/// @since 0.4.0
+pub fn emit_field_bound_checks<'a, I>(
+    generics: &Generics,
+    fields: I,
+    bound: &TokenStream,
+) -> TokenStream
+where
+    I: IntoIterator<Item = &'a Field>,
+{
+    emit_field_bound_checks_with(generics, fields, bound, SpanMode::Origin)
+}
+
+/// Like [`emit_field_bound_checks`], with the check of every field spanned according to `mode`,
+/// [`SpanMode::CallSite`] reports a missing impl on the `#[derive(...)]` instead.
+///
+/// @since 0.4.0
+pub fn emit_field_bound_checks_with<'a, I>(
+    generics: &Generics,
+    fields: I,
+    bound: &TokenStream,
+    mode: SpanMode,
+) -> TokenStream
+where
+    I: IntoIterator<Item = &'a Field>,
+{
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let checks = fields.into_iter().map(|field| {
+        let ty = &field.ty;
+        let span = mode.span_of(ty);
+        quote_spanned! {span=>
+            __synext_assert_field_bound::<#ty>();
+        }
+    });
+
+    quote! {
+        const _: () = {
+            #[allow(dead_code, clippy::extra_unused_type_parameters)]
+            fn __synext_assert_field_bounds #impl_generics () #where_clause {
+                fn __synext_assert_field_bound<__T: ?::core::marker::Sized + #bound>() {}
+                #(#checks)*
+            }
+        };
+    }
+}
+
+// ---------------------------------------------------------------- literal.escape
+
+/// Try to escape `value` for embedding between the quotes of a generated string literal
+/// written as source text (`format!`, `syn::parse_str`), escaping `\`, `"` and control characters.
+///
+/// Values emitted through [`LitStr::new`] are escaped by `proc_macro2` and don't need this.
+///
+/// - `say "hi"` -> `say \"hi\"`
+/// - `C:\tmp` -> `C:\\tmp`
+///
+/// @since 0.4.0
+pub fn escape_str(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            ch if ch.is_control() => escaped.extend(ch.escape_unicode()),
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// Try to quote `value` as a string literal in source text, see [`escape_str`].
+///
+/// - `say "hi"` -> `"say \"hi\""`
+///
+/// @since 0.4.0
+pub fn quote_str(value: &str) -> String {
+    format!("\"{}\"", escape_str(value))
+}
+
+/// Try to escape `{` and `}` in `value`, so it is printed verbatim when used
+/// as (part of) a `format!` / `write!` / `panic!` format string.
+///
+/// - `{id}` -> `{{id}}`
+///
+/// @since 0.4.0
+pub fn escape_format(value: &str) -> String {
+    value.replace('{', "{{").replace('}', "}}")
+}
+
+/// Try to build a format string literal that prints `value` verbatim.
+///
+/// # Examples
+///
+///```ignore
+/// // #[error(message = "missing {field}")]
+/// let message = format_literal(&message, span);
+/// quote! { ::core::write!(f, #message) }
+/// ```
+/// @since 0.4.0
+pub fn format_literal(value: &str, span: Span) -> LitStr {
+    LitStr::new(&escape_format(value), span)
+}
+
+/// Try to re-emit `value` as doc attributes, one `#[doc = " line"]` per line,
+/// the same shape `rustdoc` produces for `///` comments.
+///
+/// # Examples
+///
+///```ignore
+/// let docs = doc_attrs(&description, ident.span());
+/// quote! {
+///     #docs
+///     pub fn #getter(&self) -> &#ty { &self.#ident }
+/// }
+/// ```
+/// @since 0.4.0
+pub fn doc_attrs(value: &str, span: Span) -> TokenStream {
+    let lines = value.lines().map(|line| {
+        let doc = if line.is_empty() {
+            LitStr::new("", span)
+        } else {
+            LitStr::new(&format!(" {}", line), span)
+        };
+        quote_spanned! {span=> #[doc = #doc] }
+    });
+
+    quote! { #(#lines)* }
+}
+
+// ---------------------------------------------------------------- impl.header
+
+/// Try to render the complete `impl<...> Trait for Ty<...> where ...` prefix of a derived impl,
+/// to be followed by the impl body.
+///
+/// - Parameter defaults are dropped from `impl<...>`.
+/// - Every type parameter used by a field type gets a `T: Trait` bound.
+/// - `extra_bounds` are appended, then the where clause is merged and deduplicated
+///   in declaration order, see [`crate::normalize_where_clause_with`].
+///
+/// # Examples
+///
+///```ignore
+/// // struct Wrapper<T, U: Clone = String> where U: Send { value: T, other: U }
+/// let header = render_impl_header(&parse_quote!(::core::fmt::Debug), &input, &[]);
+/// quote! {
+///     #header {
+///         fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result { ... }
+///     }
+/// }
+/// ->
+/// impl<T, U: Clone> ::core::fmt::Debug for Wrapper<T, U>
+/// where
+///     U: Send + ::core::fmt::Debug,
+///     T: ::core::fmt::Debug,
+/// ```
+/// @since 0.4.0
+pub fn render_impl_header(
+    trait_path: &Path,
+    input: &DeriveInput,
+    extra_bounds: &[WherePredicate],
+) -> TokenStream {
+    render_impl_header_with(trait_path, input, extra_bounds, SpanMode::CallSite)
+}
+
+/// Like [`render_impl_header`], spanned according to `mode`: with [`SpanMode::Origin`] the
+/// header is spanned at the type and every inferred `T: Trait` bound at its type parameter,
+/// so an unsatisfied bound points at the user's declaration.
+///
+/// @since 0.4.0
+pub fn render_impl_header_with(
+    trait_path: &Path,
+    input: &DeriveInput,
+    extra_bounds: &[WherePredicate],
+    mode: SpanMode,
+) -> TokenStream {
+    let mut generics = input.generics.clone();
+    let used = try_collect_field_type_params(input);
+    let inferred: Vec<WherePredicate> = used
+        .iter()
+        .map(|ident| {
+            let span = mode.span_of(*ident);
+            let predicate = quote_spanned!(span=> #ident: #trait_path);
+            syn::parse_quote!(#predicate)
+        })
+        .collect();
+
+    let where_clause = generics.make_where_clause();
+    where_clause.predicates.extend(inferred);
+    where_clause.predicates.extend(extra_bounds.iter().cloned());
+    normalize_where_clause_with(&mut generics, OutputOrder::Declaration);
+
+    let ident = &input.ident;
+    let span = mode.span_of(ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote_spanned! {span=>
+        impl #impl_generics #trait_path for #ident #ty_generics #where_clause
+    }
+}
+
+/// The type parameters of `input` that occur in a field type, in declaration order.
+fn try_collect_field_type_params(input: &DeriveInput) -> Vec<&Ident> {
+    let used: HashSet<Ident> = try_collect_all_fields(input)
+        .into_iter()
+        .flat_map(|field| collect_type_params_in_type(&field.ty, &input.generics))
+        .collect();
+
+    input
+        .generics
+        .type_params()
+        .map(|tp| &tp.ident)
+        .filter(|ident| used.contains(*ident))
+        .collect()
+}
@@ -23,21 +23,84 @@
 extern crate proc_macro;
 
 use std::fmt::Display;
+use std::ops::RangeBounds;
 
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream};
 use syn::__private::ToTokens;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
+use syn::visit::{self, Visit};
+use syn::visit_mut::{self, VisitMut};
 use syn::{
-    parse, Data, DataStruct, DeriveInput, Field, Fields, GenericArgument, Path, PathArguments, Type,
+    parse, Abi, BoundLifetimes, Data, DataStruct, DeriveInput, Expr, Field, Fields,
+    GenericArgument, Ident, Item, Lifetime, Macro, Path, PathArguments, PathSegment, ReturnType,
+    TraitBound, Type, TypeBareFn, TypeMacro, TypeParamBound,
 };
 
+use crate::diag::{emit_diagnostic, emit_spanned_diagnostic, ErrorAccumulator, Severity};
+use crate::error::{FieldsKind, SynextError};
+use crate::model::FieldModel;
+use crate::syntax::attr::parser::{
+    did_you_mean, mark_attr_consumed, try_extract_attribute_path_value,
+};
+use crate::syntax::types::registry::{ContainerKind, TypeRegistry};
+
 // ----------------------------------------------------------------
 
 pub const BUILTIN_TYPE_OPTION: &str = "Option";
 pub const BUILTIN_TYPE_VEC: &str = "Vec";
 
+/// @since 0.4.0
+pub const BUILTIN_TYPE_BOX: &str = "Box";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_RC: &str = "Rc";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_ARC: &str = "Arc";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_COW: &str = "Cow";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_STRING: &str = "String";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_RESULT: &str = "Result";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_PHANTOM_DATA: &str = "PhantomData";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_HASH_MAP: &str = "HashMap";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_BTREE_MAP: &str = "BTreeMap";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_HASH_SET: &str = "HashSet";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_BTREE_SET: &str = "BTreeSet";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_CELL: &str = "Cell";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_REF_CELL: &str = "RefCell";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_MUTEX: &str = "Mutex";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_RW_LOCK: &str = "RwLock";
+/// @since 0.4.0
+pub const BUILTIN_TYPE_PIN: &str = "Pin";
+/// The atomic types of [`std::sync::atomic`].
+///
+/// @since 0.4.0
+pub const BUILTIN_TYPE_ATOMICS: [&str; 12] = [
+    "AtomicBool",
+    "AtomicI8",
+    "AtomicI16",
+    "AtomicI32",
+    "AtomicI64",
+    "AtomicIsize",
+    "AtomicU8",
+    "AtomicU16",
+    "AtomicU32",
+    "AtomicU64",
+    "AtomicUsize",
+    "AtomicPtr",
+];
+
 // ----------------------------------------------------------------
 
 /// Try parse [`proc_macro::TokenStream`] to [`syn::DeriveInput`].
@@ -45,6 +108,94 @@ pub fn try_derive_input(input: proc_macro::TokenStream) -> DeriveInput {
     parse(input).unwrap()
 }
 
+/// Try parse [`proc_macro::TokenStream`] to [`syn::DeriveInput`], returning the parse error
+/// instead of panicking.
+///
+/// @since 0.4.0
+pub fn try_derive_input_result(input: proc_macro::TokenStream) -> syn::Result<DeriveInput> {
+    parse(input)
+}
+
+/// Try parse [`proc_macro::TokenStream`] to [`syn::DeriveInput`], turning the parse error
+/// into `compile_error!` tokens ready to be returned from the macro.
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_derive(Builder, attributes(builder))]
+/// pub fn builder(input: TokenStream) -> TokenStream {
+///     let input = match try_derive_input_or_compile_error(input) {
+///         Ok(input) => input,
+///         Err(error) => return error,
+///     };
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_derive_input_or_compile_error(
+    input: proc_macro::TokenStream,
+) -> Result<DeriveInput, proc_macro::TokenStream> {
+    try_derive_input_result(input).map_err(|error| error.to_compile_error().into())
+}
+
+// ---------------------------------------------------------------- input.summary
+
+/// A concise structural summary of [`syn::DeriveInput`] for error and panic messages,
+/// telling users which item a macro was applied to.
+///
+/// - `struct Hello<'a, T> { 3 named fields }`
+/// - `struct Meters(1 unnamed field)`
+/// - `struct Marker;`
+/// - `enum Shape { 2 variants }`
+///
+/// @since 0.4.0
+pub fn summarize(input: &DeriveInput) -> String {
+    let params = input
+        .generics
+        .params
+        .iter()
+        .map(|param| param.to_token_stream().to_string().replace(" :", ":"))
+        .collect::<Vec<_>>();
+    let generics = match params.is_empty() {
+        true => String::new(),
+        false => format!("<{}>", params.join(", ")),
+    };
+    let plural = |count: usize, noun: &str| match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    };
+
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => format!(
+                "struct {}{} {{ {} }}",
+                input.ident,
+                generics,
+                plural(fields.named.len(), "named field")
+            ),
+            Fields::Unnamed(fields) => format!(
+                "struct {}{}({})",
+                input.ident,
+                generics,
+                plural(fields.unnamed.len(), "unnamed field")
+            ),
+            Fields::Unit => format!("struct {}{};", input.ident, generics),
+        },
+        Data::Enum(data) => format!(
+            "enum {}{} {{ {} }}",
+            input.ident,
+            generics,
+            plural(data.variants.len(), "variant")
+        ),
+        Data::Union(data) => format!(
+            "union {}{} {{ {} }}",
+            input.ident,
+            generics,
+            plural(data.fields.named.len(), "field")
+        ),
+    }
+}
+
 // ----------------------------------------------------------------
 
 /// Try parse [`syn::DeriveInput`] named fields [`Punctuated<Field, Comma>`].
@@ -57,18 +208,36 @@ pub fn try_parse_named_fields(input: &DeriveInput) -> &Punctuated<Field, Comma>
         Data::Struct(data) => match &data.fields {
             Fields::Named(fields) => &fields.named,
             _ => panic!(
-                "synext: Does not contain named fields! target:`{}`",
-                struct_name
+                "synext: Does not contain named fields! target:`{}` input:`{}`",
+                struct_name,
+                summarize(input)
             ),
         },
         _ => panic!(
-            "synext: Only structs are supported! target:`{}`",
-            struct_name
+            "synext: Only structs are supported! target:`{}` input:`{}`",
+            struct_name,
+            summarize(input)
         ),
     }
     // @formatter:on
 }
 
+/// Try parse [`syn::DeriveInput`] named fields [`Punctuated<Field, Comma>`],
+/// returning a spanned error instead of panicking when the shape doesn't match.
+///
+/// @since 0.4.0
+pub fn try_parse_named_fields_result(
+    input: &DeriveInput,
+) -> syn::Result<&Punctuated<Field, Comma>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(SynextError::missing_fields(input, FieldsKind::Named).into()),
+        },
+        _ => Err(only_structs_error(input)),
+    }
+}
+
 // ----------------------------------------------------------------
 
 /// Try parse [`syn::DeriveInput`] unnamed fields [`Punctuated<Field, Comma>`].
@@ -81,18 +250,36 @@ pub fn try_parse_unnamed_fields(input: &DeriveInput) -> &Punctuated<Field, Comma
         Data::Struct(data) => match &data.fields {
             Fields::Unnamed(fields) => &fields.unnamed,
             _ => panic!(
-                "synext: Does not contain unnamed fields! target:`{}`",
-                struct_name
+                "synext: Does not contain unnamed fields! target:`{}` input:`{}`",
+                struct_name,
+                summarize(input)
             ),
         },
         // @formatter:on
         _ => panic!(
-            "synext: Only structs are supported! target:`{}`",
-            struct_name
+            "synext: Only structs are supported! target:`{}` input:`{}`",
+            struct_name,
+            summarize(input)
         ),
     }
 }
 
+/// Try parse [`syn::DeriveInput`] unnamed fields [`Punctuated<Field, Comma>`],
+/// returning a spanned error instead of panicking when the shape doesn't match.
+///
+/// @since 0.4.0
+pub fn try_parse_unnamed_fields_result(
+    input: &DeriveInput,
+) -> syn::Result<&Punctuated<Field, Comma>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) => Ok(&fields.unnamed),
+            _ => Err(SynextError::missing_fields(input, FieldsKind::Unnamed).into()),
+        },
+        _ => Err(only_structs_error(input)),
+    }
+}
+
 // ----------------------------------------------------------------
 
 /// Try parse [`syn::DeriveInput`] matches fields [`Punctuated<Field, Comma>`].
@@ -111,13 +298,46 @@ pub fn try_match_fields(input: &DeriveInput) -> &Punctuated<Field, Comma> {
             ..
         }) => &fields.unnamed,
         _ => panic!(
-            "synext: Does not contain any fields! target:`{}`",
-            struct_name
+            "synext: Does not contain any fields! target:`{}` input:`{}`",
+            struct_name,
+            summarize(input)
         ),
     }
     // @formatter:on
 }
 
+/// Try parse [`syn::DeriveInput`] matches fields [`Punctuated<Field, Comma>`],
+/// returning a spanned error instead of panicking on unit structs, enums and unions.
+///
+/// @since 0.4.0
+pub fn try_match_fields_result(input: &DeriveInput) -> syn::Result<&Punctuated<Field, Comma>> {
+    match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => Ok(&fields.named),
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) => Ok(&fields.unnamed),
+        _ => Err(SynextError::missing_fields(input, FieldsKind::Any).into()),
+    }
+}
+
+fn only_structs_error(input: &DeriveInput) -> syn::Error {
+    match SynextError::unsupported_data(input) {
+        Some(error) => error.into(),
+        None => SynextError::custom(
+            input.ident.span(),
+            format!(
+                "synext: Only structs are supported! target:`{}`",
+                input.ident
+            ),
+        )
+        .into(),
+    }
+}
+
 // ----------------------------------------------------------------
 
 /// Try unwrap `syn::Type` [`core::option::Option<T>`] inner types.
@@ -143,7 +363,7 @@ pub fn try_unwrap_types<'a>(
             ..
         }) = ty {
         // @formatter:on
-        if try_predicate_is_ident(&ident, &path) && try_predicate_path_segments_is_not_empty(path) {
+        if try_predicate_is_ident(ident, path) && try_predicate_path_segments_is_not_empty(path) {
             let inner_type = try_extract_inner_types(ty);
             let mut len = 0;
             if let Some(ref inner) = inner_type {
@@ -157,7 +377,7 @@ pub fn try_unwrap_types<'a>(
             }
         }
 
-        if try_predicate_is_not_ident(&ident, &path) {
+        if try_predicate_is_not_ident(ident, path) {
             let res_ident = path.get_ident();
             if let Some(res_ident) = res_ident {
                 panic!("synext: Expected Type `{:?}`, got `{:?}`", ident, res_ident);
@@ -206,6 +426,53 @@ pub fn try_extract_inner_types(ty: &Type) -> Option<Vec<&Type>> {
     None
 }
 
+/// Try unwrap the type arguments of the wrapper `ident`, whatever their number,
+/// without panicking, e.g. of a user-defined generic wrapper.
+///
+/// Like [`try_unwrap_single_type`], the wrapper may be written with a qualified path and a
+/// mismatch yields `None`. A bare `Wrapper` has no type argument, lifetimes and consts are skipped.
+///
+/// - `"Pair"`, Pair\<A, B\> -> `[A, B]`
+/// - `"Wrapper"`, my::Wrapper\<'a, T, 8\> -> `[T]`
+/// - `"Marker"`, Marker -> `[]`
+///
+/// @since 0.4.0
+pub fn try_unwrap_types_any<'a>(ident: &str, ty: &'a Type) -> Option<Vec<&'a Type>> {
+    let segment = match ty {
+        Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != ident {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(bracketed) => Some(
+            bracketed
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        PathArguments::None => Some(Vec::new()),
+        PathArguments::Parenthesized(_) => None,
+    }
+}
+
+/// Like [`try_unwrap_types_any`], when the number of type arguments is within `arity`,
+/// e.g. `1..=2` for `HashSet<T>` / `HashSet<T, S>`.
+///
+/// @since 0.4.0
+pub fn try_unwrap_types_in<'a, R>(ident: &str, arity: R, ty: &'a Type) -> Option<Vec<&'a Type>>
+where
+    R: RangeBounds<usize>,
+{
+    try_unwrap_types_any(ident, ty).filter(|types| arity.contains(&types.len()))
+}
+
 // ----------------------------------------------------------------
 
 /// Try to extract the specified path attribute value from a field's attributes.
@@ -214,17 +481,17 @@ pub fn try_extract_inner_types(ty: &Type) -> Option<Vec<&Type>> {
 ///
 /// * `derive_attribute` - The identifier of the derive attribute that needs to be found.
 /// * `path_attribute`   - The identifier of the key-value pair attribute within the derive
-///                        attribute that needs to be extracted.
+///   attribute that needs to be extracted.
 /// * `field`            - A reference to the `Field` struct which contains the attributes
-///                        to be searched.
+///   to be searched.
 ///
 /// # Returns
 ///
 /// * `Ok(Some(syn::Ident))` - If the specified path attribute is found, returns the identifier
-///                            wrapped in `Some`.
+///   wrapped in `Some`.
 /// * `Ok(None)`             - If the specified path attribute is not found.
 /// * `Err(syn::Error)`      - If an error occurs during parsing or the expected attribute format
-///                            is not met.
+///   is not met.
 ///
 /// # Example:
 ///
@@ -273,11 +540,12 @@ pub fn try_extract_field_attribute_path_attribute(derive_attribute: &str, path_a
                             }
                         } else {
                             if let Ok(syn::Meta::List(ref list)) = attr.parse_meta() {
+                                let got = kv.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default();
                                 return Err(syn::Error::new_spanned(
                                     list,
                                     format!(
-                                        r#"expected `{}({} = "...")`"#,
-                                        derive_attribute, path_attribute
+                                        r#"expected `{}({} = "...")`, unknown key `{}`{}"#,
+                                        derive_attribute, path_attribute, got, did_you_mean(&[path_attribute], &got)
                                     ),
                                 ));
                             }
@@ -290,86 +558,1777 @@ pub fn try_extract_field_attribute_path_attribute(derive_attribute: &str, path_a
     Ok(None)
 }
 
+/// Like [`try_extract_field_attribute_path_attribute`], but the identifier is returned as
+/// [`crate::Spanned`] carrying the span of the string literal rather than of the whole attribute.
+///
+/// Every nested key is inspected, other keys are left to the caller instead of being rejected,
+/// and a value that is not a valid identifier is reported on the literal instead of panicking.
+///
+/// @since 0.4.0
+pub fn try_extract_field_attribute_path_attribute_spanned(
+    derive_attribute: &str,
+    path_attribute: &str,
+    field: &Field,
+) -> syn::Result<Option<crate::Spanned<syn::Ident>>> {
+    let value = try_extract_attribute_path_value(&field.attrs, derive_attribute, path_attribute)?;
+    match value {
+        Some(lit) => {
+            let ident = lit.parse::<syn::Ident>()?;
+            Ok(Some(crate::Spanned::new(ident, lit.span())))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Ensure the `#[derive_attribute(...)]` attributes of a field only use `allowed` keys,
+/// reporting every unknown key on its path with the allowed keys and the closest match.
+///
+/// # Examples
+///
+///```ignore
+/// #[derive(Builder)]
+/// pub struct Hello {
+///     #[builder(each = "activity", defualt)]
+///     activities: Vec<String>,
+/// }
+///
+/// ->
+/// deny_unknown_keys("builder", &["each", "default", "skip"], field)?;
+/// // unknown `builder` key `defualt`, expected one of `each`, `default`, `skip`, did you mean `default`?
+/// ```
+/// @since 0.4.0
+pub fn deny_unknown_keys(
+    derive_attribute: &str,
+    allowed: &[&str],
+    field: &Field,
+) -> syn::Result<()> {
+    let mut errors = ErrorAccumulator::new();
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(derive_attribute))
+    {
+        mark_attr_consumed(attr);
+        let nested = match attr.parse_meta()? {
+            syn::Meta::List(list) => list.nested,
+            _ => continue,
+        };
+
+        for nested in nested {
+            let path = match nested {
+                syn::NestedMeta::Meta(ref meta) => meta.path(),
+                syn::NestedMeta::Lit(ref lit) => {
+                    errors.push(syn::Error::new_spanned(
+                        lit,
+                        format!("expected a `{}` key, got a literal", derive_attribute),
+                    ));
+                    continue;
+                }
+            };
+            if let Some(error) = try_check_known_key(derive_attribute, allowed, path) {
+                errors.push(error);
+            }
+        }
+    }
+
+    errors.finish()
+}
+
+/// Like [`try_extract_field_attribute_path_attribute_spanned`], reading the values already parsed
+/// into a [`FieldModel`] instead of parsing the field's attributes again.
+///
+/// # Examples
+///
+///```ignore
+/// let model = StructModel::parse(&input, &["builder"])?;
+/// for field in &model.fields {
+///     deny_unknown_field_model_keys("builder", &["each", "default"], field)?;
+///     let each = try_extract_field_model_path_attribute("builder", "each", field)?;
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_extract_field_model_path_attribute(
+    derive_attribute: &str,
+    path_attribute: &str,
+    field: &FieldModel<'_>,
+) -> syn::Result<Option<crate::Spanned<syn::Ident>>> {
+    match field.helpers.lit(derive_attribute, path_attribute) {
+        Some(syn::Lit::Str(lit)) => {
+            let ident = lit.parse::<syn::Ident>()?;
+            Ok(Some(crate::Spanned::new(ident, lit.span())))
+        }
+        Some(other) => Err(syn::Error::new_spanned(
+            other,
+            format!(
+                r#"expected `{}({} = "...")`"#,
+                derive_attribute, path_attribute
+            ),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Like [`deny_unknown_keys`], over the values already parsed into a [`FieldModel`].
+///
+/// @since 0.4.0
+pub fn deny_unknown_field_model_keys(
+    derive_attribute: &str,
+    allowed: &[&str],
+    field: &FieldModel<'_>,
+) -> syn::Result<()> {
+    let mut errors = ErrorAccumulator::new();
+    if let Some(values) = field.helpers.get(derive_attribute) {
+        errors.extend(
+            values
+                .iter()
+                .filter_map(|value| try_check_known_key(derive_attribute, allowed, &value.key)),
+        );
+    }
+
+    errors.finish()
+}
+
+fn try_check_known_key(
+    derive_attribute: &str,
+    allowed: &[&str],
+    path: &Path,
+) -> Option<syn::Error> {
+    let key = path
+        .get_ident()
+        .map(|ident| ident.to_string())
+        .unwrap_or_else(|| path.to_token_stream().to_string().replace(' ', ""));
+    if allowed.contains(&key.as_str()) {
+        return None;
+    }
+
+    Some(syn::Error::new_spanned(
+        path,
+        format!(
+            "unknown `{}` key `{}`, expected one of {}{}",
+            derive_attribute,
+            key,
+            allowed
+                .iter()
+                .map(|key| format!("`{}`", key))
+                .collect::<Vec<_>>()
+                .join(", "),
+            did_you_mean(allowed, &key)
+        ),
+    ))
+}
+
 // ----------------------------------------------------------------
 
+/// Make a `compile_error!` on `span`.
+///
+/// With the `diagnostics` feature on nightly, the error goes through `proc_macro::Diagnostic`
+/// instead, see [`crate::diag::emit_diagnostic`].
 pub fn make_new_compile_error<T: Display>(span: Span, message: T) -> proc_macro::TokenStream {
-    syn::Error::new(span, message).to_compile_error().into()
+    emit_diagnostic(span, Severity::Error, message)
 }
 
+/// Make a `compile_error!` spanned at `tokens`.
+///
+/// With the `diagnostics` feature on nightly, the error goes through `proc_macro::Diagnostic`
+/// instead, see [`crate::diag::emit_spanned_diagnostic`].
 pub fn make_new_spanned_compile_error<T: ToTokens, U: Display>(
     tokens: T,
     message: U,
 ) -> proc_macro::TokenStream {
-    syn::Error::new_spanned(tokens, message)
-        .to_compile_error()
-        .into()
+    emit_spanned_diagnostic(tokens, Severity::Error, message)
 }
 
-// ---------------------------------------------------------------- boolean.function
+// ---------------------------------------------------------------- interior.mutability
 
-/// Try to predicate that [`syn::Type`] is neither of type [`core::option::Option<T>`] nor of type [`std::vec::Vec<T>`]
+/// The interior-mutability wrapper of a [`syn::Type`] together with the protected type.
 ///
-/// @since 0.2.0
-pub fn try_predicate_is_not_option_and_vec(ty: &Type) -> bool {
-    try_predicate_is_not_option(ty) && try_predicate_is_not_vec(ty)
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteriorMutable<'a> {
+    Cell(&'a Type),
+    RefCell(&'a Type),
+    Mutex(&'a Type),
+    RwLock(&'a Type),
+    /// `AtomicX` types, carrying the atomic ident (`AtomicU32`, `AtomicPtr`, ...).
+    Atomic(&'a Ident),
 }
 
-/// Try to predicate that [`syn::Type`] is not [`core::option::Option<T>`] type.
+impl<'a> InteriorMutable<'a> {
+    /// The protected type, `None` for atomics which protect a primitive.
+    pub fn inner(&self) -> Option<&'a Type> {
+        match *self {
+            InteriorMutable::Cell(ty)
+            | InteriorMutable::RefCell(ty)
+            | InteriorMutable::Mutex(ty)
+            | InteriorMutable::RwLock(ty) => Some(ty),
+            InteriorMutable::Atomic(_) => None,
+        }
+    }
+
+    /// The primitive protected by an atomic (`AtomicU32` -> `u32`), `None` otherwise.
+    pub fn atomic_primitive(&self) -> Option<&'static str> {
+        let ident = match *self {
+            InteriorMutable::Atomic(ident) => ident.to_string(),
+            _ => return None,
+        };
+
+        match ident.as_str() {
+            "AtomicBool" => Some("bool"),
+            "AtomicI8" => Some("i8"),
+            "AtomicI16" => Some("i16"),
+            "AtomicI32" => Some("i32"),
+            "AtomicI64" => Some("i64"),
+            "AtomicIsize" => Some("isize"),
+            "AtomicU8" => Some("u8"),
+            "AtomicU16" => Some("u16"),
+            "AtomicU32" => Some("u32"),
+            "AtomicU64" => Some("u64"),
+            "AtomicUsize" => Some("usize"),
+            _ => None,
+        }
+    }
+
+    /// Whether reads go through a lock (`Mutex`/`RwLock`) rather than a borrow or a copy.
+    pub fn is_lock(&self) -> bool {
+        matches!(self, InteriorMutable::Mutex(_) | InteriorMutable::RwLock(_))
+    }
+}
+
+/// Try to extract the interior-mutability wrapper of [`syn::Type`].
 ///
-/// @since 0.2.0
-pub fn try_predicate_is_not_option(ty: &Type) -> bool {
-    !try_predicate_is_option(ty)
+/// - Cell\<T\>, RefCell\<T\>, Mutex\<T\>, RwLock\<T\> -> the protected `T`
+/// - AtomicU32, AtomicPtr\<T\>, ... -> [`InteriorMutable::Atomic`], only the std atomics
+///   listed in [`BUILTIN_TYPE_ATOMICS`], not `AtomicCell<T>` or other `Atomic*` types
+/// - std::sync::Mutex\<T\> and other qualified paths are matched by their last segment
+///
+/// @since 0.4.0
+pub fn try_extract_interior_mutable(ty: &Type) -> Option<InteriorMutable<'_>> {
+    let segment = match ty {
+        Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last()?,
+        _ => return None,
+    };
+
+    let ident = segment.ident.to_string();
+    if BUILTIN_TYPE_ATOMICS.contains(&ident.as_str()) {
+        return Some(InteriorMutable::Atomic(&segment.ident));
+    }
+
+    let inner = try_unwrap_single_type(&ident, ty)?;
+    match ident.as_str() {
+        BUILTIN_TYPE_CELL => Some(InteriorMutable::Cell(inner)),
+        BUILTIN_TYPE_REF_CELL => Some(InteriorMutable::RefCell(inner)),
+        BUILTIN_TYPE_MUTEX => Some(InteriorMutable::Mutex(inner)),
+        BUILTIN_TYPE_RW_LOCK => Some(InteriorMutable::RwLock(inner)),
+        _ => None,
+    }
 }
 
-/// Try to predicate that [`syn::Type`] is not [`std::vec::Vec<T>`] type.
+/// Try unwrap the protected type of an interior-mutability wrapper, see [`try_extract_interior_mutable`].
 ///
-/// @since 0.2.0
-pub fn try_predicate_is_not_vec(ty: &Type) -> bool {
-    !try_predicate_is_vec(ty)
+/// @since 0.4.0
+pub fn try_unwrap_interior_mutable(ty: &Type) -> Option<&Type> {
+    try_extract_interior_mutable(ty).and_then(|im| im.inner())
 }
 
-/// Try to predicate that [`syn::Type`] is [`core::option::Option<T>`] type.
+/// Try to predicate that [`syn::Type`] is an interior-mutability wrapper
+/// (`Cell`, `RefCell`, `Mutex`, `RwLock` or `AtomicX`).
 ///
-/// @since 0.2.0
-pub fn try_predicate_is_option(ty: &Type) -> bool {
-    try_predicate_is_type(BUILTIN_TYPE_OPTION, 1, ty)
+/// @since 0.4.0
+pub fn try_predicate_is_interior_mutable(ty: &Type) -> bool {
+    try_extract_interior_mutable(ty).is_some()
 }
 
-/// Try to predicate that [`syn::Type`] is [`std::vec::Vec<T>`] type.
+/// Try unwrap the value type of [`std::cell::Cell<T>`], bare or qualified.
 ///
-/// @since 0.2.0
-pub fn try_predicate_is_vec(ty: &Type) -> bool {
-    try_predicate_is_type(BUILTIN_TYPE_VEC, 1, ty)
+/// @since 0.4.0
+pub fn try_unwrap_cell(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_CELL, ty)
 }
 
-#[rustfmt::skip]
-pub fn try_predicate_is_type(ident: &str, target_types: usize, ty: &Type) -> bool {
-    // @formatter:off
-    if let Type::Path(
-        syn::TypePath {
-            ref path,
-            ..
-        }) = ty {
-        // @formatter:on
-        if try_predicate_is_ident(&ident, &path) && path.segments.len() == target_types {
-            return true;
-        }
+/// Try to predicate that [`syn::Type`] is [`std::cell::Cell<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_cell(ty: &Type) -> bool {
+    try_unwrap_cell(ty).is_some()
+}
+
+/// Try unwrap the borrowed type of [`std::cell::RefCell<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_unwrap_ref_cell(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_REF_CELL, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::cell::RefCell<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_ref_cell(ty: &Type) -> bool {
+    try_unwrap_ref_cell(ty).is_some()
+}
+
+/// Try unwrap the guarded type of [`std::sync::Mutex<T>`], bare or qualified.
+///
+/// - Mutex\<State\>, std::sync::Mutex\<State\>, tokio::sync::Mutex\<State\> -> `State`
+///
+/// @since 0.4.0
+pub fn try_unwrap_mutex(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_MUTEX, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::sync::Mutex<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_mutex(ty: &Type) -> bool {
+    try_unwrap_mutex(ty).is_some()
+}
+
+/// Try unwrap the guarded type of [`std::sync::RwLock<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_unwrap_rw_lock(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_RW_LOCK, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::sync::RwLock<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_rw_lock(ty: &Type) -> bool {
+    try_unwrap_rw_lock(ty).is_some()
+}
+
+/// Try unwrap the single type argument of the wrapper `ident` without panicking.
+///
+/// Unlike [`try_unwrap_types`], the wrapper may be written with a qualified path
+/// (`std::sync::Mutex<T>`) and a mismatch yields `None`. Matched by [`try_unwrap_types_any`],
+/// like the types of a [`TypeRegistry`].
+///
+/// @since 0.4.0
+pub fn try_unwrap_single_type<'a>(ident: &str, ty: &'a Type) -> Option<&'a Type> {
+    try_unwrap_types_in(ident, 1..=1, ty).map(|types| types[0])
+}
+
+// ---------------------------------------------------------------- unwrap.chain
+
+/// Try unwrap a known sequence of single-argument wrappers in one call, outermost first,
+/// returning the innermost type.
+///
+/// Steps are separated by `>` and matched on the last path segment like [`try_unwrap_single_type`],
+/// the error is spanned at the type where the chain broke.
+///
+/// - `"Option>Vec>Box"`, Option\<Vec\<Box\<Node\>\>\> -> `Node`
+/// - `"Option>Vec"`, Option\<HashSet\<u8\>\> -> `` synext: Expected `Vec<_>` at step 2 of `Option>Vec`, got `HashSet < u8 >` ``
+///
+/// # Examples
+///
+///```ignore
+/// // instead of try_unwrap_box(try_unwrap_vec(try_unwrap_option(ty)))
+/// let node = try_unwrap_chain("Option>Vec>Box", &field.ty)?;
+/// ```
+/// @since 0.4.0
+pub fn try_unwrap_chain<'a>(chain: &str, ty: &'a Type) -> syn::Result<&'a Type> {
+    let mut current = ty;
+    for (index, step) in chain.split('>').map(str::trim).enumerate() {
+        current = match try_unwrap_single_type(step, current) {
+            Some(inner) => inner,
+            None => {
+                return Err(syn::Error::new_spanned(
+                    current,
+                    format!(
+                        "synext: Expected `{}<_>` at step {} of `{}`, got `{}`",
+                        step,
+                        index + 1,
+                        chain,
+                        current.to_token_stream()
+                    ),
+                ))
+            }
+        };
     }
-    false
+
+    Ok(current)
 }
 
-pub fn try_predicate_is_not_ident(ident: &str, path: &Path) -> bool {
-    !try_predicate_is_ident(ident, path)
+// ---------------------------------------------------------------- smart.pointer
+
+/// Try unwrap the boxed type of [`Box<T>`], e.g. of a recursive field.
+///
+/// - Box\<Node\> -> `Node`
+/// - std::boxed::Box\<Node\> -> `Node`
+/// - Vec\<Node\> -> `None`
+///
+/// @since 0.4.0
+pub fn try_unwrap_box(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_BOX, ty)
 }
 
-pub fn try_predicate_is_ident(ident: &str, path: &Path) -> bool {
-    try_predicate_path_segments_is_not_empty(path) && path.segments.last().unwrap().ident == ident
+/// Try to predicate that [`syn::Type`] is [`Box<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_box(ty: &Type) -> bool {
+    try_unwrap_box(ty).is_some()
 }
 
-pub fn try_predicate_path_segments_is_not_empty(path: &Path) -> bool {
-    !try_predicate_path_segments_is_empty(path)
+/// Try unwrap the shared type of [`std::rc::Rc<T>`].
+///
+/// - Rc\<Service\>, std::rc::Rc\<Service\> -> `Service`
+///
+/// @since 0.4.0
+pub fn try_unwrap_rc(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_RC, ty)
 }
 
-pub fn try_predicate_path_segments_is_empty(path: &Path) -> bool {
-    path.segments.is_empty()
+/// Try to predicate that [`syn::Type`] is [`std::rc::Rc<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_rc(ty: &Type) -> bool {
+    try_unwrap_rc(ty).is_some()
+}
+
+/// Try unwrap the shared type of [`std::sync::Arc<T>`], e.g. of a service injected by a DI-style derive.
+///
+/// - Arc\<dyn Repository\>, std::sync::Arc\<dyn Repository\> -> `dyn Repository`
+///
+/// @since 0.4.0
+pub fn try_unwrap_arc(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_ARC, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::sync::Arc<T>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_arc(ty: &Type) -> bool {
+    try_unwrap_arc(ty).is_some()
+}
+
+// ---------------------------------------------------------------- pin
+
+/// Try unwrap the pointer type of [`std::pin::Pin<P>`], bare or qualified.
+///
+/// - Pin\<Box\<dyn Future\<Output = ()\>\>\> -> `Box<dyn Future<Output = ()>>`
+/// - Pin\<&mut T\> -> `&mut T`
+///
+/// @since 0.4.0
+pub fn try_unwrap_pin(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_PIN, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::pin::Pin<P>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_pin(ty: &Type) -> bool {
+    try_unwrap_pin(ty).is_some()
+}
+
+/// Try unwrap the payload of a stack of pointer, pinning and interior-mutability wrappers,
+/// `Box`, `Rc`, `Arc`, `Pin`, `Cell`, `RefCell`, `Mutex` and `RwLock`, outermost first.
+///
+/// A type without wrappers is its own payload.
+///
+/// - Arc\<Mutex\<State\>\> -> `State`
+/// - Pin\<Box\<T\>\> -> `T`
+/// - Rc\<RefCell\<Vec\<Node\>\>\> -> `Vec<Node>`
+/// - Arc\<AtomicU32\> -> `AtomicU32`
+///
+/// @since 0.4.0
+pub fn try_unwrap_payload(ty: &Type) -> &Type {
+    let mut payload = ty;
+    while let Some(inner) = try_unwrap_box(payload)
+        .or_else(|| try_unwrap_rc(payload))
+        .or_else(|| try_unwrap_arc(payload))
+        .or_else(|| try_unwrap_pin(payload))
+        .or_else(|| try_unwrap_interior_mutable(payload))
+    {
+        payload = inner;
+    }
+
+    payload
+}
+
+// ---------------------------------------------------------------- phantom.data
+
+/// Try unwrap the marker type of [`core::marker::PhantomData<T>`], bare or qualified.
+///
+/// - PhantomData\<T\> -> `T`
+/// - std::marker::PhantomData\<fn() -> T\> -> `fn() -> T`
+///
+/// @since 0.4.0
+pub fn try_unwrap_phantom_data(ty: &Type) -> Option<&Type> {
+    try_unwrap_single_type(BUILTIN_TYPE_PHANTOM_DATA, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`core::marker::PhantomData<T>`], bare or qualified,
+/// e.g. to skip it in generated constructors, builders and `Debug` impls.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_phantom_data(ty: &Type) -> bool {
+    try_unwrap_phantom_data(ty).is_some()
+}
+
+// ---------------------------------------------------------------- self.reference
+
+/// The heap or pointer containers that break a recursive type, `Box<Node>` and `Vec<Node>` are sized.
+const INDIRECTIONS: &[&str] = &[
+    BUILTIN_TYPE_BOX,
+    BUILTIN_TYPE_RC,
+    BUILTIN_TYPE_ARC,
+    "Weak",
+    BUILTIN_TYPE_VEC,
+    "VecDeque",
+    "LinkedList",
+    "BinaryHeap",
+    BUILTIN_TYPE_HASH_MAP,
+    BUILTIN_TYPE_BTREE_MAP,
+    BUILTIN_TYPE_HASH_SET,
+    BUILTIN_TYPE_BTREE_SET,
+    BUILTIN_TYPE_PHANTOM_DATA,
+];
+
+/// Try to predicate that a field type embeds the struct `struct_ident` itself, by name or as `Self`,
+/// outside of an indirection, e.g. to report "wrap this field in `Box`" instead of rustc's
+/// infinite size error.
+///
+/// Indirections are `Box`, `Rc`, `Arc`, `Weak`, the std collections, `PhantomData`, references,
+/// raw and `fn` pointers and trait objects. Use [`TypeRegistry::try_predicate_references_self`]
+/// to treat registered containers as indirections too.
+///
+/// - Option\<Node\>, `Node` -> `true`
+/// - (u8, [Self; 2]), `Node` -> `true`
+/// - Option\<Box\<Node\>\>, `Node` -> `false`
+/// - Vec\<Node\>, `Node` -> `false`
+/// - &'a Node, `Node` -> `false`
+///
+/// # Examples
+///
+///```ignore
+/// for field in try_collect_all_fields(&input) {
+///     if try_predicate_references_self(&field.ty, &input.ident) {
+///         return Err(syn::Error::new_spanned(&field.ty, "synext: Expected an indirection, wrap this field in `Box`"));
+///     }
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_predicate_references_self(field_ty: &Type, struct_ident: &Ident) -> bool {
+    try_predicate_references_self_in(&TypeRegistry::new(), field_ty, struct_ident)
+}
+
+pub(crate) fn try_predicate_references_self_in(
+    registry: &TypeRegistry,
+    field_ty: &Type,
+    struct_ident: &Ident,
+) -> bool {
+    match field_ty {
+        Type::Group(group) => try_predicate_references_self_in(registry, &group.elem, struct_ident),
+        Type::Paren(paren) => try_predicate_references_self_in(registry, &paren.elem, struct_ident),
+        Type::Array(array) => try_predicate_references_self_in(registry, &array.elem, struct_ident),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .any(|elem| try_predicate_references_self_in(registry, elem, struct_ident)),
+        Type::Path(tp) if tp.qself.is_none() => {
+            let segment = match tp.path.segments.last() {
+                Some(segment) => segment,
+                None => return false,
+            };
+            let is_self = tp.path.segments.len() == 1 && segment.ident == "Self";
+            if is_self || segment.ident == *struct_ident {
+                return true;
+            }
+            if INDIRECTIONS.iter().any(|ident| segment.ident == ident) {
+                return false;
+            }
+            let indirect = matches!(
+                registry.kind_of(field_ty),
+                Some(
+                    ContainerKind::Pointer
+                        | ContainerKind::Vec
+                        | ContainerKind::Set
+                        | ContainerKind::Map
+                )
+            );
+            if indirect {
+                return false;
+            }
+
+            match &segment.arguments {
+                PathArguments::AngleBracketed(bracketed) => {
+                    bracketed.args.iter().any(|arg| match arg {
+                        GenericArgument::Type(ty) => {
+                            try_predicate_references_self_in(registry, ty, struct_ident)
+                        }
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------- lifetime
+
+/// Try to collect the distinct lifetimes of [`syn::Type`], in order of first occurrence,
+/// e.g. of an unwrapped inner type, to decide whether a generated signature borrows.
+///
+/// - Option\<&'a str\> -> `['a]`
+/// - Vec\<Cow\<'a, str\>\> -> `['a]`
+/// - &'a HashMap\<&'b str, &'static str\> -> `['a, 'b, 'static]`
+/// - String -> `[]`
+///
+/// @since 0.4.0
+pub fn try_collect_lifetimes(ty: &Type) -> Vec<&Lifetime> {
+    struct LifetimeVisitor<'ast> {
+        lifetimes: Vec<&'ast Lifetime>,
+    }
+
+    impl<'ast> Visit<'ast> for LifetimeVisitor<'ast> {
+        fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+            if !self.lifetimes.iter().any(|lt| lt.ident == lifetime.ident) {
+                self.lifetimes.push(lifetime);
+            }
+        }
+    }
+
+    let mut visitor = LifetimeVisitor {
+        lifetimes: Vec::new(),
+    };
+    visitor.visit_type(ty);
+
+    visitor.lifetimes
+}
+
+/// Try to predicate that [`syn::Type`] mentions a lifetime, `'static` and `'_` included.
+///
+/// @since 0.4.0
+pub fn try_predicate_contains_lifetime(ty: &Type) -> bool {
+    !try_collect_lifetimes(ty).is_empty()
+}
+
+/// Try to replace every named lifetime of [`syn::Type`] with `'_`, keeping `'static` and
+/// the lifetimes bound by a `for<'a>`, so the type can be written in an elided signature.
+///
+/// - &'a str -> `&'_ str`
+/// - Vec\<Cow\<'a, str\>\> -> `Vec<Cow<'_, str>>`
+/// - &'static str -> `&'static str`
+/// - Box\<dyn for\<'b\> Fn(&'b T) -> &'a T\> -> `Box<dyn for<'b> Fn(&'b T) -> &'_ T>`
+///
+/// @since 0.4.0
+pub fn try_elide_lifetimes(ty: &Type) -> Type {
+    struct LifetimeElider {
+        bound: Vec<Ident>,
+    }
+
+    impl LifetimeElider {
+        /// Bind the `for<'a>` lifetimes, returning the depth to truncate back to.
+        fn bind(&mut self, lifetimes: &Option<BoundLifetimes>) -> usize {
+            let depth = self.bound.len();
+            if let Some(lifetimes) = lifetimes {
+                self.bound.extend(
+                    lifetimes
+                        .lifetimes
+                        .iter()
+                        .map(|def| def.lifetime.ident.clone()),
+                );
+            }
+
+            depth
+        }
+    }
+
+    impl VisitMut for LifetimeElider {
+        fn visit_bound_lifetimes_mut(&mut self, _lifetimes: &mut BoundLifetimes) {}
+
+        fn visit_type_bare_fn_mut(&mut self, bare_fn: &mut TypeBareFn) {
+            let depth = self.bind(&bare_fn.lifetimes);
+            visit_mut::visit_type_bare_fn_mut(self, bare_fn);
+            self.bound.truncate(depth);
+        }
+
+        fn visit_trait_bound_mut(&mut self, bound: &mut TraitBound) {
+            let depth = self.bind(&bound.lifetimes);
+            visit_mut::visit_trait_bound_mut(self, bound);
+            self.bound.truncate(depth);
+        }
+
+        fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+            if lifetime.ident != "static" && !self.bound.contains(&lifetime.ident) {
+                lifetime.ident = Ident::new("_", lifetime.ident.span());
+            }
+            visit_mut::visit_lifetime_mut(self, lifetime);
+        }
+    }
+
+    let mut elided = ty.clone();
+    LifetimeElider { bound: Vec::new() }.visit_type_mut(&mut elided);
+
+    elided
+}
+
+// ---------------------------------------------------------------- reference
+
+/// The parts of a reference type `&'a mut T`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefInfo<'a> {
+    /// `None` when elided, `&T`.
+    pub lifetime: Option<&'a Lifetime>,
+    /// `true` for `&mut T`.
+    pub mutability: bool,
+    /// The referenced type `T`.
+    pub elem: &'a Type,
+}
+
+/// Try to extract the lifetime, mutability and referenced type of a reference type,
+/// e.g. of a borrowed field.
+///
+/// - &str -> (`None`, shared, `str`)
+/// - &'a T -> (`Some('a)`, shared, `T`)
+/// - &'a mut Vec\<T\> -> (`Some('a)`, mutable, `Vec<T>`)
+/// - Box\<T\> -> `None`
+///
+/// @since 0.4.0
+pub fn try_unwrap_reference(ty: &Type) -> Option<RefInfo<'_>> {
+    match ty {
+        Type::Reference(reference) => Some(RefInfo {
+            lifetime: reference.lifetime.as_ref(),
+            mutability: reference.mutability.is_some(),
+            elem: &reference.elem,
+        }),
+        Type::Group(group) => try_unwrap_reference(&group.elem),
+        Type::Paren(paren) => try_unwrap_reference(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a shared or mutable reference.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_reference(ty: &Type) -> bool {
+    try_unwrap_reference(ty).is_some()
+}
+
+/// Try to predicate that [`syn::Type`] is a mutable reference `&mut T`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_mut_reference(ty: &Type) -> bool {
+    try_unwrap_reference(ty).is_some_and(|reference| reference.mutability)
+}
+
+// ---------------------------------------------------------------- array
+
+/// Try unwrap the element type and the length expression of an array type `[T; N]`,
+/// the length being a literal, a constant or a const generic parameter.
+///
+/// - \[u8; 32\] -> (`u8`, `32`)
+/// - \[T; N\] -> (`T`, `N`)
+/// - \[T\], Vec\<T\> -> `None`
+///
+/// @since 0.4.0
+pub fn try_unwrap_array(ty: &Type) -> Option<(&Type, &Expr)> {
+    match ty {
+        Type::Array(array) => Some((&array.elem, &array.len)),
+        Type::Group(group) => try_unwrap_array(&group.elem),
+        Type::Paren(paren) => try_unwrap_array(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is an array type `[T; N]`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_array(ty: &Type) -> bool {
+    try_unwrap_array(ty).is_some()
+}
+
+// ---------------------------------------------------------------- slice
+
+/// Try unwrap the element type of a slice `[T]`, also behind a reference `&[T]` / `&'a mut [T]`,
+/// see [`try_unwrap_reference`].
+///
+/// - \[u8\] -> `u8`
+/// - &'a \[String\] -> `String`
+/// - \[u8; 32\], Vec\<u8\> -> `None`
+///
+/// @since 0.4.0
+pub fn try_unwrap_slice(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Slice(slice) => Some(&slice.elem),
+        Type::Group(group) => try_unwrap_slice(&group.elem),
+        Type::Paren(paren) => try_unwrap_slice(&paren.elem),
+        Type::Reference(_) => try_unwrap_reference(ty).and_then(|reference| match reference.elem {
+            Type::Reference(_) => None,
+            elem => try_unwrap_slice(elem),
+        }),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a slice `[T]` or a reference to one.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_slice(ty: &Type) -> bool {
+    try_unwrap_slice(ty).is_some()
+}
+
+// ---------------------------------------------------------------- trait.object
+
+/// The bounds of a trait object `dyn Service + Send + 'static` or of an `impl Trait` type,
+/// in declaration order.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraitBounds<'a> {
+    /// `Service`, `Send`; `?Sized` bounds included.
+    pub traits: Vec<&'a TraitBound>,
+    /// `'static`
+    pub lifetimes: Vec<&'a Lifetime>,
+}
+
+impl<'a> TraitBounds<'a> {
+    fn from_bounds<I>(bounds: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TypeParamBound>,
+    {
+        let mut traits = Vec::new();
+        let mut lifetimes = Vec::new();
+        for bound in bounds {
+            match bound {
+                TypeParamBound::Trait(bound) => traits.push(bound),
+                TypeParamBound::Lifetime(lifetime) => lifetimes.push(lifetime),
+            }
+        }
+
+        Self { traits, lifetimes }
+    }
+
+    /// The first trait that is not an auto trait (`Send`, `Sync`, `Unpin`, ...), e.g. `Service`.
+    pub fn principal(&self) -> Option<&'a TraitBound> {
+        self.traits.iter().copied().find(|bound| {
+            !AUTO_TRAITS
+                .iter()
+                .any(|name| try_predicate_is_ident(name, &bound.path))
+        })
+    }
+
+    /// Whether one of the traits is `name`, matched on the last path segment
+    /// (`Send` matches `core::marker::Send`).
+    pub fn has_trait(&self, name: &str) -> bool {
+        self.find_trait(name).is_some()
+    }
+
+    /// The trait `name`, matched on the last path segment.
+    pub fn find_trait(&self, name: &str) -> Option<&'a TraitBound> {
+        self.traits
+            .iter()
+            .copied()
+            .find(|bound| try_predicate_is_ident(name, &bound.path))
+    }
+
+    /// The associated type `name` bound by any of the traits,
+    /// `impl Future<Output = T>`, `Output` -> `T`.
+    pub fn assoc_type(&self, name: &str) -> Option<&'a Type> {
+        self.traits.iter().find_map(|bound| {
+            let segment = bound.path.segments.last()?;
+            match &segment.arguments {
+                PathArguments::AngleBracketed(bracketed) => {
+                    bracketed.args.iter().find_map(|arg| match arg {
+                        GenericArgument::Binding(binding) if binding.ident == name => {
+                            Some(&binding.ty)
+                        }
+                        _ => None,
+                    })
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+const AUTO_TRAITS: [&str; 5] = ["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+/// Try to extract the traits and lifetimes of a trait object type.
+///
+/// - dyn Service + Send + 'static -> (`[Service, Send]`, `['static]`)
+/// - Box\<dyn Service\> -> `None`, unwrap it first, see [`try_unwrap_box`]
+///
+/// # Examples
+///
+///```ignore
+/// // Arc<dyn Repository + Send + Sync> vs Arc<PgRepository>
+/// let service = try_unwrap_arc(&field.ty).unwrap();
+/// if let Some(bounds) = try_extract_trait_object(service) {
+///     let principal = bounds.principal(); // Repository
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_extract_trait_object(ty: &Type) -> Option<TraitBounds<'_>> {
+    match ty {
+        Type::TraitObject(object) => Some(TraitBounds::from_bounds(&object.bounds)),
+        Type::Group(group) => try_extract_trait_object(&group.elem),
+        Type::Paren(paren) => try_extract_trait_object(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a trait object `dyn Trait`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_trait_object(ty: &Type) -> bool {
+    try_extract_trait_object(ty).is_some()
+}
+
+// ---------------------------------------------------------------- impl.trait
+
+/// Try to extract the traits and lifetimes of an `impl Trait` type, in argument or return position.
+///
+/// - impl Future\<Output = T\> + Send -> (`[Future<Output = T>, Send]`, `[]`)
+/// - impl Iterator\<Item = &'a str\> + 'a -> (`[Iterator<Item = &'a str>]`, `['a]`)
+///
+/// # Examples
+///
+///```ignore
+/// // async-like rewrite of `fn fetch() -> impl Future<Output = User>`
+/// if let ReturnType::Type(_, ref ty) = item_fn.sig.output {
+///     let output = try_extract_impl_trait(ty).and_then(|bounds| bounds.assoc_type("Output")); // User
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_extract_impl_trait(ty: &Type) -> Option<TraitBounds<'_>> {
+    match ty {
+        Type::ImplTrait(impl_trait) => Some(TraitBounds::from_bounds(&impl_trait.bounds)),
+        Type::Group(group) => try_extract_impl_trait(&group.elem),
+        Type::Paren(paren) => try_extract_impl_trait(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is an `impl Trait` type.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_impl_trait(ty: &Type) -> bool {
+    try_extract_impl_trait(ty).is_some()
+}
+
+// ---------------------------------------------------------------- fn.pointer
+
+/// The parts of a function pointer type `unsafe extern "C" fn(A, B) -> C`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnPointer<'a> {
+    /// The argument types, in order, without their optional names.
+    pub inputs: Vec<&'a Type>,
+    /// `None` for `fn(A)`, which returns `()`.
+    pub output: Option<&'a Type>,
+    pub unsafety: bool,
+    /// `extern "C"`
+    pub abi: Option<&'a Abi>,
+    /// The higher-ranked lifetimes of `for<'a> fn(&'a str)`.
+    pub lifetimes: Vec<&'a Lifetime>,
+    /// `fn(*const c_char, ...)`
+    pub variadic: bool,
+}
+
+/// Try to extract the argument and return types of a function pointer type,
+/// e.g. to generate a wrapper with a matching signature.
+///
+/// - fn(u8, &str) -> bool -> (`[u8, &str]`, `Some(bool)`)
+/// - for\<'a\> fn(&'a str) -> (`[&'a str]`, `None`)
+/// - Box\<dyn Fn(u8)\>, impl Fn(u8) -> `None`, see [`try_extract_trait_object`]
+///
+/// @since 0.4.0
+pub fn try_extract_fn_pointer(ty: &Type) -> Option<FnPointer<'_>> {
+    match ty {
+        Type::BareFn(bare) => Some(FnPointer {
+            inputs: bare.inputs.iter().map(|arg| &arg.ty).collect(),
+            output: match bare.output {
+                ReturnType::Default => None,
+                ReturnType::Type(_, ref ty) => Some(ty),
+            },
+            unsafety: bare.unsafety.is_some(),
+            abi: bare.abi.as_ref(),
+            lifetimes: bare
+                .lifetimes
+                .iter()
+                .flat_map(|bound| bound.lifetimes.iter().map(|def| &def.lifetime))
+                .collect(),
+            variadic: bare.variadic.is_some(),
+        }),
+        Type::Group(group) => try_extract_fn_pointer(&group.elem),
+        Type::Paren(paren) => try_extract_fn_pointer(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a function pointer type `fn(A) -> B`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_fn_pointer(ty: &Type) -> bool {
+    try_extract_fn_pointer(ty).is_some()
+}
+
+// ---------------------------------------------------------------- raw.pointer
+
+/// Try unwrap the pointee type of a raw pointer `*const T` / `*mut T`.
+///
+/// - \*const c_char -> `c_char`
+/// - \*mut \*mut u8 -> `*mut u8`
+///
+/// @since 0.4.0
+pub fn try_unwrap_ptr(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Ptr(ptr) => Some(&ptr.elem),
+        Type::Group(group) => try_unwrap_ptr(&group.elem),
+        Type::Paren(paren) => try_unwrap_ptr(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a `*const T` raw pointer.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_const_ptr(ty: &Type) -> bool {
+    try_predicate_is_ptr(ty, false)
+}
+
+/// Try to predicate that [`syn::Type`] is a `*mut T` raw pointer.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_mut_ptr(ty: &Type) -> bool {
+    try_predicate_is_ptr(ty, true)
+}
+
+fn try_predicate_is_ptr(ty: &Type, mutable: bool) -> bool {
+    match ty {
+        Type::Ptr(ptr) => ptr.mutability.is_some() == mutable,
+        Type::Group(group) => try_predicate_is_ptr(&group.elem, mutable),
+        Type::Paren(paren) => try_predicate_is_ptr(&paren.elem, mutable),
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------- clone.on.write
+
+/// The parts of a [`std::borrow::Cow<'a, B>`] type.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CowType<'a> {
+    /// `None` when elided, `Cow<str>`.
+    pub lifetime: Option<&'a Lifetime>,
+    /// The borrowed type argument `B`.
+    pub borrowed: &'a Type,
+}
+
+impl<'a> CowType<'a> {
+    /// The owned counterpart of the borrowed type, `<B as ToOwned>::Owned` spelled out for
+    /// the std types, bare or `std::`-qualified:
+    ///
+    /// - `str` -> `String`, `[T]` -> `Vec<T>`
+    /// - `Path` -> `PathBuf`, `OsStr` -> `OsString`, `CStr` -> `CString`
+    /// - any other `B` -> `<B as ToOwned>::Owned`, e.g. `my::Path`
+    pub fn owned(&self) -> Type {
+        let borrowed = self.borrowed;
+        match borrowed {
+            Type::Slice(slice) => {
+                let elem = &slice.elem;
+                syn::parse_quote!(::std::vec::Vec<#elem>)
+            }
+            Type::Path(tp)
+                if tp.qself.is_none()
+                    && try_predicate_is_std_path(&tp.path, "primitive", "str") =>
+            {
+                syn::parse_quote!(::std::string::String)
+            }
+            Type::Path(tp)
+                if tp.qself.is_none() && try_predicate_is_std_path(&tp.path, "path", "Path") =>
+            {
+                syn::parse_quote!(::std::path::PathBuf)
+            }
+            Type::Path(tp)
+                if tp.qself.is_none() && try_predicate_is_std_path(&tp.path, "ffi", "OsStr") =>
+            {
+                syn::parse_quote!(::std::ffi::OsString)
+            }
+            Type::Path(tp)
+                if tp.qself.is_none() && try_predicate_is_std_path(&tp.path, "ffi", "CStr") =>
+            {
+                syn::parse_quote!(::std::ffi::CString)
+            }
+            _ => syn::parse_quote!(<#borrowed as ::std::borrow::ToOwned>::Owned),
+        }
+    }
+}
+
+/// `item` bare, or `std::module::item`, without type arguments.
+fn try_predicate_is_std_path(path: &Path, module: &str, item: &str) -> bool {
+    if path
+        .segments
+        .iter()
+        .any(|segment| !segment.arguments.is_empty())
+    {
+        return false;
+    }
+    let idents: Vec<&Ident> = path.segments.iter().map(|segment| &segment.ident).collect();
+    match idents.as_slice() {
+        [ident] => path.leading_colon.is_none() && *ident == item,
+        [std, m, ident] => *std == "std" && *m == module && *ident == item,
+        _ => false,
+    }
+}
+
+/// Try to extract the lifetime and the borrowed type argument of [`std::borrow::Cow<'a, B>`],
+/// bare or qualified.
+///
+/// - Cow\<'a, str\> -> (`Some('a)`, `str`)
+/// - std::borrow::Cow\<\[u8\]\> -> (`None`, `[u8]`)
+///
+/// @since 0.4.0
+pub fn try_unwrap_cow(ty: &Type) -> Option<CowType<'_>> {
+    let segment = match ty {
+        Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != BUILTIN_TYPE_COW {
+        return None;
+    }
+
+    let bracketed = match &segment.arguments {
+        PathArguments::AngleBracketed(bracketed) => bracketed,
+        _ => return None,
+    };
+    let mut lifetime = None;
+    let mut borrowed = None;
+    for arg in &bracketed.args {
+        match arg {
+            GenericArgument::Lifetime(lt) if lifetime.is_none() && borrowed.is_none() => {
+                lifetime = Some(lt)
+            }
+            GenericArgument::Type(ty) if borrowed.is_none() => borrowed = Some(ty),
+            _ => return None,
+        }
+    }
+
+    Some(CowType {
+        lifetime,
+        borrowed: borrowed?,
+    })
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::borrow::Cow<'a, B>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_cow(ty: &Type) -> bool {
+    try_unwrap_cow(ty).is_some()
+}
+
+// ---------------------------------------------------------------- result
+
+/// Try unwrap the ok and error types of [`core::result::Result<T, E>`], bare or qualified.
+///
+/// Single-argument aliases (`std::io::Result<T>`, `syn::Result<T>`, `anyhow::Result<T>`)
+/// are detected as Results whose error type is fixed by the alias, and yield `None` for it.
+///
+/// - Result\<User, Error\> -> (`User`, `Some(Error)`)
+/// - std::io::Result\<usize\> -> (`usize`, `None`)
+/// - Option\<T\> -> `None`
+///
+/// @since 0.4.0
+pub fn try_unwrap_result(ty: &Type) -> Option<(&Type, Option<&Type>)> {
+    let types = try_extract_collection_types(&[BUILTIN_TYPE_RESULT], ty)?;
+    match types.as_slice() {
+        [ok] => Some((ok, None)),
+        [ok, err] => Some((ok, Some(err))),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is [`core::result::Result<T, E>`] or a
+/// single-argument `Result` alias, bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_result(ty: &Type) -> bool {
+    try_unwrap_result(ty).is_some()
+}
+
+// ---------------------------------------------------------------- collection
+
+/// Try unwrap the key and value types of a map, [`std::collections::HashMap<K, V>`]
+/// or [`std::collections::BTreeMap<K, V>`], bare or qualified.
+///
+/// A trailing hasher or allocator argument is ignored.
+///
+/// - HashMap\<String, u32\> -> (`String`, `u32`)
+/// - std::collections::BTreeMap\<u64, Vec\<Item\>\> -> (`u64`, `Vec<Item>`)
+/// - HashMap\<K, V, RandomState\> -> (`K`, `V`)
+/// - Vec\<(K, V)\> -> `None`
+///
+/// @since 0.4.0
+pub fn try_unwrap_map(ty: &Type) -> Option<(&Type, &Type)> {
+    let types = try_extract_collection_types(&[BUILTIN_TYPE_HASH_MAP, BUILTIN_TYPE_BTREE_MAP], ty)?;
+    match types.as_slice() {
+        [key, value] | [key, value, _] => Some((key, value)),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a `HashMap` or `BTreeMap`, bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_map(ty: &Type) -> bool {
+    try_unwrap_map(ty).is_some()
+}
+
+/// Try unwrap the element type of a set, [`std::collections::HashSet<T>`]
+/// or [`std::collections::BTreeSet<T>`], bare or qualified.
+///
+/// A trailing hasher or allocator argument is ignored.
+///
+/// - HashSet\<String\> -> `String`
+/// - std::collections::BTreeSet\<u64\> -> `u64`
+/// - HashSet\<T, RandomState\> -> `T`
+///
+/// @since 0.4.0
+pub fn try_unwrap_set(ty: &Type) -> Option<&Type> {
+    let types = try_extract_collection_types(&[BUILTIN_TYPE_HASH_SET, BUILTIN_TYPE_BTREE_SET], ty)?;
+    match types.as_slice() {
+        [elem] | [elem, _] => Some(elem),
+        _ => None,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a `HashSet` or `BTreeSet`, bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_set(ty: &Type) -> bool {
+    try_unwrap_set(ty).is_some()
+}
+
+fn try_extract_collection_types<'a>(idents: &[&str], ty: &'a Type) -> Option<Vec<&'a Type>> {
+    idents
+        .iter()
+        .find_map(|ident| try_unwrap_types_any(ident, ty))
+}
+
+// ---------------------------------------------------------------- primitive
+
+const PRIMITIVE_SIGNED_INTEGERS: [&str; 6] = ["i8", "i16", "i32", "i64", "i128", "isize"];
+const PRIMITIVE_UNSIGNED_INTEGERS: [&str; 6] = ["u8", "u16", "u32", "u64", "u128", "usize"];
+const PRIMITIVE_FLOATS: [&str; 2] = ["f32", "f64"];
+
+/// The name of a primitive type path, bare or through the `primitive` module,
+/// `u8`, `core::primitive::u8`.
+fn try_extract_primitive_name(ty: &Type) -> Option<String> {
+    let tp = match ty {
+        Type::Group(group) => return try_extract_primitive_name(&group.elem),
+        Type::Paren(paren) => return try_extract_primitive_name(&paren.elem),
+        Type::Path(tp) if tp.qself.is_none() => tp,
+        _ => return None,
+    };
+
+    let segments: Vec<&PathSegment> = tp.path.segments.iter().collect();
+    let last = match segments.as_slice() {
+        [last] if tp.path.leading_colon.is_none() => last,
+        [krate, module, last]
+            if (krate.ident == "std" || krate.ident == "core") && module.ident == "primitive" =>
+        {
+            last
+        }
+        _ => return None,
+    };
+
+    match last.arguments {
+        PathArguments::None => Some(last.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn try_predicate_is_primitive_in(names: &[&str], ty: &Type) -> bool {
+    try_extract_primitive_name(ty).is_some_and(|name| names.contains(&name.as_str()))
+}
+
+/// Try to predicate that [`syn::Type`] is a signed integer primitive, `i8` ..= `i128`, `isize`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_signed_integer(ty: &Type) -> bool {
+    try_predicate_is_primitive_in(&PRIMITIVE_SIGNED_INTEGERS, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is an unsigned integer primitive, `u8` ..= `u128`, `usize`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_unsigned_integer(ty: &Type) -> bool {
+    try_predicate_is_primitive_in(&PRIMITIVE_UNSIGNED_INTEGERS, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is an integer primitive, signed or unsigned.
+///
+/// - u8, core::primitive::i64, usize -> `true`
+/// - f32, NonZeroU8, u8\<T\> -> `false`
+///
+/// @since 0.4.0
+pub fn try_predicate_is_integer(ty: &Type) -> bool {
+    try_predicate_is_signed_integer(ty) || try_predicate_is_unsigned_integer(ty)
+}
+
+/// Try to predicate that [`syn::Type`] is a floating point primitive, `f32` or `f64`.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_float(ty: &Type) -> bool {
+    try_predicate_is_primitive_in(&PRIMITIVE_FLOATS, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is a numeric primitive, an integer or a float.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_numeric(ty: &Type) -> bool {
+    try_predicate_is_integer(ty) || try_predicate_is_float(ty)
+}
+
+/// Try to predicate that [`syn::Type`] is the `bool` primitive.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_bool(ty: &Type) -> bool {
+    try_predicate_is_primitive_in(&["bool"], ty)
+}
+
+/// Try to predicate that [`syn::Type`] is the `char` primitive.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_char(ty: &Type) -> bool {
+    try_predicate_is_primitive_in(&["char"], ty)
+}
+
+/// Try to predicate that [`syn::Type`] is a scalar primitive: a number, `bool` or `char`.
+///
+/// `str`, slices, arrays, tuples and `!` are primitives too, but not scalars, they are rejected.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_scalar(ty: &Type) -> bool {
+    try_predicate_is_numeric(ty) || try_predicate_is_bool(ty) || try_predicate_is_char(ty)
+}
+
+// ---------------------------------------------------------------- string
+
+/// Try to predicate that [`syn::Type`] is [`String`], bare or qualified.
+///
+/// - String, std::string::String, alloc::string::String -> `true`
+/// - &str, Cow\<str\>, Box\<str\> -> `false`
+///
+/// @since 0.4.0
+pub fn try_predicate_is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Group(group) => try_predicate_is_string(&group.elem),
+        Type::Paren(paren) => try_predicate_is_string(&paren.elem),
+        Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last().is_some_and(|segment| {
+            segment.ident == BUILTIN_TYPE_STRING && segment.arguments.is_empty()
+        }),
+        _ => false,
+    }
+}
+
+/// Try to predicate that [`syn::Type`] is a shared `str` reference, elided, named or `'static`.
+///
+/// - &str, &'a str, &'static str, &core::primitive::str -> `true`
+/// - &mut str, &&str, String -> `false`
+///
+/// @since 0.4.0
+pub fn try_predicate_is_str_ref(ty: &Type) -> bool {
+    try_unwrap_reference(ty)
+        .is_some_and(|info| !info.mutability && try_predicate_is_primitive_in(&["str"], info.elem))
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::borrow::Cow<'a, str>`], bare or qualified.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_cow_str(ty: &Type) -> bool {
+    try_unwrap_cow(ty).is_some_and(|cow| try_predicate_is_primitive_in(&["str"], cow.borrowed))
+}
+
+/// Try to predicate that [`syn::Type`] holds a string: [`String`], `&str` or `Cow<str>`,
+/// e.g. to accept `impl Into<String>` in a generated setter.
+///
+/// @since 0.4.0
+pub fn try_predicate_is_string_like(ty: &Type) -> bool {
+    try_predicate_is_string(ty) || try_predicate_is_str_ref(ty) || try_predicate_is_cow_str(ty)
+}
+
+// ---------------------------------------------------------------- boolean.function
+
+/// Try to predicate that [`syn::Type`] is neither of type [`core::option::Option<T>`] nor of type [`std::vec::Vec<T>`]
+///
+/// @since 0.2.0
+pub fn try_predicate_is_not_option_and_vec(ty: &Type) -> bool {
+    try_predicate_is_not_option(ty) && try_predicate_is_not_vec(ty)
+}
+
+/// Try to predicate that [`syn::Type`] is not [`core::option::Option<T>`] type.
+///
+/// @since 0.2.0
+pub fn try_predicate_is_not_option(ty: &Type) -> bool {
+    !try_predicate_is_option(ty)
+}
+
+/// Try to predicate that [`syn::Type`] is not [`std::vec::Vec<T>`] type.
+///
+/// @since 0.2.0
+pub fn try_predicate_is_not_vec(ty: &Type) -> bool {
+    !try_predicate_is_vec(ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`core::option::Option<T>`] type.
+///
+/// @since 0.2.0
+pub fn try_predicate_is_option(ty: &Type) -> bool {
+    try_predicate_is_type(BUILTIN_TYPE_OPTION, 1, ty)
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::vec::Vec<T>`] type.
+///
+/// @since 0.2.0
+pub fn try_predicate_is_vec(ty: &Type) -> bool {
+    try_predicate_is_type(BUILTIN_TYPE_VEC, 1, ty)
+}
+
+#[rustfmt::skip]
+pub fn try_predicate_is_type(ident: &str, target_types: usize, ty: &Type) -> bool {
+    // @formatter:off
+    if let Type::Path(
+        syn::TypePath {
+            ref path,
+            ..
+        }) = ty {
+        // @formatter:on
+        if try_predicate_is_ident(ident, path) && path.segments.len() == target_types {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn try_predicate_is_not_ident(ident: &str, path: &Path) -> bool {
+    !try_predicate_is_ident(ident, path)
+}
+
+pub fn try_predicate_is_ident(ident: &str, path: &Path) -> bool {
+    try_predicate_path_segments_is_not_empty(path) && path.segments.last().unwrap().ident == ident
+}
+
+pub fn try_predicate_path_segments_is_not_empty(path: &Path) -> bool {
+    !try_predicate_path_segments_is_empty(path)
+}
+
+pub fn try_predicate_path_segments_is_empty(path: &Path) -> bool {
+    path.segments.is_empty()
+}
+
+// ---------------------------------------------------------------- macro.input
+
+/// How helpers treat input containing unexpanded macro invocations, e.g. a field of type
+/// `Foo<cfg_if::cfg_if! { ... }>` or a `macro_rules!` item: the tokens can't be inspected
+/// until the compiler expands them, after the derive has run.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MacroPolicy {
+    /// Leave the field or item out.
+    Skip,
+    /// Report a spanned error on the macro invocation.
+    #[default]
+    Error,
+    /// Keep the field or item as is, its tokens are re-emitted untouched.
+    Passthrough,
+}
+
+struct TypeMacroFinder<'ast> {
+    found: Option<&'ast TypeMacro>,
+}
+
+impl<'ast> Visit<'ast> for TypeMacroFinder<'ast> {
+    fn visit_type_macro(&mut self, node: &'ast TypeMacro) {
+        if self.found.is_none() {
+            self.found = Some(node);
+        }
+    }
+}
+
+/// Try to find the first macro invocation in a [`syn::Type`], at any depth.
+///
+/// - `cfg_if! { .. }` -> `Some(..)`
+/// - `Vec<Option<ty!()>>` -> `Some(..)`
+/// - `Vec<u8>` -> `None`
+///
+/// @since 0.4.0
+pub fn try_find_type_macro(ty: &Type) -> Option<&TypeMacro> {
+    let mut finder = TypeMacroFinder { found: None };
+    finder.visit_type(ty);
+    finder.found
+}
+
+/// Try to predicate that [`syn::Type`] contains a macro invocation at any depth.
+///
+/// @since 0.4.0
+pub fn try_predicate_contains_macro(ty: &Type) -> bool {
+    try_find_type_macro(ty).is_some()
+}
+
+/// Try to apply a [`MacroPolicy`] to fields whose types contain macro invocations,
+/// reporting every offending field at once under [`MacroPolicy::Error`].
+///
+/// # Examples
+///
+///```ignore
+/// pub struct Config {
+///     name: String,
+///     flags: bitflags_ty!(u8), // -> skipped / error / kept
+/// }
+///
+/// let fields = try_apply_macro_policy(try_match_fields(&input), MacroPolicy::Skip)?;
+/// ```
+/// @since 0.4.0
+pub fn try_apply_macro_policy<'a, I>(fields: I, policy: MacroPolicy) -> syn::Result<Vec<&'a Field>>
+where
+    I: IntoIterator<Item = &'a Field>,
+{
+    let mut errors = ErrorAccumulator::new();
+    let mut kept = Vec::new();
+    for field in fields {
+        let invocation = match try_find_type_macro(&field.ty) {
+            Some(invocation) => invocation,
+            None => {
+                kept.push(field);
+                continue;
+            }
+        };
+
+        match policy {
+            MacroPolicy::Skip => {}
+            MacroPolicy::Passthrough => kept.push(field),
+            MacroPolicy::Error => {
+                errors.push(unexpanded_macro_error(&invocation.mac, "field types"))
+            }
+        }
+    }
+
+    errors.finish_with(kept)
+}
+
+/// Try to apply a [`MacroPolicy`] to item-position macro invocations (`macro_rules!`,
+/// `lazy_static! { .. }`), e.g. the items of a module handled by an attribute macro.
+///
+/// @since 0.4.0
+pub fn try_apply_item_macro_policy(items: &[Item], policy: MacroPolicy) -> syn::Result<Vec<&Item>> {
+    let mut errors = ErrorAccumulator::new();
+    let mut kept = Vec::new();
+    for item in items {
+        let error = match item {
+            Item::Macro(invocation) => unexpanded_macro_error(&invocation.mac, "items"),
+            Item::Macro2(definition) => syn::Error::new_spanned(
+                definition,
+                format!(
+                    "synext: Unexpanded macros are not supported in items! macro:`{}`",
+                    definition.ident
+                ),
+            ),
+            _ => {
+                kept.push(item);
+                continue;
+            }
+        };
+
+        match policy {
+            MacroPolicy::Skip => {}
+            MacroPolicy::Passthrough => kept.push(item),
+            MacroPolicy::Error => errors.push(error),
+        }
+    }
+
+    errors.finish_with(kept)
+}
+
+fn unexpanded_macro_error(mac: &Macro, position: &str) -> syn::Error {
+    let name = mac
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+
+    syn::Error::new_spanned(
+        mac,
+        format!(
+            "synext: Unexpanded macros are not supported in {}! macro:`{}!`",
+            position, name
+        ),
+    )
+}
+
+// ---------------------------------------------------------------- verbatim.syntax
+
+/// How helpers treat `Type::Verbatim` and `Expr::Verbatim` nodes, the raw tokens syn falls back
+/// to for syntax newer than it understands.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerbatimPolicy {
+    /// Treat the tokens as an unknown type or expression: predicates don't match, unwrapping
+    /// finds nothing and the tokens are re-emitted untouched.
+    #[default]
+    Opaque,
+    /// Report a spanned error on the tokens.
+    Error,
+}
+
+struct VerbatimFinder<'ast> {
+    found: Option<&'ast TokenStream>,
+}
+
+impl<'ast> Visit<'ast> for VerbatimFinder<'ast> {
+    fn visit_type(&mut self, node: &'ast Type) {
+        match node {
+            Type::Verbatim(tokens) if self.found.is_none() => self.found = Some(tokens),
+            node => visit::visit_type(self, node),
+        }
+    }
+
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        match node {
+            Expr::Verbatim(tokens) if self.found.is_none() => self.found = Some(tokens),
+            node => visit::visit_expr(self, node),
+        }
+    }
+}
+
+/// Try to find the first verbatim node of a [`syn::Type`], at any depth, including the
+/// array length expressions within it.
+///
+/// @since 0.4.0
+pub fn try_find_verbatim_type(ty: &Type) -> Option<&TokenStream> {
+    let mut finder = VerbatimFinder { found: None };
+    finder.visit_type(ty);
+    finder.found
+}
+
+/// Try to find the first verbatim node of a [`syn::Expr`], at any depth.
+///
+/// @since 0.4.0
+pub fn try_find_verbatim_expr(expr: &Expr) -> Option<&TokenStream> {
+    let mut finder = VerbatimFinder { found: None };
+    finder.visit_expr(expr);
+    finder.found
+}
+
+/// Try to predicate that [`syn::Type`] contains syntax syn only kept as verbatim tokens.
+///
+/// @since 0.4.0
+pub fn try_predicate_contains_verbatim(ty: &Type) -> bool {
+    try_find_verbatim_type(ty).is_some()
+}
+
+/// Try to apply a [`VerbatimPolicy`] to a type before inspecting or transforming it.
+///
+/// @since 0.4.0
+pub fn try_check_verbatim_type(ty: &Type, policy: VerbatimPolicy) -> syn::Result<()> {
+    match (policy, try_find_verbatim_type(ty)) {
+        (VerbatimPolicy::Error, Some(tokens)) => Err(unsupported_verbatim_error(tokens, ty)),
+        _ => Ok(()),
+    }
+}
+
+/// Try to apply a [`VerbatimPolicy`] to an expression before inspecting or transforming it.
+///
+/// @since 0.4.0
+pub fn try_check_verbatim_expr(expr: &Expr, policy: VerbatimPolicy) -> syn::Result<()> {
+    match (policy, try_find_verbatim_expr(expr)) {
+        (VerbatimPolicy::Error, Some(tokens)) => Err(unsupported_verbatim_error(tokens, expr)),
+        _ => Ok(()),
+    }
+}
+
+/// Try to apply a [`VerbatimPolicy`] to every field type, reporting all offending fields at once.
+///
+/// # Examples
+///
+///```ignore
+/// try_check_verbatim_fields(try_match_fields(&input), VerbatimPolicy::Error)?;
+/// for field in try_match_fields(&input) {
+///     let inner = try_unwrap_option_with(&field.ty, VerbatimPolicy::Error)?; // None if not an `Option`
+/// }
+/// ```
+/// @since 0.4.0
+pub fn try_check_verbatim_fields<'a, I>(fields: I, policy: VerbatimPolicy) -> syn::Result<()>
+where
+    I: IntoIterator<Item = &'a Field>,
+{
+    fields
+        .into_iter()
+        .filter_map(|field| try_check_verbatim_type(&field.ty, policy).err())
+        .collect::<ErrorAccumulator>()
+        .finish()
+}
+
+/// Like [`try_unwrap_types_in`], applying a [`VerbatimPolicy`] to the type first: verbatim
+/// tokens unwrap to nothing with [`VerbatimPolicy::Opaque`] and are reported with [`VerbatimPolicy::Error`].
+///
+/// - `"Option"`, Option\<T\>, 1..=1 -> `Ok(Some([T]))`
+/// - `"Option"`, \<verbatim\>, 1..=1, `Error` -> `Err(..)`
+///
+/// @since 0.4.0
+pub fn try_unwrap_types_with<'a, R>(
+    ident: &str,
+    arity: R,
+    ty: &'a Type,
+    policy: VerbatimPolicy,
+) -> syn::Result<Option<Vec<&'a Type>>>
+where
+    R: RangeBounds<usize>,
+{
+    try_check_verbatim_type(ty, policy)?;
+
+    Ok(try_unwrap_types_in(ident, arity, ty))
+}
+
+/// Try to unwrap the `T` of [`core::option::Option<T>`] under a [`VerbatimPolicy`],
+/// `None` instead of a panic when the type is not an `Option`.
+///
+/// @since 0.4.0
+pub fn try_unwrap_option_with(ty: &Type, policy: VerbatimPolicy) -> syn::Result<Option<&Type>> {
+    try_unwrap_types_with(BUILTIN_TYPE_OPTION, 1..=1, ty, policy)
+        .map(|types| types.map(|types| types[0]))
+}
+
+/// Try to unwrap the `T` of [`std::vec::Vec<T>`] under a [`VerbatimPolicy`],
+/// `None` instead of a panic when the type is not a `Vec`.
+///
+/// @since 0.4.0
+pub fn try_unwrap_vec_with(ty: &Type, policy: VerbatimPolicy) -> syn::Result<Option<&Type>> {
+    try_unwrap_types_with(BUILTIN_TYPE_VEC, 1..=1, ty, policy)
+        .map(|types| types.map(|types| types[0]))
+}
+
+/// Try to predicate that [`syn::Type`] is [`core::option::Option<T>`] under a [`VerbatimPolicy`],
+/// [`try_predicate_is_option`] behaves as [`VerbatimPolicy::Opaque`].
+///
+/// @since 0.4.0
+pub fn try_predicate_is_option_with(ty: &Type, policy: VerbatimPolicy) -> syn::Result<bool> {
+    try_unwrap_option_with(ty, policy).map(|inner| inner.is_some())
+}
+
+/// Try to predicate that [`syn::Type`] is [`std::vec::Vec<T>`] under a [`VerbatimPolicy`],
+/// [`try_predicate_is_vec`] behaves as [`VerbatimPolicy::Opaque`].
+///
+/// @since 0.4.0
+pub fn try_predicate_is_vec_with(ty: &Type, policy: VerbatimPolicy) -> syn::Result<bool> {
+    try_unwrap_vec_with(ty, policy).map(|inner| inner.is_some())
+}
+
+fn unsupported_verbatim_error<T: ToTokens>(tokens: &TokenStream, owner: &T) -> syn::Error {
+    let message = format!("synext: Unsupported syntax! tokens:`{}`", tokens);
+    match tokens.is_empty() {
+        true => syn::Error::new_spanned(owner, message),
+        false => syn::Error::new_spanned(tokens, message),
+    }
 }
@@ -0,0 +1,336 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/generics/parser
+
+// ----------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    Expr, GenericParam, Generics, Ident, Lifetime, Type, TypeParamBound, TypePath, WherePredicate,
+};
+
+// ----------------------------------------------------------------
+
+/// A generic parameter of [`syn::Generics`], see [`generic_params`].
+///
+/// Bounds gather the inline bounds followed by the bounds of the where-clause predicates
+/// on the bare parameter (`where T: Send`, `where 'a: 'b`), in declaration order.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamKind<'a> {
+    /// `'a: 'b`
+    Lifetime {
+        lifetime: &'a Lifetime,
+        bounds: Vec<&'a Lifetime>,
+    },
+    /// `T: Clone = String`
+    Type {
+        ident: &'a Ident,
+        bounds: Vec<&'a TypeParamBound>,
+        default: Option<&'a Type>,
+    },
+    /// `const N: usize = 8`
+    Const {
+        ident: &'a Ident,
+        ty: &'a Type,
+        default: Option<&'a Expr>,
+    },
+}
+
+/// Try to iterate the generic parameters of [`syn::Generics`] in declaration order,
+/// tagged by kind, see [`ParamKind`].
+///
+/// # Examples
+///
+///```ignore
+/// for param in generic_params(&input.generics) {
+///     match param {
+///         ParamKind::Lifetime { lifetime, .. } => { /* ... */ }
+///         ParamKind::Type { ident, bounds, default } => { /* ... */ }
+///         ParamKind::Const { ident, ty, .. } => { /* ... */ }
+///     }
+/// }
+/// ```
+/// @since 0.4.0
+pub fn generic_params(generics: &Generics) -> impl Iterator<Item = ParamKind<'_>> {
+    let predicates = generics
+        .where_clause
+        .as_ref()
+        .map(|where_clause| &where_clause.predicates);
+
+    generics.params.iter().map(move |param| match param {
+        GenericParam::Lifetime(ld) => {
+            let mut bounds: Vec<&Lifetime> = ld.bounds.iter().collect();
+            for predicate in predicates.into_iter().flatten() {
+                if let WherePredicate::Lifetime(pl) = predicate {
+                    if pl.lifetime == ld.lifetime {
+                        bounds.extend(pl.bounds.iter());
+                    }
+                }
+            }
+            ParamKind::Lifetime {
+                lifetime: &ld.lifetime,
+                bounds,
+            }
+        }
+        GenericParam::Type(tp) => {
+            let mut bounds: Vec<&TypeParamBound> = tp.bounds.iter().collect();
+            for predicate in predicates.into_iter().flatten() {
+                if let WherePredicate::Type(pt) = predicate {
+                    let bare = match &pt.bounded_ty {
+                        Type::Path(path) if path.qself.is_none() && pt.lifetimes.is_none() => {
+                            path.path.is_ident(&tp.ident)
+                        }
+                        _ => false,
+                    };
+                    if bare {
+                        bounds.extend(pt.bounds.iter());
+                    }
+                }
+            }
+            ParamKind::Type {
+                ident: &tp.ident,
+                bounds,
+                default: tp.default.as_ref(),
+            }
+        }
+        GenericParam::Const(cp) => ParamKind::Const {
+            ident: &cp.ident,
+            ty: &cp.ty,
+            default: cp.default.as_ref(),
+        },
+    })
+}
+
+/// Try to predicate that [`syn::Generics`] declares at least one default
+/// (`struct Foo<T = String>` or `struct Foo<const N: usize = 8>`).
+///
+/// @since 0.4.0
+pub fn try_predicate_has_generic_defaults(generics: &Generics) -> bool {
+    generics.params.iter().any(|param| match param {
+        GenericParam::Type(tp) => tp.default.is_some(),
+        GenericParam::Const(cp) => cp.default.is_some(),
+        GenericParam::Lifetime(_) => false,
+    })
+}
+
+/// Try to extract the default types of the type parameters of [`syn::Generics`],
+/// in declaration order.
+///
+/// - `struct Foo<T = String, U>` -> `[(T, String)]`
+///
+/// @since 0.4.0
+pub fn try_extract_generic_defaults(generics: &Generics) -> Vec<(&Ident, &Type)> {
+    generics
+        .type_params()
+        .filter_map(|tp| tp.default.as_ref().map(|ty| (&tp.ident, ty)))
+        .collect()
+}
+
+/// Try to strip every type/const parameter default from [`syn::Generics`].
+///
+/// Defaults are only legal on type definitions, rustc rejects them on `impl` blocks.
+/// Use the stripped copy whenever the generics are rendered as `impl<...>` by hand
+/// (e.g. after pushing extra params), and keep the original [`syn::Generics`]
+/// for companion type definitions (mirror structs, builders) so they expose the same defaults.
+///
+/// - `<T: Clone = String, const N: usize = 8>` -> `<T: Clone, const N: usize>`
+///
+/// @since 0.4.0
+pub fn try_strip_generic_defaults(generics: &Generics) -> Generics {
+    let mut stripped = generics.clone();
+    for param in stripped.params.iter_mut() {
+        match param {
+            GenericParam::Type(tp) => {
+                tp.eq_token = None;
+                tp.default = None;
+            }
+            GenericParam::Const(cp) => {
+                cp.eq_token = None;
+                cp.default = None;
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+
+    stripped
+}
+
+/// Try to collect the type parameters of `generics` that a [`syn::Type`] mentions, at any depth,
+/// e.g. to bound only the parameters that occur in fields.
+///
+/// A parameter is mentioned by a path starting with it, `T` or `T::Output`, also inside
+/// references, tuples, trait objects and qualified paths.
+///
+/// - Vec\<T\>, `<T, U>` -> `{T}`
+/// - HashMap\<K, Option\<V::Id\>\>, `<K, V, S>` -> `{K, V}`
+/// - PhantomData\<fn() -> U\>, `<T, U>` -> `{U}`
+/// - my::T, `<T>` -> `{}`
+///
+/// @since 0.4.0
+pub fn collect_type_params_in_type(ty: &Type, generics: &Generics) -> HashSet<Ident> {
+    struct TypeParamVisitor<'a> {
+        params: Vec<&'a Ident>,
+        used: HashSet<Ident>,
+    }
+
+    impl<'ast> Visit<'ast> for TypeParamVisitor<'_> {
+        fn visit_type_path(&mut self, tp: &'ast TypePath) {
+            if tp.qself.is_none() && tp.path.leading_colon.is_none() {
+                if let Some(first) = tp.path.segments.first() {
+                    if let Some(param) = self.params.iter().find(|param| **param == &first.ident) {
+                        self.used.insert((*param).clone());
+                    }
+                }
+            }
+            visit::visit_type_path(self, tp);
+        }
+    }
+
+    let mut visitor = TypeParamVisitor {
+        params: generics.type_params().map(|tp| &tp.ident).collect(),
+        used: HashSet::new(),
+    };
+    visitor.visit_type(ty);
+
+    visitor.used
+}
+
+// ----------------------------------------------------------------
+
+/// Normalize the where clause of [`syn::Generics`] in place.
+///
+/// - Bounds on the same bounded type (or lifetime) are merged into one predicate.
+/// - Duplicate bounds are dropped, first occurrence wins.
+/// - Predicates are sorted stably: lifetime predicates first, then type predicates
+///   ordered by their bounded type, then equality predicates.
+///
+/// `where T: Clone, U: Debug, T: Clone + Send` -> `where T: Clone + Send, U: Debug`
+///
+/// @since 0.4.0
+pub fn normalize_where_clause(generics: &mut Generics) {
+    normalize_where_clause_with(generics, OutputOrder::Sorted)
+}
+
+/// The ordering of items emitted by a helper.
+///
+/// Every helper is deterministic whatever the order: the same input always produces
+/// the same output, which build caches and snapshot tests rely on.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputOrder {
+    /// Keep the order in which items were first declared.
+    Declaration,
+    /// Sort items by a stable, documented key.
+    #[default]
+    Sorted,
+}
+
+/// Like [`normalize_where_clause`], with [`OutputOrder::Declaration`] merging and deduplicating
+/// predicates while keeping each one at the position of its first occurrence.
+///
+/// @since 0.4.0
+pub fn normalize_where_clause_with(generics: &mut Generics, order: OutputOrder) {
+    let where_clause = match generics.where_clause.as_mut() {
+        Some(where_clause) => where_clause,
+        None => return,
+    };
+
+    let mut merged: Vec<(u8, String, WherePredicate)> = Vec::new();
+    for predicate in std::mem::take(&mut where_clause.predicates) {
+        let (rank, key) = where_predicate_sort_key(&predicate);
+        match merged
+            .iter_mut()
+            .find(|(r, k, _)| rank < 2 && *r == rank && *k == key)
+        {
+            Some((_, _, existing)) => merge_where_predicate_bounds(existing, predicate),
+            None => {
+                let mut predicate = predicate;
+                dedup_where_predicate_bounds(&mut predicate);
+                merged.push((rank, key, predicate));
+            }
+        }
+    }
+
+    if order == OutputOrder::Sorted {
+        merged.sort_by(|(ra, ka, _), (rb, kb, _)| ra.cmp(rb).then_with(|| ka.cmp(kb)));
+    }
+
+    where_clause.predicates = merged.into_iter().map(|(_, _, p)| p).collect();
+}
+
+fn where_predicate_sort_key(predicate: &WherePredicate) -> (u8, String) {
+    match predicate {
+        WherePredicate::Lifetime(pl) => (0, pl.lifetime.to_string()),
+        WherePredicate::Type(pt) => {
+            let mut key = pt.bounded_ty.to_token_stream().to_string();
+            if let Some(ref lifetimes) = pt.lifetimes {
+                key = format!("{} {}", lifetimes.to_token_stream(), key);
+            }
+            (1, key)
+        }
+        WherePredicate::Eq(pe) => (2, pe.to_token_stream().to_string()),
+    }
+}
+
+fn merge_where_predicate_bounds(existing: &mut WherePredicate, incoming: WherePredicate) {
+    match (existing, incoming) {
+        (WherePredicate::Type(ref mut into), WherePredicate::Type(from)) => {
+            into.bounds.extend(from.bounds);
+            into.bounds = dedup_by_tokens(std::mem::take(&mut into.bounds));
+        }
+        (WherePredicate::Lifetime(ref mut into), WherePredicate::Lifetime(from)) => {
+            into.bounds.extend(from.bounds);
+            into.bounds = dedup_by_tokens(std::mem::take(&mut into.bounds));
+        }
+        _ => {}
+    }
+}
+
+fn dedup_where_predicate_bounds(predicate: &mut WherePredicate) {
+    match predicate {
+        WherePredicate::Type(ref mut pt) => {
+            pt.bounds = dedup_by_tokens(std::mem::take(&mut pt.bounds));
+        }
+        WherePredicate::Lifetime(ref mut pl) => {
+            pl.bounds = dedup_by_tokens(std::mem::take(&mut pl.bounds));
+        }
+        WherePredicate::Eq(_) => {}
+    }
+}
+
+fn dedup_by_tokens<T: ToTokens, P: Default>(items: Punctuated<T, P>) -> Punctuated<T, P> {
+    let mut seen = Vec::new();
+    let mut deduped = Punctuated::new();
+    for item in items {
+        let key = item.to_token_stream().to_string();
+        if !seen.contains(&key) {
+            seen.push(key);
+            deduped.push(item);
+        }
+    }
+
+    deduped
+}
@@ -0,0 +1,132 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/peek/parser
+
+// ----------------------------------------------------------------
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::parse::{ParseStream, Parser};
+use syn::{Attribute, Ident, Token, Visibility};
+
+// ----------------------------------------------------------------
+
+/// Item keywords directly followed by the item's name.
+pub const ITEM_KEYWORDS: [&str; 9] = [
+    "struct", "enum", "union", "fn", "trait", "mod", "type", "static", "const",
+];
+
+// ----------------------------------------------------------------
+
+/// The header of an item: everything up to and including its name,
+/// read without parsing the item's body.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ItemHeader {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    /// `struct`, `enum`, `fn`, ...
+    pub keyword: Ident,
+    pub ident: Ident,
+}
+
+/// Try to read the outer attributes, visibility, keyword and name of an item,
+/// leaving fields, variants and function bodies unparsed.
+///
+/// Attribute macros that mostly pass the item through unchanged can use this
+/// instead of parsing a full `syn::Item`.
+///
+/// - `#[x] pub(crate) const unsafe fn run() {}` -> `fn`, `run`
+/// - `static mut COUNTER: usize = 0;` -> `static`, `COUNTER`
+/// - `impl<const N: usize> Foo for [u8; N] {}` -> error, an `impl` has no name
+///
+/// # Examples
+///
+///```ignore
+/// #[proc_macro_attribute]
+/// pub fn register(_args: TokenStream, item: TokenStream) -> TokenStream {
+///     let item = proc_macro2::TokenStream::from(item);
+///     let header = peek_item(&item)?;
+///     let ident = header.ident;
+///     quote! {
+///         #item
+///         inventory::submit! { Entry::new(stringify!(#ident)) }
+///     }
+/// }
+/// ```
+/// @since 0.4.0
+pub fn peek_item(tokens: &TokenStream) -> syn::Result<ItemHeader> {
+    parse_item_header.parse2(tokens.clone())
+}
+
+/// Try to read only the name of an item.
+///
+/// @since 0.4.0
+pub fn peek_ident(tokens: &TokenStream) -> syn::Result<Ident> {
+    peek_item(tokens).map(|header| header.ident)
+}
+
+/// Try to read only the outer attributes of an item, without looking at the rest of it.
+///
+/// @since 0.4.0
+pub fn peek_attributes(tokens: &TokenStream) -> syn::Result<Vec<Attribute>> {
+    let parser = |input: ParseStream| {
+        let attrs = input.call(Attribute::parse_outer)?;
+        input.parse::<TokenStream>()?;
+        Ok(attrs)
+    };
+
+    parser.parse2(tokens.clone())
+}
+
+fn parse_item_header(input: ParseStream) -> syn::Result<ItemHeader> {
+    let attrs = input.call(Attribute::parse_outer)?;
+    let vis: Visibility = input.parse()?;
+    let start = input.span();
+
+    // Only the qualifiers before the keyword are scanned (`const`, `unsafe`, `async`, `extern "C"`),
+    // the first punctuation or group ends the header, e.g. the generics of `impl<const N: usize>`.
+    while !input.is_empty() {
+        let keyword = match input.parse::<TokenTree>()? {
+            TokenTree::Ident(ident) if ITEM_KEYWORDS.iter().any(|kw| ident == kw) => ident,
+            TokenTree::Ident(_) | TokenTree::Literal(_) => continue,
+            TokenTree::Punct(_) | TokenTree::Group(_) => break,
+        };
+        if keyword == "static" && input.peek(Token![mut]) {
+            input.parse::<Token![mut]>()?;
+        }
+        // `const fn`, `unsafe fn`: keep scanning for the keyword followed by a name.
+        if input.peek(Ident) {
+            let ident: Ident = input.parse()?;
+            input.parse::<TokenStream>()?;
+
+            return Ok(ItemHeader {
+                attrs,
+                vis,
+                keyword,
+                ident,
+            });
+        }
+    }
+
+    Err(syn::Error::new(
+        start,
+        "synext: expected a named item (struct, enum, fn, trait, ...)",
+    ))
+}
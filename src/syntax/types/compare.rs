@@ -0,0 +1,291 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/types/compare
+
+// ----------------------------------------------------------------
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+use syn::{AngleBracketedGenericArguments, Path, PathSegment, Type, TypePath};
+
+// ----------------------------------------------------------------
+
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// The items of the std prelude by module, `std::option::Option` is `Option` wherever it is written.
+const PRELUDE_ITEMS: &[(&str, &[&str])] = &[
+    ("option", &["Option"]),
+    ("result", &["Result"]),
+    ("vec", &["Vec"]),
+    ("string", &["String", "ToString"]),
+    ("boxed", &["Box"]),
+    ("borrow", &["ToOwned"]),
+    ("clone", &["Clone"]),
+    ("default", &["Default"]),
+    ("marker", &["Copy", "Send", "Sized", "Sync", "Unpin"]),
+    ("ops", &["Drop", "Fn", "FnMut", "FnOnce"]),
+    ("cmp", &["Eq", "Ord", "PartialEq", "PartialOrd"]),
+    (
+        "convert",
+        &["AsMut", "AsRef", "From", "Into", "TryFrom", "TryInto"],
+    ),
+    (
+        "iter",
+        &[
+            "DoubleEndedIterator",
+            "ExactSizeIterator",
+            "Extend",
+            "FromIterator",
+            "IntoIterator",
+            "Iterator",
+        ],
+    ),
+];
+
+/// Try to predicate that two [`syn::Type`]s are the same type, structurally.
+///
+/// Unlike comparing `to_token_stream().to_string()`, spacing and spans are ignored, and:
+///
+/// - a leading `::` is ignored, `::my::Id` == `my::Id`
+/// - the std prelude items equal their bare names, `std::option::Option<T>` == `Option<T>`,
+///   `core::iter::Iterator` == `Iterator`
+/// - parenthesized and invisible-grouped types are transparent, `(T)` == `T`
+/// - a turbofish is ignored, `Option::<T>` == `Option<T>`
+///
+/// Every other path is compared in full, `std::io::Result<u8>` != `Result<u8>`,
+/// `std::io::Error` != `std::fmt::Error`, `crate::Id` != `Id`.
+///
+/// # Examples
+///
+///```ignore
+/// let a: Type = parse_quote!(std::option::Option<::std::vec::Vec<u8>>);
+/// let b: Type = parse_quote!(Option<Vec<u8>>);
+/// assert!(types_equal(&a, &b));
+/// ```
+/// @since 0.4.0
+pub fn types_equal(a: &Type, b: &Type) -> bool {
+    normalize_type(a) == normalize_type(b)
+}
+
+// ---------------------------------------------------------------- canonical.string
+
+/// Render a [`syn::Type`] into a normalized, whitespace-stable string, e.g. as a map key
+/// or in a generated error message.
+///
+/// The type is normalized like [`types_equal`], so two types are equal exactly when their
+/// canonical strings are, and spaced the way `rustfmt` writes types.
+///
+/// - std::option::Option \<Vec \< u8 \> \> -> `Option<Vec<u8>>`
+/// - & 'a mut HashMap \< K , V \> -> `&'a mut HashMap<K, V>`
+/// - Box \< dyn Fn ( u8 ) -> u8 + Send \> -> `Box<dyn Fn(u8) -> u8 + Send>`
+/// - [ u8 ; 4 ] -> `[u8; 4]`
+///
+/// # Examples
+///
+///```ignore
+/// let mut seen = HashSet::new();
+/// for field in fields {
+///     if !seen.insert(type_to_canonical_string(&field.ty)) {
+///         continue; // one bound per distinct type
+///     }
+///     // ...
+/// }
+/// ```
+/// @since 0.4.0
+pub fn type_to_canonical_string(ty: &Type) -> String {
+    render_tokens(normalize_type(ty).to_token_stream())
+}
+
+/// Keywords followed by a space, even before a group: `&mut (A, B)` but `fn(u8)`.
+const SPACED_KEYWORDS: &[&str] = &[
+    "as", "const", "dyn", "extern", "impl", "in", "mut", "unsafe", "where",
+];
+
+enum Atom {
+    Word(String),
+    Punct(String),
+    Literal(String),
+    Group(Delimiter, String),
+}
+
+impl Atom {
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self, Atom::Punct(p) if p == punct)
+    }
+
+    /// A path segment or a type name, directly followed by `<`, `(` or `!`: `Vec<u8>`, `Fn(u8)`, `m!(..)`.
+    fn is_name(&self) -> bool {
+        matches!(self, Atom::Word(word) if !SPACED_KEYWORDS.contains(&word.as_str()))
+    }
+
+    fn is_tight_after(&self) -> bool {
+        ["&", "*", "<", "::", "?"]
+            .iter()
+            .any(|punct| self.is_punct(punct))
+    }
+
+    fn is_tight_before(&self) -> bool {
+        [",", ";", ">", "::"]
+            .iter()
+            .any(|punct| self.is_punct(punct))
+    }
+}
+
+fn render_tokens(tokens: TokenStream) -> String {
+    let mut atoms: Vec<Atom> = Vec::new();
+    let mut joint = String::new();
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) => {
+                joint.push(punct.as_char());
+                if punct.spacing() == Spacing::Alone {
+                    atoms.push(Atom::Punct(std::mem::take(&mut joint)));
+                }
+            }
+            TokenTree::Ident(ident) => {
+                // a lifetime is a joint `'` followed by its ident
+                atoms.push(Atom::Word(format!(
+                    "{}{}",
+                    std::mem::take(&mut joint),
+                    ident
+                )));
+            }
+            TokenTree::Literal(literal) => atoms.push(Atom::Literal(literal.to_string())),
+            TokenTree::Group(group) => atoms.push(Atom::Group(
+                group.delimiter(),
+                render_tokens(group.stream()),
+            )),
+        }
+    }
+    if !joint.is_empty() {
+        atoms.push(Atom::Punct(joint));
+    }
+
+    let mut rendered = String::new();
+    let mut prev: Option<&Atom> = None;
+    for atom in atoms.iter() {
+        let spaced = match (prev, atom) {
+            (None, _) => false,
+            (Some(prev), _) if prev.is_tight_after() || atom.is_tight_before() => false,
+            (Some(prev), Atom::Punct(punct)) if punct == "<" || punct == "!" => !prev.is_name(),
+            (Some(prev), Atom::Group(Delimiter::Parenthesis | Delimiter::Bracket, _)) => {
+                !prev.is_name() && !prev.is_punct("!")
+            }
+            _ => true,
+        };
+        if spaced {
+            rendered.push(' ');
+        }
+        match atom {
+            Atom::Word(text) | Atom::Punct(text) | Atom::Literal(text) => rendered.push_str(text),
+            Atom::Group(delimiter, inner) => match delimiter {
+                Delimiter::Parenthesis => rendered.push_str(&format!("({})", inner)),
+                Delimiter::Bracket => rendered.push_str(&format!("[{}]", inner)),
+                Delimiter::Brace => rendered.push_str(&format!("{{ {} }}", inner)),
+                Delimiter::None => rendered.push_str(inner),
+            },
+        }
+        prev = Some(atom);
+    }
+
+    rendered
+}
+
+// ----------------------------------------------------------------
+
+fn normalize_type(ty: &Type) -> Type {
+    let mut ty = ty.clone();
+    TypeNormalizer.visit_type_mut(&mut ty);
+
+    ty
+}
+
+struct TypeNormalizer;
+
+impl VisitMut for TypeNormalizer {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        while let Type::Group(_) | Type::Paren(_) = ty {
+            *ty = match ty {
+                Type::Group(group) => (*group.elem).clone(),
+                Type::Paren(paren) => (*paren.elem).clone(),
+                _ => unreachable!(),
+            };
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_type_path_mut(&mut self, tp: &mut TypePath) {
+        // `<T as Trait>::Item` indexes the trait by segment position, keep its segments as written
+        match &mut tp.qself {
+            Some(qself) => {
+                self.visit_type_mut(&mut qself.ty);
+                // the `::` of `<T>::Item` is the leading colon of `Item`
+                if qself.position > 0 {
+                    tp.path.leading_colon = None;
+                }
+                // <T as std::iter::Iterator>::Item -> <T as Iterator>::Item
+                if qself.position == 3 && is_prelude_path(tp.path.segments.iter().take(3)) {
+                    tp.path.segments = std::mem::take(&mut tp.path.segments)
+                        .into_pairs()
+                        .skip(2)
+                        .collect();
+                    qself.position = 1;
+                }
+                for segment in tp.path.segments.iter_mut() {
+                    self.visit_path_arguments_mut(&mut segment.arguments);
+                }
+            }
+            None => self.visit_path_mut(&mut tp.path),
+        }
+    }
+
+    fn visit_angle_bracketed_generic_arguments_mut(
+        &mut self,
+        arguments: &mut AngleBracketedGenericArguments,
+    ) {
+        // Option::<T> -> Option<T>
+        arguments.colon2_token = None;
+        visit_mut::visit_angle_bracketed_generic_arguments_mut(self, arguments);
+    }
+
+    fn visit_path_mut(&mut self, path: &mut Path) {
+        path.leading_colon = None;
+        if path.segments.len() == 3 && is_prelude_path(path.segments.iter()) {
+            let last = path.segments.pop().unwrap().into_value();
+            path.segments.clear();
+            path.segments.push(last);
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+}
+
+/// Whether `segments` are `<std|core|alloc>::<module>::<Item>` of a std prelude item.
+fn is_prelude_path<'a>(segments: impl Iterator<Item = &'a PathSegment>) -> bool {
+    let segments: Vec<&PathSegment> = segments.collect();
+    match segments.as_slice() {
+        [krate, module, item] if krate.arguments.is_empty() && module.arguments.is_empty() => {
+            STD_CRATES.iter().any(|name| krate.ident == name)
+                && PRELUDE_ITEMS.iter().any(|(name, items)| {
+                    module.ident == name && items.iter().any(|it| item.ident == it)
+                })
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,236 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/types/registry
+
+// ----------------------------------------------------------------
+
+use std::ops::RangeInclusive;
+
+use syn::{Ident, Type};
+
+use crate::syntax::derive::parser::{
+    try_predicate_is_option, try_predicate_is_vec, try_predicate_references_self_in,
+    try_unwrap_map, try_unwrap_set, try_unwrap_types_any, try_unwrap_types_in, BUILTIN_TYPE_OPTION,
+    BUILTIN_TYPE_VEC,
+};
+
+// ----------------------------------------------------------------
+
+/// The role of a registered container type, deciding which predicates and unwrappers consult it.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ContainerKind {
+    /// `Option<T>`-like, consulted by [`TypeRegistry::try_predicate_is_option`] and [`TypeRegistry::try_unwrap_option`].
+    Option,
+    /// `Vec<T>`-like, consulted by [`TypeRegistry::try_predicate_is_vec`] and [`TypeRegistry::try_unwrap_vec`].
+    Vec,
+    /// `HashSet<T>`-like, consulted by [`TypeRegistry::try_predicate_is_set`] and [`TypeRegistry::try_unwrap_set`].
+    Set,
+    /// `HashMap<K, V>`-like, consulted by [`TypeRegistry::try_predicate_is_map`] and [`TypeRegistry::try_unwrap_map`].
+    Map,
+    /// `Arc<T>`-like, a pointer or wrapper of a single value, consulted as an indirection by
+    /// [`TypeRegistry::try_predicate_references_self`].
+    Pointer,
+}
+
+/// A registered container type: the last path segment, its role and the accepted number of type arguments.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerType {
+    pub ident: String,
+    pub kind: ContainerKind,
+    pub arity: RangeInclusive<usize>,
+}
+
+/// The container types a macro crate knows of, beyond the hardcoded `Option`/`Vec`/`HashMap`/`HashSet`.
+///
+/// - [`TypeRegistry::new`] is empty.
+/// - [`TypeRegistry::with_builtins`] holds the std containers, for queries over the registry alone.
+///
+/// Types are matched by the last path segment, so `smallvec::SmallVec<[T; 4]>` matches `SmallVec`.
+///
+/// The registry is passed explicitly: its `try_predicate_*`/`try_unwrap_*` methods accept the
+/// hardcoded std types and the registered ones, while the free functions of the same name keep
+/// matching the std types only.
+///
+/// # Examples
+///
+///```ignore
+/// let mut registry = TypeRegistry::new();
+/// registry
+///     .register("SmallVec", ContainerKind::Vec, 1..=1)
+///     .register("MyOption", ContainerKind::Option, 1..=1)
+///     .register("ArcSwap", ContainerKind::Pointer, 1..=1);
+///
+/// assert!(registry.try_predicate_is_option(&field.ty)); // MyOption<String>
+/// let inner = registry.try_unwrap_option(&field.ty);    // Some(String)
+/// let cyclic = registry.try_predicate_references_self(&field.ty, &input.ident); // ArcSwap<Node> -> false
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeRegistry {
+    types: Vec<ContainerType>,
+}
+
+impl TypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The std containers: `Option`, `Vec`, `VecDeque`, `LinkedList`, `HashSet`, `BTreeSet`,
+    /// `HashMap`, `BTreeMap`, `Box`, `Rc` and `Arc`.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register("Option", ContainerKind::Option, 1..=1)
+            .register("Vec", ContainerKind::Vec, 1..=2)
+            .register("VecDeque", ContainerKind::Vec, 1..=2)
+            .register("LinkedList", ContainerKind::Vec, 1..=2)
+            .register("HashSet", ContainerKind::Set, 1..=2)
+            .register("BTreeSet", ContainerKind::Set, 1..=2)
+            .register("HashMap", ContainerKind::Map, 2..=3)
+            .register("BTreeMap", ContainerKind::Map, 2..=3)
+            .register("Box", ContainerKind::Pointer, 1..=2)
+            .register("Rc", ContainerKind::Pointer, 1..=2)
+            .register("Arc", ContainerKind::Pointer, 1..=2);
+
+        registry
+    }
+
+    /// Register a container type, replacing an earlier registration of the same `ident`.
+    pub fn register(
+        &mut self,
+        ident: impl Into<String>,
+        kind: ContainerKind,
+        arity: RangeInclusive<usize>,
+    ) -> &mut Self {
+        let ident = ident.into();
+        self.types.retain(|registered| registered.ident != ident);
+        self.types.push(ContainerType { ident, kind, arity });
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ContainerType> {
+        self.types.iter()
+    }
+
+    /// The registration matching `ty`, by last path segment and arity.
+    pub fn lookup(&self, ty: &Type) -> Option<&ContainerType> {
+        self.types.iter().find(|registered| {
+            try_unwrap_types_any(&registered.ident, ty)
+                .is_some_and(|types| registered.arity.contains(&types.len()))
+        })
+    }
+
+    /// The role of `ty`, if it is a registered container.
+    pub fn kind_of(&self, ty: &Type) -> Option<ContainerKind> {
+        self.lookup(ty).map(|registered| registered.kind)
+    }
+
+    /// Try to predicate that `ty` is a registered container of role `kind`.
+    pub fn is(&self, kind: ContainerKind, ty: &Type) -> bool {
+        self.kind_of(ty) == Some(kind)
+    }
+
+    /// Try unwrap the type arguments of `ty`, when it is a registered container of role `kind`.
+    ///
+    /// - MyOption\<String\> -> `[String]`
+    /// - MyMap\<String, u32\> -> `[String, u32]`
+    pub fn unwrap<'a>(&self, kind: ContainerKind, ty: &'a Type) -> Option<Vec<&'a Type>> {
+        let registered = self
+            .lookup(ty)
+            .filter(|registered| registered.kind == kind)?;
+        try_unwrap_types_any(&registered.ident, ty)
+    }
+
+    /// Try to predicate that `ty` is an `Option<T>` or a registered [`ContainerKind::Option`].
+    pub fn try_predicate_is_option(&self, ty: &Type) -> bool {
+        try_predicate_is_option(ty) || self.is(ContainerKind::Option, ty)
+    }
+
+    /// Try to predicate that `ty` is a `Vec<T>` or a registered [`ContainerKind::Vec`].
+    pub fn try_predicate_is_vec(&self, ty: &Type) -> bool {
+        try_predicate_is_vec(ty) || self.is(ContainerKind::Vec, ty)
+    }
+
+    /// Try to predicate that `ty` is a `HashSet`/`BTreeSet` or a registered [`ContainerKind::Set`].
+    pub fn try_predicate_is_set(&self, ty: &Type) -> bool {
+        self.try_unwrap_set(ty).is_some()
+    }
+
+    /// Try to predicate that `ty` is a `HashMap`/`BTreeMap` or a registered [`ContainerKind::Map`].
+    pub fn try_predicate_is_map(&self, ty: &Type) -> bool {
+        self.try_unwrap_map(ty).is_some()
+    }
+
+    /// Try unwrap the inner type of an `Option<T>` or a registered [`ContainerKind::Option`],
+    /// without panicking.
+    ///
+    /// - MyOption\<String\> -> `String`
+    /// - String -> `None`
+    pub fn try_unwrap_option<'a>(&self, ty: &'a Type) -> Option<&'a Type> {
+        try_unwrap_types_in(BUILTIN_TYPE_OPTION, 1..=1, ty)
+            .or_else(|| self.unwrap(ContainerKind::Option, ty))
+            .and_then(|types| types.first().copied())
+    }
+
+    /// Try unwrap the element type of a `Vec<T>` or a registered [`ContainerKind::Vec`],
+    /// without panicking.
+    ///
+    /// - SmallVec\<u8\> -> `u8`
+    pub fn try_unwrap_vec<'a>(&self, ty: &'a Type) -> Option<&'a Type> {
+        try_unwrap_types_in(BUILTIN_TYPE_VEC, 1..=2, ty)
+            .or_else(|| self.unwrap(ContainerKind::Vec, ty))
+            .and_then(|types| types.first().copied())
+    }
+
+    /// Try unwrap the element type of a set, see [`crate::try_unwrap_set`], or of a registered
+    /// [`ContainerKind::Set`].
+    pub fn try_unwrap_set<'a>(&self, ty: &'a Type) -> Option<&'a Type> {
+        try_unwrap_set(ty).or_else(|| {
+            self.unwrap(ContainerKind::Set, ty)
+                .and_then(|types| types.first().copied())
+        })
+    }
+
+    /// Try unwrap the key and value types of a map, see [`crate::try_unwrap_map`], or of a
+    /// registered [`ContainerKind::Map`].
+    pub fn try_unwrap_map<'a>(&self, ty: &'a Type) -> Option<(&'a Type, &'a Type)> {
+        try_unwrap_map(ty).or_else(|| match self.unwrap(ContainerKind::Map, ty)?.as_slice() {
+            [key, value] | [key, value, _] => Some((*key, *value)),
+            _ => None,
+        })
+    }
+
+    /// Like [`crate::try_predicate_references_self`], treating the registered
+    /// [`ContainerKind::Pointer`], `Vec`, `Set` and `Map` types as indirections too.
+    ///
+    /// - ArcSwap\<Node\>, `Node` -> `false` with `ArcSwap` registered as a pointer
+    pub fn try_predicate_references_self(&self, field_ty: &Type, struct_ident: &Ident) -> bool {
+        try_predicate_references_self_in(self, field_ty, struct_ident)
+    }
+}
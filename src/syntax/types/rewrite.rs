@@ -0,0 +1,163 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/types/rewrite
+
+// ----------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{ExprPath, Ident, Path, PathArguments, QSelf, Type, TypePath};
+
+// ----------------------------------------------------------------
+
+/// Rewrite every `Self` of a [`syn::Type`] into `with`, e.g. of a method moved out of its impl block.
+///
+/// Every path starting with `Self` is rewritten, in generic arguments, qualified
+/// paths and array lengths too:
+///
+/// - Self -> `Config<T>`
+/// - Option\<Box\<Self\>\> -> `Option<Box<Config<T>>>`
+/// - \<Self as Iterator\>::Item -> `<Config<T> as Iterator>::Item`
+/// - [u8; Self::LEN] -> `[u8; Config::<T>::LEN]`
+///
+/// `Self::Item` becomes `Config<T>::Item`, write `<Self as Trait>::Item` for an associated
+/// type of a trait impl.
+///
+/// # Examples
+///
+///```ignore
+/// let self_ty: Path = parse_quote!(Config<T>);
+/// let ty = replace_self(&method_arg.ty, &self_ty);
+/// ```
+/// @since 0.4.0
+pub fn replace_self(ty: &Type, with: &Path) -> Type {
+    let mut ty = ty.clone();
+    SelfReplacer { with }.visit_type_mut(&mut ty);
+
+    ty
+}
+
+struct SelfReplacer<'a> {
+    with: &'a Path,
+}
+
+impl VisitMut for SelfReplacer<'_> {
+    fn visit_path_mut(&mut self, path: &mut Path) {
+        let starts_with_self = path.leading_colon.is_none()
+            && path
+                .segments
+                .first()
+                .is_some_and(|segment| segment.ident == "Self");
+        if starts_with_self {
+            let rest = std::mem::take(&mut path.segments);
+            path.leading_colon = self.with.leading_colon;
+            path.segments.extend(self.with.segments.iter().cloned());
+            path.segments.extend(rest.into_iter().skip(1));
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_expr_path_mut(&mut self, expr: &mut ExprPath) {
+        visit_mut::visit_expr_path_mut(self, expr);
+        // generic arguments of an expression path need the turbofish, `Config::<T>::LEN`
+        for segment in expr.path.segments.iter_mut() {
+            if let PathArguments::AngleBracketed(bracketed) = &mut segment.arguments {
+                bracketed.colon2_token.get_or_insert_with(Default::default);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------- type.params
+
+/// Substitute the type parameters of a [`syn::Type`] with concrete types, e.g. to monomorphize a field type.
+///
+/// Every bare path naming a parameter of `substitutions` is replaced, at any depth, and
+/// an associated type of a parameter becomes a qualified path:
+///
+/// - Vec\<T\>, {T: u8} -> `Vec<u8>`
+/// - HashMap\<K, Option\<V\>\>, {K: String, V: Id} -> `HashMap<String, Option<Id>>`
+/// - T::Output, {T: Adder} -> `<Adder>::Output`
+/// - \<T as Iterator\>::Item, {T: Lines} -> `<Lines as Iterator>::Item`
+///
+/// Qualified paths (`my::T`) and types without a substitution are kept.
+///
+/// # Examples
+///
+///```ignore
+/// let substitutions: HashMap<Ident, Type> = HashMap::from([(format_ident!("T"), parse_quote!(u8))]);
+/// let ty = substitute_type_params(&field.ty, &substitutions); // Option<Vec<T>> -> Option<Vec<u8>>
+/// ```
+/// @since 0.4.0
+pub fn substitute_type_params(ty: &Type, substitutions: &HashMap<Ident, Type>) -> Type {
+    let mut ty = ty.clone();
+    TypeParamSubstitutor { substitutions }.visit_type_mut(&mut ty);
+
+    ty
+}
+
+struct TypeParamSubstitutor<'a> {
+    substitutions: &'a HashMap<Ident, Type>,
+}
+
+impl TypeParamSubstitutor<'_> {
+    /// The substitution of the leading parameter of a path, `T` of `T` and `T::Output`.
+    fn substitution_of(&self, tp: &TypePath) -> Option<&Type> {
+        if tp.qself.is_some() || tp.path.leading_colon.is_some() {
+            return None;
+        }
+        let first = tp.path.segments.first()?;
+        match first.arguments {
+            PathArguments::None => self.substitutions.get(&first.ident),
+            _ => None,
+        }
+    }
+}
+
+impl VisitMut for TypeParamSubstitutor<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(tp) = ty {
+            if let Some(substitution) = self.substitution_of(tp).cloned() {
+                if tp.path.segments.len() == 1 {
+                    *ty = substitution;
+                } else {
+                    // T::Output -> <Adder>::Output
+                    let rest: Punctuated<_, _> = std::mem::take(&mut tp.path.segments)
+                        .into_iter()
+                        .skip(1)
+                        .collect();
+                    tp.path.leading_colon = Some(Default::default());
+                    tp.path.segments = rest;
+                    tp.qself = Some(QSelf {
+                        lt_token: Default::default(),
+                        ty: Box::new(substitution),
+                        position: 0,
+                        as_token: None,
+                        gt_token: Default::default(),
+                    });
+                    visit_mut::visit_path_mut(self, &mut tp.path);
+                }
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
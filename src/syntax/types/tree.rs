@@ -0,0 +1,147 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// syntax/types/tree
+
+// ----------------------------------------------------------------
+
+use syn::{GenericArgument, Ident, PathArguments, Type};
+
+// ----------------------------------------------------------------
+
+/// A [`syn::Type`] decomposed recursively into containers and leaves.
+///
+/// - Path types with type arguments are containers of their type arguments,
+///   `HashMap<String, Box<T>>` -> `[String, Box<T>]`. Lifetimes, consts and bindings are skipped.
+/// - References, pointers, slices, arrays and tuples are containers of their element types.
+/// - Parenthesized and invisible-grouped types are transparent.
+/// - Everything else is a leaf: bare paths (`String`, `T`), trait objects, `impl Trait`, `fn` pointers, `!`, `_`.
+///
+/// # Examples
+///
+///```ignore
+/// let tree = TypeTree::new(&field.ty); // Option<Vec<HashMap<String, Box<T>>>>
+/// assert_eq!(tree.depth(), 4);
+/// assert!(tree.contains("Box"));
+/// let map = tree.innermost();          // HashMap<String, Box<T>>
+/// let leaves = tree.leaves();          // [String, T]
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeTree<'a> {
+    pub ty: &'a Type,
+    /// The last path segment of a path type, `Option`, `String`; `None` for other types.
+    pub ident: Option<&'a Ident>,
+    pub children: Vec<TypeTree<'a>>,
+}
+
+impl<'a> TypeTree<'a> {
+    pub fn new(ty: &'a Type) -> Self {
+        let node = |ident, children| TypeTree {
+            ty,
+            ident,
+            children,
+        };
+        match ty {
+            Type::Group(group) => TypeTree::new(&group.elem),
+            Type::Paren(paren) => TypeTree::new(&paren.elem),
+            Type::Path(tp) => match tp.path.segments.last() {
+                Some(segment) => {
+                    let children = match &segment.arguments {
+                        PathArguments::AngleBracketed(bracketed) => bracketed
+                            .args
+                            .iter()
+                            .filter_map(|arg| match arg {
+                                GenericArgument::Type(ty) => Some(TypeTree::new(ty)),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    };
+                    node(Some(&segment.ident), children)
+                }
+                None => node(None, Vec::new()),
+            },
+            Type::Reference(reference) => node(None, vec![TypeTree::new(&reference.elem)]),
+            Type::Ptr(ptr) => node(None, vec![TypeTree::new(&ptr.elem)]),
+            Type::Slice(slice) => node(None, vec![TypeTree::new(&slice.elem)]),
+            Type::Array(array) => node(None, vec![TypeTree::new(&array.elem)]),
+            Type::Tuple(tuple) => node(None, tuple.elems.iter().map(TypeTree::new).collect()),
+            _ => node(None, Vec::new()),
+        }
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Whether this node is the path type `name`, matched on the last segment.
+    pub fn is(&self, name: &str) -> bool {
+        self.ident.is_some_and(|ident| ident == name)
+    }
+
+    /// The number of container levels above the deepest leaf, `0` for a leaf.
+    ///
+    /// - String -> 0
+    /// - Option\<Vec\<u8\>\> -> 2
+    pub fn depth(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| child.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The node reached by descending while there is exactly one child.
+    ///
+    /// - Option\<Vec\<u8\>\> -> `u8`
+    /// - Option\<HashMap\<K, V\>\> -> `HashMap<K, V>`
+    pub fn innermost(&self) -> &TypeTree<'a> {
+        let mut node = self;
+        while let [child] = node.children.as_slice() {
+            node = child;
+        }
+
+        node
+    }
+
+    /// The leaves, left to right.
+    pub fn leaves(&self) -> Vec<&TypeTree<'a>> {
+        self.iter().filter(|node| node.is_leaf()).collect()
+    }
+
+    /// Whether this node or any node below it is the path type `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    /// The first node, pre-order, that is the path type `name`.
+    pub fn find(&self, name: &str) -> Option<&TypeTree<'a>> {
+        self.iter().find(|node| node.is(name))
+    }
+
+    /// Every node, pre-order: a container comes before its children.
+    pub fn iter(&self) -> impl Iterator<Item = &TypeTree<'a>> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+}
@@ -0,0 +1,308 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// testing/arbitrary
+
+// ----------------------------------------------------------------
+
+use std::panic::{self, RefUnwindSafe};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Attribute, DeriveInput, Meta, Type};
+
+// ----------------------------------------------------------------
+
+const KEYS: [&str; 10] = [
+    "each", "default", "skip", "rename", "with", "method", "bound", "r#type", "crate_", "_x",
+];
+const TYPE_NAMES: [&str; 6] = ["u8", "i64", "String", "bool", "Foo", "self::Bar"];
+
+/// Limits of the generated shapes.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct ArbitraryConfig {
+    /// Helper attribute names put on containers and fields, e.g. `["builder"]`.
+    pub helpers: Vec<String>,
+    pub max_depth: usize,
+    pub max_width: usize,
+    pub max_fields: usize,
+}
+
+impl Default for ArbitraryConfig {
+    fn default() -> Self {
+        Self {
+            helpers: vec!["x".to_string()],
+            max_depth: 3,
+            max_width: 4,
+            max_fields: 5,
+        }
+    }
+}
+
+/// A seeded generator of random-but-valid attribute metas, types and struct shapes for
+/// property tests. The same seed always generates the same inputs.
+///
+/// # Examples
+///
+///```ignore
+/// let mut arbitrary = Arbitrary::new(42);
+/// let attr: Attribute = arbitrary.attribute("builder"); // #[builder(each = "a", skip, with(r#type = 1u8))]
+/// let input: DeriveInput = arbitrary.derive_input();
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone)]
+pub struct Arbitrary {
+    state: u64,
+    config: ArbitraryConfig,
+}
+
+impl Arbitrary {
+    pub fn new(seed: u64) -> Self {
+        Self::with_config(seed, ArbitraryConfig::default())
+    }
+
+    pub fn with_config(seed: u64, config: ArbitraryConfig) -> Self {
+        Self {
+            // xorshift gets stuck on zero
+            state: match splitmix64(seed) {
+                0 => 0x9E37_79B9_7F4A_7C15,
+                state => state,
+            },
+            config,
+        }
+    }
+
+    /// A `#[name(...)]` attribute, possibly a bare `#[name]`.
+    pub fn attribute(&mut self, name: &str) -> Attribute {
+        let tokens = self.meta_tokens(name, 0);
+        syn::parse_quote!(#[#tokens])
+    }
+
+    /// A path, name-value or nested list meta named `name`.
+    pub fn meta(&mut self, name: &str) -> Meta {
+        let tokens = self.meta_tokens(name, 0);
+        syn::parse2(tokens).expect("synext: generated an invalid meta")
+    }
+
+    pub fn ty(&mut self) -> Type {
+        let tokens = self.type_tokens(0);
+        syn::parse2(tokens).expect("synext: generated an invalid type")
+    }
+
+    /// A named, tuple or unit struct, sometimes generic, with random helper attributes on the
+    /// container and its fields.
+    pub fn derive_input(&mut self) -> DeriveInput {
+        let ident = format_ident!("Arbitrary{}", self.below(1000));
+        let attrs = self.helper_attributes();
+        let generics = match self.below(3) {
+            0 => quote! { <'a, T: Clone, const N: usize> },
+            1 => quote! { <T> },
+            _ => TokenStream::new(),
+        };
+        let where_clause = match self.below(4) {
+            0 => quote! { where T: Default },
+            _ => TokenStream::new(),
+        };
+        let fields = (0..self.below(self.config.max_fields + 1))
+            .map(|index| {
+                let attrs = self.helper_attributes();
+                let ty = self.type_tokens(0);
+                (format_ident!("field_{}", index), attrs, ty)
+            })
+            .collect::<Vec<_>>();
+
+        let tokens = match self.below(3) {
+            0 => {
+                let fields = fields
+                    .iter()
+                    .map(|(ident, attrs, ty)| quote! { #(#attrs)* pub #ident: #ty });
+                quote! { #(#attrs)* pub struct #ident #generics #where_clause { #(#fields,)* } }
+            }
+            1 if !fields.is_empty() => {
+                let fields = fields
+                    .iter()
+                    .map(|(_, attrs, ty)| quote! { #(#attrs)* #ty });
+                quote! { #(#attrs)* pub struct #ident #generics (#(#fields,)*) #where_clause; }
+            }
+            _ => quote! { #(#attrs)* pub struct #ident; },
+        };
+
+        syn::parse2(tokens).expect("synext: generated an invalid struct")
+    }
+
+    fn helper_attributes(&mut self) -> Vec<TokenStream> {
+        let helpers = self.config.helpers.clone();
+        let mut attrs = Vec::new();
+        for helper in helpers {
+            for _ in 0..self.below(3) {
+                let meta = self.meta_tokens(&helper, 0);
+                attrs.push(quote! { #[#meta] });
+            }
+        }
+        if self.below(4) == 0 {
+            attrs.push(quote! { #[doc = " arbitrary"] });
+        }
+
+        attrs
+    }
+
+    fn meta_tokens(&mut self, name: &str, depth: usize) -> TokenStream {
+        let path: syn::Path = syn::parse_str(name).expect("synext: invalid meta name");
+        match self.below(4) {
+            0 if depth > 0 => quote! { #path },
+            1 if depth > 0 => {
+                let lit = self.lit_tokens();
+                quote! { #path = #lit }
+            }
+            _ if depth >= self.config.max_depth => quote! { #path },
+            _ => {
+                let nested = (0..self.below(self.config.max_width + 1))
+                    .map(|_| match self.below(5) {
+                        0 => self.lit_tokens(),
+                        _ => {
+                            let key = self.key();
+                            self.meta_tokens(key, depth + 1)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                match self.below(2) {
+                    0 => quote! { #path(#(#nested),*) },
+                    _ => quote! { #path(#(#nested,)*) },
+                }
+            }
+        }
+    }
+
+    fn type_tokens(&mut self, depth: usize) -> TokenStream {
+        if depth >= self.config.max_depth {
+            return self.type_name();
+        }
+
+        match self.below(9) {
+            0 => {
+                let inner = self.type_tokens(depth + 1);
+                quote! { Option<#inner> }
+            }
+            1 => {
+                let inner = self.type_tokens(depth + 1);
+                quote! { Vec<#inner> }
+            }
+            2 => {
+                let inner = self.type_tokens(depth + 1);
+                quote! { &'static #inner }
+            }
+            3 => {
+                let (key, value) = (self.type_tokens(depth + 1), self.type_tokens(depth + 1));
+                quote! { ::std::collections::HashMap<#key, #value> }
+            }
+            4 => {
+                let items = (0..self.below(3))
+                    .map(|_| self.type_tokens(depth + 1))
+                    .collect::<Vec<_>>();
+                quote! { (#(#items,)*) }
+            }
+            5 => {
+                let (inner, len) = (self.type_tokens(depth + 1), self.below(8));
+                quote! { [#inner; #len] }
+            }
+            6 => {
+                let inner = self.type_tokens(depth + 1);
+                quote! { Box<#inner> }
+            }
+            _ => self.type_name(),
+        }
+    }
+
+    fn type_name(&mut self) -> TokenStream {
+        let name = TYPE_NAMES[self.below(TYPE_NAMES.len())];
+        syn::parse_str::<Type>(name)
+            .expect("synext: invalid type name")
+            .to_token_stream()
+    }
+
+    fn lit_tokens(&mut self) -> TokenStream {
+        let span = Span::call_site();
+        match self.below(7) {
+            0 => syn::LitStr::new(&format!("value_{}", self.below(100)), span).to_token_stream(),
+            1 => syn::LitStr::new("", span).to_token_stream(),
+            2 => syn::LitInt::new(&self.below(1 << 16).to_string(), span).to_token_stream(),
+            3 => syn::LitInt::new(&format!("{}u8", self.below(256)), span).to_token_stream(),
+            4 => syn::LitBool::new(self.below(2) == 0, span).to_token_stream(),
+            5 => syn::LitFloat::new(&format!("{}.5", self.below(100)), span).to_token_stream(),
+            _ => syn::LitByteStr::new(b"\x7fELF", span).to_token_stream(),
+        }
+    }
+
+    fn key(&mut self) -> &'static str {
+        KEYS[self.below(KEYS.len())]
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) % bound as u64) as usize
+    }
+}
+
+/// Scramble a seed into a xorshift state, so nearby seeds start far apart.
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Run `check` against `cases` generated structs, panicking with the offending input when it
+/// panics, to verify a parser never panics on weird-but-legal input.
+///
+/// # Examples
+///
+///```ignore
+/// #[test]
+/// fn builder_never_panics() {
+///     assert_never_panics(42, 1000, |input| {
+///         let _ = generate_builder(input);
+///     });
+/// }
+/// ```
+/// @since 0.4.0
+#[track_caller]
+pub fn assert_never_panics<F>(seed: u64, cases: usize, check: F)
+where
+    F: Fn(&DeriveInput) + RefUnwindSafe,
+{
+    let mut arbitrary = Arbitrary::new(seed);
+    for case in 0..cases {
+        let input = arbitrary.derive_input();
+        if panic::catch_unwind(|| check(&input)).is_err() {
+            panic!(
+                "synext: case {} of seed {} panicked on:\n{}",
+                case,
+                seed,
+                input.to_token_stream()
+            );
+        }
+    }
+}
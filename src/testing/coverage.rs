@@ -0,0 +1,197 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// testing/coverage
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{Expr, ImplItem, Item, Stmt};
+
+// ----------------------------------------------------------------
+
+/// One `impl` block found in generated code.
+///
+/// Paths and types are rendered without whitespace, e.g. `core::fmt::Debug`, `Foo<T>`.
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplReport {
+    /// `None` for inherent impls.
+    pub trait_path: Option<String>,
+    pub self_ty: String,
+    pub methods: Vec<String>,
+    pub assoc_types: Vec<String>,
+    pub assoc_consts: Vec<String>,
+}
+
+impl ImplReport {
+    /// Whether the impl is for `self_ty`, compared with or without generic arguments.
+    pub fn is_for(&self, self_ty: &str) -> bool {
+        let query = compact(self_ty);
+        self.self_ty == query || base_path(&self.self_ty) == query
+    }
+
+    /// Whether the impl is of `trait_path`, compared on the full path or its last segment
+    /// (`"Debug"` matches `core::fmt::Debug`).
+    pub fn is_of(&self, trait_path: &str) -> bool {
+        let query = compact(trait_path);
+        match self.trait_path {
+            Some(ref path) => {
+                let base = base_path(path);
+                *path == query || base == query || base.rsplit("::").next() == Some(query.as_str())
+            }
+            None => false,
+        }
+    }
+
+    pub fn has_method(&self, name: &str) -> bool {
+        self.methods.iter().any(|method| method == name)
+    }
+}
+
+/// The structure of a generated token stream: impls (trait, self type, methods) and top-level items,
+/// letting macro tests assert "generates `Deref` and 3 setters" without matching entire outputs.
+///
+/// Items nested in modules and in `const _: () = { ... };` blocks are included.
+///
+/// # Examples
+///
+///```ignore
+/// let report = CoverageReport::from_tokens(&generated)?;
+/// assert!(report.has_impl("Deref", "Hello"));
+/// assert_eq!(report.inherent_methods("HelloBuilder").iter().filter(|m| m.starts_with("set_")).count(), 3);
+/// ```
+/// @since 0.4.0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub impls: Vec<ImplReport>,
+    pub structs: Vec<String>,
+    pub enums: Vec<String>,
+    pub fns: Vec<String>,
+}
+
+impl CoverageReport {
+    pub fn from_tokens(tokens: &TokenStream) -> syn::Result<Self> {
+        let file: syn::File = syn::parse2(tokens.clone())?;
+        let mut report = CoverageReport::default();
+        for item in &file.items {
+            report.visit_item(item);
+        }
+
+        Ok(report)
+    }
+
+    pub fn has_impl(&self, trait_path: &str, self_ty: &str) -> bool {
+        self.impls
+            .iter()
+            .any(|report| report.is_of(trait_path) && report.is_for(self_ty))
+    }
+
+    pub fn impls_of(&self, trait_path: &str) -> Vec<&ImplReport> {
+        self.impls
+            .iter()
+            .filter(|report| report.is_of(trait_path))
+            .collect()
+    }
+
+    pub fn impls_for(&self, self_ty: &str) -> Vec<&ImplReport> {
+        self.impls
+            .iter()
+            .filter(|report| report.is_for(self_ty))
+            .collect()
+    }
+
+    /// The methods of every inherent impl of `self_ty`, in declaration order.
+    pub fn inherent_methods(&self, self_ty: &str) -> Vec<&str> {
+        self.impls_for(self_ty)
+            .into_iter()
+            .filter(|report| report.trait_path.is_none())
+            .flat_map(|report| report.methods.iter().map(String::as_str))
+            .collect()
+    }
+
+    fn visit_item(&mut self, item: &Item) {
+        match item {
+            Item::Impl(item) => {
+                let mut report = ImplReport {
+                    trait_path: item.trait_.as_ref().map(|(_, path, _)| render(path)),
+                    self_ty: render(&item.self_ty),
+                    methods: Vec::new(),
+                    assoc_types: Vec::new(),
+                    assoc_consts: Vec::new(),
+                };
+                for impl_item in &item.items {
+                    match impl_item {
+                        ImplItem::Method(method) => {
+                            report.methods.push(method.sig.ident.to_string())
+                        }
+                        ImplItem::Type(ty) => report.assoc_types.push(ty.ident.to_string()),
+                        ImplItem::Const(constant) => {
+                            report.assoc_consts.push(constant.ident.to_string())
+                        }
+                        _ => {}
+                    }
+                }
+                self.impls.push(report);
+            }
+            Item::Struct(item) => self.structs.push(item.ident.to_string()),
+            Item::Enum(item) => self.enums.push(item.ident.to_string()),
+            Item::Fn(item) => self.fns.push(item.sig.ident.to_string()),
+            Item::Mod(item) => {
+                if let Some((_, ref items)) = item.content {
+                    for item in items {
+                        self.visit_item(item);
+                    }
+                }
+            }
+            Item::Const(item) => self.visit_expr(&item.expr),
+            _ => {}
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Block(block) = expr {
+            for stmt in &block.block.stmts {
+                if let Stmt::Item(item) = stmt {
+                    self.visit_item(item);
+                }
+            }
+        }
+    }
+}
+
+/// Parse generated tokens into a [`CoverageReport`], see [`CoverageReport::from_tokens`].
+///
+/// @since 0.4.0
+pub fn coverage(tokens: &TokenStream) -> syn::Result<CoverageReport> {
+    CoverageReport::from_tokens(tokens)
+}
+
+fn render<T: ToTokens>(node: &T) -> String {
+    compact(&node.to_token_stream().to_string())
+}
+
+fn compact(text: &str) -> String {
+    text.chars().filter(|ch| !ch.is_whitespace()).collect()
+}
+
+fn base_path(text: &str) -> &str {
+    text.split('<').next().unwrap_or(text)
+}
@@ -0,0 +1,158 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// testing/render
+
+// ----------------------------------------------------------------
+
+use proc_macro2::Span;
+
+// ----------------------------------------------------------------
+
+/// Where a diagnostic points in its source, lines 1-based and columns 0-based (in chars),
+/// as reported by [`proc_macro2::LineColumn`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosticLocation {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// One message of a (possibly combined) [`syn::Error`].
+///
+/// @since 0.4.0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// `None` when span locations are unavailable, see [`render_diagnostics`].
+    pub location: Option<DiagnosticLocation>,
+}
+
+impl Diagnostic {
+    /// Split a combined [`syn::Error`] into its messages, in the order they were combined.
+    pub fn from_error(error: &syn::Error) -> Vec<Diagnostic> {
+        error
+            .clone()
+            .into_iter()
+            .map(|error| Diagnostic {
+                message: error.to_string(),
+                location: span_location(error.span()),
+            })
+            .collect()
+    }
+}
+
+/// Render collected errors the way rustc does, with `file:line:column` and a code frame
+/// underlining the span, so failing macro tests show readable output.
+///
+/// Locations need the `span-locations` feature and spans that come from parsing `source`
+/// (e.g. `syn::parse_str`), otherwise only the messages are rendered.
+///
+/// # Examples
+///
+///```ignore
+/// let input: DeriveInput = syn::parse_str(source)?;
+/// let error = try_parse_named_fields_result(&input).unwrap_err();
+/// println!("{}", render_diagnostics(&error, "tests/ui/tuple.rs", source));
+///
+/// ->
+/// error: synext: Does not contain named fields! target:`Tuple`
+///  --> tests/ui/tuple.rs:1:13
+///   |
+/// 1 | struct Tuple(u8, u16);
+///   |             ^^^^^^^^^
+/// ```
+/// @since 0.4.0
+pub fn render_diagnostics(error: &syn::Error, file: &str, source: &str) -> String {
+    Diagnostic::from_error(error)
+        .iter()
+        .map(|diagnostic| render_diagnostic(diagnostic, file, source))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render a single [`Diagnostic`], see [`render_diagnostics`].
+///
+/// @since 0.4.0
+pub fn render_diagnostic(diagnostic: &Diagnostic, file: &str, source: &str) -> String {
+    let mut rendered = format!("error: {}\n", diagnostic.message);
+    let location = match diagnostic.location {
+        Some(location) => location,
+        None => return rendered,
+    };
+
+    let gutter = " ".repeat(location.line.to_string().len());
+    rendered.push_str(&format!(
+        "{}--> {}:{}:{}\n",
+        gutter,
+        file,
+        location.line,
+        location.column + 1
+    ));
+
+    // lines are 1-based, a line 0 has no source line to show
+    let line = match location
+        .line
+        .checked_sub(1)
+        .and_then(|index| source.lines().nth(index))
+    {
+        Some(line) => line,
+        None => return rendered,
+    };
+    let width = line.chars().count();
+    let start = location.column.min(width);
+    let end = match location.end_line == location.line {
+        true => location.end_column.min(width),
+        false => width,
+    };
+
+    rendered.push_str(&format!("{} |\n", gutter));
+    rendered.push_str(&format!("{} | {}\n", location.line, line));
+    rendered.push_str(&format!(
+        "{} | {}{}\n",
+        gutter,
+        " ".repeat(start),
+        "^".repeat(end.saturating_sub(start).max(1))
+    ));
+
+    rendered
+}
+
+#[cfg(feature = "span-locations")]
+fn span_location(span: Span) -> Option<DiagnosticLocation> {
+    // call-site spans have no source text, their location would point at the first line
+    let (start, end) = (span.start(), span.end());
+    if start.line == 0 || span.source_text().is_none() {
+        return None;
+    }
+
+    Some(DiagnosticLocation {
+        line: start.line,
+        column: start.column,
+        end_line: end.line,
+        end_column: end.column,
+    })
+}
+
+#[cfg(not(feature = "span-locations"))]
+fn span_location(_span: Span) -> Option<DiagnosticLocation> {
+    None
+}
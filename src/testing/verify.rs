@@ -0,0 +1,82 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![allow(dead_code)]
+
+// testing/verify
+
+// ----------------------------------------------------------------
+
+use proc_macro2::TokenStream;
+
+// ----------------------------------------------------------------
+
+/// Verify that generated code is syntactically valid Rust by parsing it back through syn
+/// as a whole file, before rustc sees it.
+///
+/// @since 0.4.0
+pub fn verify_parses(tokens: &TokenStream) -> syn::Result<syn::File> {
+    syn::parse2(tokens.clone())
+}
+
+/// Like [`verify_parses`], panicking with the syn error and the generated code on failure.
+///
+/// # Examples
+///
+///```ignore
+/// #[test]
+/// fn builder_output_parses() {
+///     let generated = expand_builder(quote! { struct Hello { name: String } });
+///     let file = assert_parses(&generated);
+///     assert_eq!(file.items.len(), 2);
+/// }
+/// ```
+/// @since 0.4.0
+#[track_caller]
+pub fn assert_parses(tokens: &TokenStream) -> syn::File {
+    match verify_parses(tokens) {
+        Ok(file) => file,
+        Err(error) => panic!("{}", render_parse_failure(tokens, &error)),
+    }
+}
+
+/// The debug assertion mode of [`assert_parses`]: verifies `tokens` only when debug assertions
+/// are enabled and hands them back unchanged, so a macro can wrap its output unconditionally.
+///
+///```ignore
+/// #[proc_macro_derive(Builder)]
+/// pub fn builder_derive(input: TokenStream) -> TokenStream {
+///     debug_assert_parses(expand(input)).into()
+/// }
+/// ```
+/// @since 0.4.0
+#[track_caller]
+pub fn debug_assert_parses(tokens: TokenStream) -> TokenStream {
+    if cfg!(debug_assertions) {
+        if let Err(error) = verify_parses(&tokens) {
+            panic!("{}", render_parse_failure(&tokens, &error));
+        }
+    }
+
+    tokens
+}
+
+fn render_parse_failure(tokens: &TokenStream, error: &syn::Error) -> String {
+    format!(
+        "synext: generated code does not parse: {}\n\n--- generated ---\n{}\n-----------------",
+        error, tokens
+    )
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright © 2024 the original author or authors.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// v2
+
+// ----------------------------------------------------------------
+
+//! The `0.4` API: `syn::Result` based helpers that report errors with spans
+//! instead of panicking, plus the non-panicking `0.3` predicates and the
+//! [`crate::expand`] entry points.
+//!
+//! Items are listed one by one rather than glob-imported, so the panicking `0.3`
+//! helpers (`try_derive_input`, `try_match_fields`, `try_unwrap_option`, ...) stay
+//! out of this module. New helpers are added to the list as they land, so a macro
+//! crate can switch one import at a time.
+
+#[doc(inline)]
+pub use crate::diag::{
+    emit_diagnostic, emit_spanned_diagnostic, is_diagnostic_backend_enabled, try_map_fields,
+    ErrorAccumulator, ErrorCtx, FieldErrorMode, FieldResults, Severity,
+};
+#[doc(inline)]
+pub use crate::error::{DataKind, FieldsKind, SpanRange, SynextError};
+#[doc(inline)]
+pub use crate::expand::{
+    expand_attribute, expand_attribute2, expand_batch, expand_derive, expand_derive2,
+    expansion_observer, set_expansion_observer, ExpansionContext, ExpansionObserver,
+};
+#[doc(inline)]
+pub use crate::ext::{DeriveInputExt, FieldExt, IdentExt, PathExt, TypeExt};
+#[doc(inline)]
+pub use crate::model::{FieldModel, HelperAttributes, StructModel};
+#[doc(inline)]
+pub use crate::syntax::analysis::parser::{
+    ensure_expr_allowed, try_analyze_auto_traits, try_analyze_type_auto_traits,
+    try_analyze_unsized, try_collect_all_fields, try_collect_field_defaults,
+    try_collect_from_conversions, try_extract_error_markers, try_extract_field_dependencies,
+    try_extract_maybe_unsized_params, try_find_error_source_field, try_order_field_defaults,
+    try_parse_conversion_plan, try_parse_transition_table, try_predicate_is_anyhow_error,
+    try_predicate_is_boxed_error, try_predicate_is_dyn_error, try_predicate_is_maybe_unsized_type,
+    AutoTraitReport, AutoTraits, ConversionDirection, ConversionPlan, ConversionTarget,
+    ErrorMarkers, ExprContext, FieldDefault, FieldPair, FromConversion, Transition,
+    TransitionTable, UnsizedReport,
+};
+#[cfg(feature = "async")]
+#[doc(inline)]
+pub use crate::syntax::analysis::parser::{
+    try_classify_async_type, try_predicate_is_async_type, AsyncType, AsyncTypeKind,
+    ASYNC_RUNTIME_CRATES,
+};
+#[doc(inline)]
+pub use crate::syntax::attr::inherit::{
+    decode_inherited_defaults, encode_inherited_defaults, forward_to_inherited_defaults,
+    inherited_defaults_const_ident, inherited_defaults_marker, merge_inherited_defaults,
+    try_split_inherited_defaults,
+};
+#[doc(inline)]
+pub use crate::syntax::attr::literal::{
+    ensure_byte_len, lit_float_as, lit_int_as, lit_int_with_unit, lit_source_text, lit_to_tokens,
+    lit_with_span, try_extract_attribute_bytes, try_extract_attribute_lit, try_lit_to_bytes,
+    ByteLen, FromLitFloat,
+};
+#[doc(inline)]
+pub use crate::syntax::attr::parser::{
+    did_you_mean, ensure_no_unconsumed_helper_attrs, ensure_unique_attr_values, find_meta,
+    find_metas, mark_attr_consumed, suggest_closest, try_extract_attribute_args,
+    try_extract_attribute_args_spanned, try_extract_attribute_first_args,
+    try_extract_attribute_first_args_spanned, try_extract_attribute_path_value,
+    try_extract_attribute_path_value_spanned, try_find_marker_attribute,
+    try_predicate_has_marker_attribute, Attributed, ConsumedAttrs, Spanned,
+};
+#[doc(inline)]
+pub use crate::syntax::attr::resolve::{ResolvedValue, ValueResolver, ValueSource};
+#[doc(inline)]
+pub use crate::syntax::attr::schema::{
+    generate_helper_constants, AttrPosition, AttributeKey, AttributeSchema, AttributeValue,
+    AttributeValues, KeyCheck, KeyRule, ValueKind,
+};
+#[doc(inline)]
+pub use crate::syntax::attr::template::{render_template, TemplateVars};
+#[doc(inline)]
+pub use crate::syntax::codegen::generator::{
+    attr_to_tokens, attrs_to_tokens, doc_attrs, emit_assert_impl, emit_assert_impl_with,
+    emit_assert_size_le, emit_const_assert, emit_field_bound_checks, emit_field_bound_checks_with,
+    escape_format, escape_str, filter_attrs, format_literal, quote_str, render_impl_header,
+    render_impl_header_with, respan, try_generate_flag_set, try_generate_flag_set_with,
+    try_generate_variant_accessors, try_generate_variant_accessors_with, try_parse_dispatch_table,
+    try_parse_flag_set, try_parse_pin_projection, try_to_snake_case, AttrCopyPolicy, AttrForwarder,
+    DispatchEntry, DispatchTable, FlagSet, FlagVariant, PinField, PinProjection, SpanMode,
+};
+#[doc(inline)]
+pub use crate::syntax::derive::parser::{
+    deny_unknown_field_model_keys, deny_unknown_keys, make_new_compile_error,
+    make_new_spanned_compile_error, summarize, try_apply_item_macro_policy, try_apply_macro_policy,
+    try_check_verbatim_expr, try_check_verbatim_fields, try_check_verbatim_type,
+    try_collect_lifetimes, try_derive_input_or_compile_error, try_derive_input_result,
+    try_elide_lifetimes, try_extract_field_attribute_path_attribute,
+    try_extract_field_attribute_path_attribute_spanned, try_extract_field_model_path_attribute,
+    try_extract_fn_pointer, try_extract_impl_trait, try_extract_inner_types,
+    try_extract_interior_mutable, try_extract_trait_object, try_find_type_macro,
+    try_find_verbatim_expr, try_find_verbatim_type, try_match_fields_result,
+    try_parse_named_fields_result, try_parse_unnamed_fields_result,
+    try_predicate_contains_lifetime, try_predicate_contains_macro, try_predicate_contains_verbatim,
+    try_predicate_is_arc, try_predicate_is_array, try_predicate_is_bool, try_predicate_is_box,
+    try_predicate_is_cell, try_predicate_is_char, try_predicate_is_const_ptr, try_predicate_is_cow,
+    try_predicate_is_cow_str, try_predicate_is_float, try_predicate_is_fn_pointer,
+    try_predicate_is_ident, try_predicate_is_impl_trait, try_predicate_is_integer,
+    try_predicate_is_interior_mutable, try_predicate_is_map, try_predicate_is_mut_ptr,
+    try_predicate_is_mut_reference, try_predicate_is_mutex, try_predicate_is_not_ident,
+    try_predicate_is_not_option, try_predicate_is_not_option_and_vec, try_predicate_is_not_vec,
+    try_predicate_is_numeric, try_predicate_is_option, try_predicate_is_option_with,
+    try_predicate_is_phantom_data, try_predicate_is_pin, try_predicate_is_rc,
+    try_predicate_is_ref_cell, try_predicate_is_reference, try_predicate_is_result,
+    try_predicate_is_rw_lock, try_predicate_is_scalar, try_predicate_is_set,
+    try_predicate_is_signed_integer, try_predicate_is_slice, try_predicate_is_str_ref,
+    try_predicate_is_string, try_predicate_is_string_like, try_predicate_is_trait_object,
+    try_predicate_is_type, try_predicate_is_unsigned_integer, try_predicate_is_vec,
+    try_predicate_is_vec_with, try_predicate_path_segments_is_empty,
+    try_predicate_path_segments_is_not_empty, try_predicate_references_self, try_unwrap_arc,
+    try_unwrap_array, try_unwrap_box, try_unwrap_cell, try_unwrap_chain, try_unwrap_cow,
+    try_unwrap_interior_mutable, try_unwrap_map, try_unwrap_mutex, try_unwrap_option_with,
+    try_unwrap_payload, try_unwrap_phantom_data, try_unwrap_pin, try_unwrap_ptr, try_unwrap_rc,
+    try_unwrap_ref_cell, try_unwrap_reference, try_unwrap_result, try_unwrap_rw_lock,
+    try_unwrap_set, try_unwrap_single_type, try_unwrap_slice, try_unwrap_types_any,
+    try_unwrap_types_in, try_unwrap_types_with, try_unwrap_vec_with, CowType, FnPointer,
+    InteriorMutable, MacroPolicy, RefInfo, TraitBounds, VerbatimPolicy, BUILTIN_TYPE_ARC,
+    BUILTIN_TYPE_ATOMICS, BUILTIN_TYPE_BOX, BUILTIN_TYPE_BTREE_MAP, BUILTIN_TYPE_BTREE_SET,
+    BUILTIN_TYPE_CELL, BUILTIN_TYPE_COW, BUILTIN_TYPE_HASH_MAP, BUILTIN_TYPE_HASH_SET,
+    BUILTIN_TYPE_MUTEX, BUILTIN_TYPE_OPTION, BUILTIN_TYPE_PHANTOM_DATA, BUILTIN_TYPE_PIN,
+    BUILTIN_TYPE_RC, BUILTIN_TYPE_REF_CELL, BUILTIN_TYPE_RESULT, BUILTIN_TYPE_RW_LOCK,
+    BUILTIN_TYPE_STRING, BUILTIN_TYPE_VEC,
+};
+#[doc(inline)]
+pub use crate::syntax::generics::parser::{
+    collect_type_params_in_type, generic_params, normalize_where_clause,
+    normalize_where_clause_with, try_extract_generic_defaults, try_predicate_has_generic_defaults,
+    try_strip_generic_defaults, OutputOrder, ParamKind,
+};
+#[doc(inline)]
+pub use crate::syntax::peek::parser::{
+    peek_attributes, peek_ident, peek_item, ItemHeader, ITEM_KEYWORDS,
+};
+#[doc(inline)]
+pub use crate::syntax::types::compare::{type_to_canonical_string, types_equal};
+#[doc(inline)]
+pub use crate::syntax::types::registry::{ContainerKind, ContainerType, TypeRegistry};
+#[doc(inline)]
+pub use crate::syntax::types::rewrite::{replace_self, substitute_type_params};
+#[doc(inline)]
+pub use crate::syntax::types::tree::TypeTree;
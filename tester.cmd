@@ -11,8 +11,8 @@ endlocal
 exit /b
 
 :cargo_test
-echo $ cargo test --verbose -- --show-output
-call cargo test --verbose -- --show-output
+echo $ cargo test --workspace --verbose -- --show-output
+call cargo test --workspace --verbose -- --show-output
 goto :eof
 
 :cargo_deny
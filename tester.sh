@@ -26,8 +26,8 @@ set -euo pipefail
 IFS=$'\n\t'
 
 cargo_test() {
-    echo "$ cargo test --verbose -- --show-output"
-    cargo test --verbose -- --show-output
+    echo "$ cargo test --workspace --verbose -- --show-output"
+    cargo test --workspace --verbose -- --show-output
 }
 
 cargo_deny() {